@@ -55,6 +55,49 @@ impl DatabaseTree {
         new_self
     }
 
+    /// builds a tree of `list` in which the databases and schemas expanded in this one
+    /// are expanded, and the same item is selected, where they still exist
+    pub fn rebuild(&self, list: &[crate::Database]) -> Result<Self> {
+        let mut new_self = Self::new(list, &BTreeSet::new())?;
+        let expanded = self
+            .items
+            .tree_items
+            .iter()
+            .filter(|item| {
+                let kind = item.kind();
+                (kind.is_database() && !kind.is_database_collapsed())
+                    || (kind.is_schema() && !kind.is_schema_collapsed())
+            })
+            .map(|item| item_key(item.kind()))
+            .collect::<Vec<_>>();
+
+        // schemas first, so that expanding their databases shows their tables
+        for item in &mut new_self.items.tree_items {
+            if item.kind().is_schema() && expanded.contains(&item_key(item.kind())) {
+                item.expand_schema();
+            }
+        }
+        for i in 0..new_self.items.len() {
+            let kind = new_self.items.tree_items[i].kind();
+            if kind.is_database() && expanded.contains(&item_key(kind)) {
+                new_self.items.expand(i, false);
+            }
+        }
+
+        if let Some(selected) = self.selected_item().map(|item| item_key(item.kind())) {
+            if let Some(index) = new_self
+                .items
+                .tree_items
+                .iter()
+                .position(|item| item_key(item.kind()) == selected)
+            {
+                new_self.selection = Some(index);
+            }
+        }
+        new_self.visual_selection = new_self.calc_visual_selection();
+        Ok(new_self)
+    }
+
     pub fn collapse_but_root(&mut self) {
         self.items.collapse(0, true);
         self.items.expand(0, false);
@@ -90,6 +133,61 @@ impl DatabaseTree {
         })
     }
 
+    /// selects the table called `table` in `database`, in `schema` if one is given, or
+    /// `database` itself if `table` is `None`, expanding the items above it
+    pub fn select(&mut self, database: &str, schema: Option<&str>, table: Option<&str>) -> bool {
+        let index = self
+            .items
+            .tree_items
+            .iter()
+            .position(|item| match (item.kind(), table) {
+                (DatabaseTreeItemKind::Database { name, .. }, None) => name == database,
+                (
+                    DatabaseTreeItemKind::Table {
+                        database: db,
+                        table: t,
+                    },
+                    Some(table),
+                ) => {
+                    db.name == database
+                        && t.name == table
+                        && (schema.is_none() || t.schema.as_deref() == schema)
+                }
+                _ => false,
+            });
+        let index = match index {
+            Some(index) => index,
+            None => return false,
+        };
+
+        for i in 0..index {
+            let kind = self.items.tree_items[i].kind();
+            let parent = match kind {
+                DatabaseTreeItemKind::Database { name, collapsed } => {
+                    *collapsed && name == database
+                }
+                DatabaseTreeItemKind::Schema {
+                    database: db,
+                    schema: s,
+                    collapsed,
+                } => {
+                    *collapsed
+                        && db.name == database
+                        && self.items.tree_items[index].kind().schema_name().as_deref()
+                            == Some(s.name.as_str())
+                }
+                DatabaseTreeItemKind::Table { .. } => false,
+            };
+            if parent {
+                self.items.expand(i, false);
+            }
+        }
+
+        self.selection = Some(index);
+        self.visual_selection = self.calc_visual_selection();
+        true
+    }
+
     pub fn collapse_recursive(&mut self) {
         if let Some(selection) = self.selection {
             self.items.collapse(selection, true);
@@ -364,6 +462,21 @@ impl DatabaseTree {
     }
 }
 
+/// the database, schema and table names an item is found by across trees
+fn item_key(kind: &DatabaseTreeItemKind) -> (String, Option<String>, Option<String>) {
+    match kind {
+        DatabaseTreeItemKind::Database { name, .. } => (name.clone(), None, None),
+        DatabaseTreeItemKind::Schema {
+            database, schema, ..
+        } => (database.name.clone(), Some(schema.name.clone()), None),
+        DatabaseTreeItemKind::Table { database, table } => (
+            database.name.clone(),
+            table.schema.clone(),
+            Some(table.name.clone()),
+        ),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{Database, DatabaseTree, MoveSelection, Schema, Table};
@@ -935,4 +1048,93 @@ mod test {
         assert!(tree.move_selection(MoveSelection::End));
         assert_eq!(tree.selection, Some(3));
     }
+
+    #[test]
+    fn test_select() {
+        let items = vec![
+            Database::new("a".to_string(), vec![Table::new("b".to_string()).into()]),
+            Database::new(
+                "c".to_string(),
+                vec![Schema {
+                    name: "d".to_string(),
+                    tables: vec![Table::new_with_schema("e".to_string(), "d".to_string())],
+                }
+                .into()],
+            ),
+        ];
+
+        // a
+        //   b
+        // c
+        //   d
+        //     e
+
+        let mut tree = DatabaseTree::new(&items, &BTreeSet::new()).unwrap();
+        assert!(!tree.select("c", Some("x"), Some("e")));
+        assert!(tree.select("c", Some("d"), Some("e")));
+        assert_eq!(tree.selection, Some(4));
+        assert!(tree.is_visible_index(4));
+        assert!(!tree.is_visible_index(1));
+
+        assert!(tree.select("a", None, None));
+        assert_eq!(tree.selection, Some(0));
+    }
+
+    #[test]
+    fn test_rebuild() {
+        let items = vec![
+            Database::new("a".to_string(), vec![Table::new("b".to_string()).into()]),
+            Database::new(
+                "c".to_string(),
+                vec![Schema {
+                    name: "d".to_string(),
+                    tables: vec![Table::new_with_schema("e".to_string(), "d".to_string())],
+                }
+                .into()],
+            ),
+        ];
+
+        // a
+        //   b
+        // c
+        //   d
+        //     e
+
+        let mut tree = DatabaseTree::new(&items, &BTreeSet::new()).unwrap();
+        assert!(tree.select("c", Some("d"), Some("e")));
+
+        let items = vec![
+            Database::new("a".to_string(), vec![Table::new("b".to_string()).into()]),
+            Database::new(
+                "c".to_string(),
+                vec![Schema {
+                    name: "d".to_string(),
+                    tables: vec![
+                        Table::new_with_schema("f".to_string(), "d".to_string()),
+                        Table::new_with_schema("e".to_string(), "d".to_string()),
+                    ],
+                }
+                .into()],
+            ),
+        ];
+
+        // a
+        //   b
+        // c
+        //   d
+        //     f
+        //     e
+
+        let tree = tree.rebuild(&items).unwrap();
+        assert_eq!(tree.selection, Some(5));
+        assert!(tree.is_visible_index(4));
+        assert!(tree.is_visible_index(5));
+        assert!(!tree.is_visible_index(1));
+        assert!(tree.items.tree_items[0].kind().is_database_collapsed());
+        assert!(!tree.items.tree_items[2].kind().is_database_collapsed());
+        assert!(!tree.items.tree_items[3].kind().is_schema_collapsed());
+
+        let tree = tree.rebuild(&items[..1]).unwrap();
+        assert_eq!(tree.selection, Some(0));
+    }
 }
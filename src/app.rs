@@ -1,23 +1,56 @@
+use crate::change_set::ChangeSet;
 use crate::clipboard::copy_to_clipboard;
+use crate::components::cell_editor::{CellTarget, CellUpdate};
+use crate::components::changes::Decision;
+use crate::components::column_form::ColumnForm;
+use crate::components::confirm::{Action, TreeRefresh};
+use crate::components::create_table::NewTable;
+use crate::components::insert_row::FormField;
+use crate::components::record_table::quick_filter_condition;
+use crate::components::references::ReferencingRows;
+use crate::components::tree_menu::TreeAction;
 use crate::components::{
     CommandInfo, Component as _, DrawableComponent as _, EventState, StatefulDrawableComponent,
 };
-use crate::database::{MySqlPool, Pool, PostgresPool, SqlitePool, RECORDS_LIMIT_PER_PAGE};
+use crate::database::{
+    key_columns, qualified_table_name, BindValue, MySqlPool, Pool, PostgresPool, ReadBack,
+    SqlitePool, TableRow, RECORDS_LIMIT_PER_PAGE,
+};
 use crate::event::Key;
 use crate::{
     components::tab::Tab,
     components::{
-        command, ConnectionsComponent, DatabasesComponent, ErrorComponent, HelpComponent,
-        PropertiesComponent, RecordTableComponent, SqlEditorComponent, TabComponent,
+        command, CellEditorComponent, ChangesComponent, ColumnFormComponent, ColumnStatsComponent,
+        ConfirmComponent, ConnectionsComponent, CreateTableComponent, DatabasesComponent,
+        ErrorComponent, HelpComponent, IndexFormComponent, InsertRowComponent, PropertiesComponent,
+        RecordTableComponent, ReferencesComponent, SqlEditorComponent, TabComponent,
+        TableValueComponent, TreeMenuComponent,
     },
     config::Config,
+    sql::{
+        ddl::{self, ColumnChange, IndexDefinition},
+        dml::{self, ColumnValue},
+        quote, Dialect,
+    },
 };
+use database_tree::{Child, Database, Table};
+use std::ops::Range;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     Frame,
 };
 
+/// A table open in the record table, to go back to after following a
+/// foreign key
+struct OpenedTable {
+    database: Database,
+    table: Table,
+    filter: String,
+    row: Option<usize>,
+    column: usize,
+}
+
 pub enum Focus {
     DabataseList,
     Table,
@@ -30,6 +63,19 @@ pub struct App {
     focus: Focus,
     tab: TabComponent,
     help: HelpComponent,
+    table_value: TableValueComponent,
+    column_stats: ColumnStatsComponent,
+    cell_editor: CellEditorComponent,
+    insert_row: InsertRowComponent,
+    column_form: ColumnFormComponent,
+    index_form: IndexFormComponent,
+    create_table: CreateTableComponent,
+    tree_menu: TreeMenuComponent,
+    confirm: ConfirmComponent,
+    changes_review: ChangesComponent,
+    references: ReferencesComponent,
+    changes: ChangeSet,
+    back_stack: Vec<OpenedTable>,
     databases: DatabasesComponent,
     connections: ConnectionsComponent,
     pool: Option<Box<dyn Pool>>,
@@ -40,7 +86,7 @@ pub struct App {
 
 impl App {
     pub fn new(config: Config) -> App {
-        Self {
+        let mut app = Self {
             config: config.clone(),
             connections: ConnectionsComponent::new(config.key_config.clone(), config.conn),
             record_table: RecordTableComponent::new(config.key_config.clone()),
@@ -48,12 +94,33 @@ impl App {
             sql_editor: SqlEditorComponent::new(config.key_config.clone()),
             tab: TabComponent::new(config.key_config.clone()),
             help: HelpComponent::new(config.key_config.clone()),
+            table_value: TableValueComponent::new(config.key_config.clone()),
+            column_stats: ColumnStatsComponent::new(config.key_config.clone()),
+            cell_editor: CellEditorComponent::new(config.key_config.clone()),
+            insert_row: InsertRowComponent::new(config.key_config.clone()),
+            column_form: ColumnFormComponent::new(config.key_config.clone()),
+            index_form: IndexFormComponent::new(config.key_config.clone()),
+            create_table: CreateTableComponent::new(config.key_config.clone()),
+            tree_menu: TreeMenuComponent::new(config.key_config.clone()),
+            confirm: ConfirmComponent::new(config.key_config.clone()),
+            changes_review: ChangesComponent::new(config.key_config.clone()),
+            references: ReferencesComponent::new(config.key_config.clone()),
+            changes: ChangeSet::default(),
+            back_stack: vec![],
             databases: DatabasesComponent::new(config.key_config.clone()),
             error: ErrorComponent::new(config.key_config),
             focus: Focus::ConnectionList,
             pool: None,
             left_main_chunk_percentage: 15,
-        }
+        };
+        app.record_table
+            .table
+            .set_max_column_width(app.config.max_column_width);
+        app.properties
+            .set_max_column_width(app.config.max_column_width);
+        app.sql_editor
+            .set_max_column_width(app.config.max_column_width);
+        app
     }
 
     pub fn draw<B: Backend>(&mut self, f: &mut Frame<'_, B>) -> anyhow::Result<()> {
@@ -102,11 +169,27 @@ impl App {
                     .draw(f, right_chunks[1], matches!(self.focus, Focus::Table))?;
             }
         }
+        self.table_value.draw(f, Rect::default(), false)?;
+        self.column_stats.draw(f, Rect::default(), false)?;
+        self.cell_editor.draw(f, Rect::default(), false)?;
+        self.insert_row.draw(f, Rect::default(), false)?;
+        self.column_form.draw(f, Rect::default(), false)?;
+        self.index_form.draw(f, Rect::default(), false)?;
+        self.create_table.draw(f, Rect::default(), false)?;
+        self.tree_menu.draw(f, Rect::default(), false)?;
+        self.confirm.draw(f, Rect::default(), false)?;
+        self.changes_review.draw(f, Rect::default(), false)?;
+        self.references.draw(f, Rect::default(), false)?;
         self.error.draw(f, Rect::default(), false)?;
         self.help.draw(f, Rect::default(), false)?;
         Ok(())
     }
 
+    /// Saves what should be restored the next time gobang starts.
+    pub fn save_state(&mut self) -> anyhow::Result<()> {
+        self.sql_editor.save_tabs()
+    }
+
     fn update_commands(&mut self) {
         self.help.set_cmds(self.commands());
     }
@@ -128,15 +211,28 @@ impl App {
             )),
         ];
 
+        self.table_value.commands(&mut res);
+        self.column_stats.commands(&mut res);
+        self.cell_editor.commands(&mut res);
+        self.insert_row.commands(&mut res);
+        self.column_form.commands(&mut res);
+        self.index_form.commands(&mut res);
+        self.create_table.commands(&mut res);
+        self.tree_menu.commands(&mut res);
+        self.confirm.commands(&mut res);
+        self.changes_review.commands(&mut res);
+        self.references.commands(&mut res);
         self.databases.commands(&mut res);
         self.record_table.commands(&mut res);
         self.properties.commands(&mut res);
+        self.sql_editor.commands(&mut res);
 
         res
     }
 
     async fn update_databases(&mut self) -> anyhow::Result<()> {
         if let Some(conn) = self.connections.selected_connection() {
+            self.ensure_no_pending_changes()?;
             if let Some(pool) = self.pool.as_ref() {
                 pool.close().await;
             }
@@ -156,36 +252,938 @@ impl App {
             self.databases
                 .update(conn, self.pool.as_ref().unwrap())
                 .await?;
+            self.sql_editor.set_connection(conn.identifier()?)?;
             self.focus = Focus::DabataseList;
             self.record_table.reset();
+            self.back_stack.clear();
             self.tab.reset();
         }
         Ok(())
     }
 
+    /// The table open in the record table, which isn't always the one
+    /// selected in the database list
+    fn opened_table(&self) -> Option<(Database, Table)> {
+        self.record_table.table.table().cloned()
+    }
+
     async fn update_record_table(&mut self) -> anyhow::Result<()> {
-        if let Some((database, table)) = self.databases.tree().selected_table() {
-            let (headers, records) = self
+        self.ensure_no_pending_changes()?;
+        if let Some((database, table)) = self.opened_table() {
+            self.load_records(database, table).await?;
+        }
+        Ok(())
+    }
+
+    async fn load_records(&mut self, database: Database, table: Table) -> anyhow::Result<()> {
+        let (headers, records) = self
+            .pool
+            .as_ref()
+            .unwrap()
+            .get_records(
+                &database,
+                &table,
+                0,
+                if self.record_table.filter.input_str().is_empty() {
+                    None
+                } else {
+                    Some(self.record_table.filter.input_str())
+                },
+            )
+            .await?;
+        self.record_table
+            .update(records, headers, database.clone(), table.clone());
+        self.update_column_layout(&database, &table)?;
+        self.update_column_types(&database, &table).await
+    }
+
+    /// Opens `table` in the record table and its properties, with `filter`.
+    async fn open_table(
+        &mut self,
+        database: Database,
+        table: Table,
+        filter: String,
+    ) -> anyhow::Result<()> {
+        self.ensure_no_pending_changes()?;
+        self.record_table.reset();
+        self.record_table.filter.set_input(filter);
+        self.load_records(database.clone(), table.clone()).await?;
+        self.properties
+            .update(database, table, self.pool.as_ref().unwrap())
+            .await
+    }
+
+    /// Opens the table the selected cell's foreign key references, filtered
+    /// to the row the key, with all its columns, references.
+    async fn follow_foreign_key(&mut self) -> anyhow::Result<()> {
+        let (row, column) = match self.record_table.table.selected_position() {
+            Some(position) => position,
+            None => return Ok(()),
+        };
+        let (database, table) = match self.opened_table() {
+            Some(opened) => opened,
+            None => return Ok(()),
+        };
+        self.ensure_no_pending_changes()?;
+        let header = self.record_table.table.headers[column].clone();
+
+        let pool = self.pool.as_ref().unwrap();
+        let foreign_key = pool
+            .get_foreign_key_references(&database, &table)
+            .await?
+            .into_iter()
+            .find(|foreign_key| {
+                foreign_key
+                    .columns
+                    .iter()
+                    .any(|(column, _)| *column == header)
+            })
+            .ok_or_else(|| anyhow::anyhow!("{} is not a foreign key", header))?;
+        let ref_table = find_table(
+            &database,
+            foreign_key.schema.as_deref().or(table.schema.as_deref()),
+            &foreign_key.table,
+        )
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("{} is not in {}", foreign_key.table, database.name))?;
+        // SQLite leaves out the columns when the key references the primary key.
+        let primary_key = if foreign_key.columns.iter().any(
+            |(_, ref_column)| !matches!(ref_column, Some(ref_column) if !ref_column.is_empty()),
+        ) {
+            key_columns(&pool.get_constraints(&database, &ref_table).await?)
+        } else {
+            vec![]
+        };
+
+        let records = &self.record_table.table;
+        let mut conditions = vec![];
+        for (position, (column, ref_column)) in foreign_key.columns.iter().enumerate() {
+            let ref_column = match ref_column {
+                Some(ref_column) if !ref_column.is_empty() => ref_column,
+                _ => primary_key.get(position).ok_or_else(|| {
+                    anyhow::anyhow!("{} has no primary key to reference", ref_table.name)
+                })?,
+            };
+            let index = records
+                .headers
+                .iter()
+                .position(|h| h == column)
+                .ok_or_else(|| anyhow::anyhow!("{} is not loaded", column))?;
+            let value = &records.rows[row][index];
+            if value == "NULL" {
+                anyhow::bail!("{} is NULL, so it references no row", column);
+            }
+            conditions.push(quick_filter_condition(
+                ref_column,
+                value,
+                records.column_type(index),
+                false,
+                pool.dialect(),
+            ));
+        }
+        let filter = conditions.join(" AND ");
+        self.open_related_table(ref_table, filter).await
+    }
+
+    /// Lists the foreign keys referencing the opened table, with how many
+    /// rows reference the selected row through each.
+    async fn show_references(&mut self) -> anyhow::Result<()> {
+        let row = match self.record_table.table.selected_row.selected() {
+            Some(row) => row,
+            None => return Ok(()),
+        };
+        let (database, table) = match self.opened_table() {
+            Some(opened) => opened,
+            None => return Ok(()),
+        };
+        // The rows are counted by the values in the database.
+        self.ensure_no_pending_changes()?;
+        let pool = self.pool.as_ref().unwrap();
+        let primary_key = key_columns(&pool.get_constraints(&database, &table).await?);
+        let records = &self.record_table.table;
+        let mut references = vec![];
+        'references: for reference in pool.get_references(&database, &table).await? {
+            let ref_table =
+                match find_table(&database, reference.schema.as_deref(), &reference.table) {
+                    Some(ref_table) => ref_table.clone(),
+                    None => continue,
+                };
+            let mut conditions = vec![];
+            for (position, (column, ref_column)) in reference.columns.iter().enumerate() {
+                let ref_column = match ref_column {
+                    Some(ref_column) if !ref_column.is_empty() => Some(ref_column),
+                    _ => primary_key.get(position),
+                };
+                let index = match ref_column
+                    .and_then(|ref_column| records.headers.iter().position(|h| h == ref_column))
+                {
+                    Some(index) => index,
+                    None => continue 'references,
+                };
+                let value = &records.rows[row][index];
+                // A key with NULL in any column references no row.
+                if value == "NULL" {
+                    continue 'references;
+                }
+                conditions.push(quick_filter_condition(
+                    column,
+                    value,
+                    records.column_type(index),
+                    false,
+                    pool.dialect(),
+                ));
+            }
+            let filter = conditions.join(" AND ");
+            references.push(ReferencingRows {
+                rows: pool
+                    .count_records(&database, &ref_table, Some(filter.clone()))
+                    .await?,
+                table: ref_table,
+                columns: reference
+                    .columns
+                    .into_iter()
+                    .map(|(column, _)| column)
+                    .collect(),
+                filter,
+            });
+        }
+        self.references.open(table.name, references);
+        Ok(())
+    }
+
+    /// Opens `table` filtered with `filter`, remembering where we were to go
+    /// back to.
+    async fn open_related_table(&mut self, table: Table, filter: String) -> anyhow::Result<()> {
+        let (database, opened) = match self.opened_table() {
+            Some(opened) => opened,
+            None => return Ok(()),
+        };
+        self.ensure_no_pending_changes()?;
+        let (row, column) = self.record_table.table.selection();
+        self.back_stack.push(OpenedTable {
+            database: database.clone(),
+            table: opened,
+            filter: self.record_table.filter.input_str(),
+            row,
+            column,
+        });
+        self.open_table(database, table, filter).await
+    }
+
+    /// Goes back to the table opened before following a foreign key or the
+    /// rows referencing a row.
+    async fn go_back(&mut self) -> anyhow::Result<()> {
+        self.ensure_no_pending_changes()?;
+        if let Some(opened) = self.back_stack.pop() {
+            self.open_table(opened.database, opened.table, opened.filter)
+                .await?;
+            self.record_table
+                .table
+                .restore_selection(opened.row, opened.column);
+        }
+        Ok(())
+    }
+
+    /// Opens the statistics of the record table's selected column, counting
+    /// only the rows the filter lets through.
+    async fn show_column_stats(&mut self) -> anyhow::Result<()> {
+        let (column, type_name) = match self.record_table.table.selected_field() {
+            Some((column, _, type_name)) => (column.to_string(), type_name.to_string()),
+            None => return Ok(()),
+        };
+        if let Some((database, table)) = self.opened_table() {
+            let filter = self.record_table.filter.input_str();
+            let stats = self
                 .pool
                 .as_ref()
                 .unwrap()
-                .get_records(
+                .get_column_stats(
                     &database,
                     &table,
-                    0,
-                    if self.record_table.filter.input_str().is_empty() {
+                    &column,
+                    quote::is_numeric_type(&type_name),
+                    if filter.is_empty() {
                         None
                     } else {
-                        Some(self.record_table.filter.input_str())
+                        Some(filter)
                     },
                 )
                 .await?;
-            self.record_table
-                .update(records, headers, database.clone(), table.clone());
+            self.column_stats.open(column, type_name, stats);
+        }
+        Ok(())
+    }
+
+    /// The primary or unique key values of `rows` of the record table, by
+    /// which they are found to change them. Tables without such a key can't
+    /// be changed.
+    async fn row_keys(
+        &self,
+        database: &Database,
+        table: &Table,
+        rows: Range<usize>,
+    ) -> anyhow::Result<Vec<Vec<ColumnValue>>> {
+        if rows
+            .clone()
+            .any(|row| self.changes.is_inserted(row) || self.changes.is_deleted(row))
+        {
+            anyhow::bail!("rows inserted or deleted can only be changed once that is committed");
+        }
+        let pool = self.pool.as_ref().unwrap();
+        let key_columns = key_columns(&pool.get_constraints(database, table).await?);
+        if key_columns.is_empty() {
+            anyhow::bail!(
+                "{} has no primary or unique key to find rows by",
+                table.name
+            );
+        }
+
+        let records = &self.record_table.table;
+        let mut indices = vec![];
+        for name in &key_columns {
+            indices.push(
+                records
+                    .headers
+                    .iter()
+                    .position(|header| header == name)
+                    .ok_or_else(|| anyhow::anyhow!("key column {} is not loaded", name))?,
+            );
+        }
+        rows.map(|row| {
+            key_columns
+                .iter()
+                .zip(&indices)
+                .map(|(name, index)| {
+                    // Staged statements run in order, so a key is matched
+                    // as the changes staged before leave it.
+                    let value = &records.rows[row][*index];
+                    if value == "NULL" {
+                        anyhow::bail!("key column {} of a row is NULL", name);
+                    }
+                    Ok(ColumnValue::new(
+                        name,
+                        records.column_type(*index),
+                        Some(value),
+                    ))
+                })
+                .collect()
+        })
+        .collect()
+    }
+
+    /// Opens the editor for the record table's selected cell.
+    async fn edit_cell(&mut self) -> anyhow::Result<()> {
+        let (row, column) = match self.record_table.table.selected_position() {
+            Some(position) => position,
+            None => return Ok(()),
+        };
+        let (database, table) = match self.opened_table() {
+            Some(selected) => selected,
+            None => return Ok(()),
+        };
+        let key = self
+            .row_keys(&database, &table, row..row + 1)
+            .await?
+            .remove(0);
+
+        let dialect = self.pool.as_ref().unwrap().dialect();
+        let records = &self.record_table.table;
+        let value = records.rows[row][column].clone();
+        let target = CellTarget {
+            table: qualified_table_name(&database, &table, dialect),
+            dialect,
+            column: records.headers[column].clone(),
+            type_name: records.column_type(column).to_string(),
+            key,
+            row,
+            column_index: column,
+        };
+        self.cell_editor.open(
+            target,
+            Some(value.as_str()).filter(|value| *value != "NULL"),
+        );
+        Ok(())
+    }
+
+    /// Asks to delete the rows selected in the record table, showing the
+    /// statement that does it, and stages it.
+    async fn delete_rows(&mut self) -> anyhow::Result<()> {
+        let rows = match self.record_table.table.selected_rows() {
+            Some(rows) => rows,
+            None => return Ok(()),
+        };
+        let (database, table) = match self.opened_table() {
+            Some(selected) => selected,
+            None => return Ok(()),
+        };
+        let keys = self.row_keys(&database, &table, rows.clone()).await?;
+        let dialect = self.pool.as_ref().unwrap().dialect();
+        self.confirm.open(
+            Action::DeleteRows { rows },
+            dml::delete(
+                &qualified_table_name(&database, &table, dialect),
+                &keys,
+                dialect,
+            ),
+        );
+        Ok(())
+    }
+
+    /// Goes ahead with a statement the user confirmed.
+    async fn run_confirmed(&mut self, action: Action, statement: String) -> anyhow::Result<()> {
+        match action {
+            Action::DeleteRows { rows } => {
+                self.changes.delete(rows, statement);
+                self.update_marks();
+            }
+            Action::AlterTable { statements, .. } => {
+                self.pool.as_ref().unwrap().execute_ddl(&statements).await?;
+                if let Some((database, table)) = self.opened_table() {
+                    self.open_table(database, table, String::new()).await?;
+                }
+            }
+            Action::ChangeTree {
+                statements,
+                refresh,
+                ..
+            } => {
+                self.pool.as_ref().unwrap().execute_ddl(&statements).await?;
+                self.refresh_tree(refresh).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Opens the form to add a column to the opened table, or to rename or
+    /// change the type of the column selected in its properties.
+    fn open_column_form(&mut self, key: Key) -> anyhow::Result<()> {
+        if self.opened_table().is_none() {
+            return Ok(());
+        }
+        // The records are reloaded once the table is changed.
+        self.ensure_no_pending_changes()?;
+        let key_config = &self.config.key_config;
+        if key == key_config.add_column {
+            self.column_form.open(ColumnForm::Add, "", "");
+        } else if let Some((column, type_name)) = self.properties.selected_column() {
+            let form = if key == key_config.rename_column {
+                ColumnForm::Rename {
+                    column: column.clone(),
+                }
+            } else {
+                ColumnForm::ChangeType {
+                    column: column.clone(),
+                }
+            };
+            self.column_form.open(form, &column, &type_name);
+        }
+        Ok(())
+    }
+
+    /// Asks to make `change` to the opened table, showing the statements
+    /// that do it.
+    async fn confirm_column_change(&mut self, change: ColumnChange) -> anyhow::Result<()> {
+        let (database, table) = match self.opened_table() {
+            Some(opened) => opened,
+            None => return Ok(()),
+        };
+        self.ensure_no_pending_changes()?;
+        let statements = self
+            .pool
+            .as_ref()
+            .unwrap()
+            .alter_column_statements(&database, &table, &change)
+            .await?;
+        self.column_form.close();
+        self.confirm.open(
+            Action::AlterTable {
+                message: change.description(),
+                statements: statements.clone(),
+            },
+            statements.join(";\n"),
+        );
+        Ok(())
+    }
+
+    /// Opens the form to create an index on the opened table.
+    fn open_index_form(&mut self) -> anyhow::Result<()> {
+        let (_, table) = match self.opened_table() {
+            Some(opened) => opened,
+            None => return Ok(()),
+        };
+        self.ensure_no_pending_changes()?;
+        self.index_form.open(
+            table.name,
+            self.properties.column_names(),
+            self.pool.as_ref().unwrap().dialect(),
+        );
+        Ok(())
+    }
+
+    /// Asks to create `index`, or to drop the index selected in the
+    /// properties if there is none, showing the statement that does it.
+    fn confirm_index_change(&mut self, index: Option<IndexDefinition>) -> anyhow::Result<()> {
+        let (database, table) = match self.opened_table() {
+            Some(opened) => opened,
+            None => return Ok(()),
+        };
+        self.ensure_no_pending_changes()?;
+        let dialect = self.pool.as_ref().unwrap().dialect();
+        let table_name = qualified_table_name(&database, &table, dialect);
+        let (message, statement) = match index {
+            Some(index) => (
+                format!("Create index {}?", index.name),
+                ddl::create_index(&table_name, &index, dialect),
+            ),
+            None => match self.properties.selected_index() {
+                Some(name) => (
+                    format!("Drop index {}?", name),
+                    ddl::drop_index(&table_name, table.schema.as_deref(), &name, dialect),
+                ),
+                None => return Ok(()),
+            },
+        };
+        self.confirm.open(
+            Action::AlterTable {
+                message,
+                statements: vec![statement.clone()],
+            },
+            statement,
+        );
+        Ok(())
+    }
+
+    /// Opens the form to create a table in the database or schema selected
+    /// in the tree.
+    fn open_create_table(&mut self) {
+        if let (Some((database, schema)), Some(pool)) =
+            (self.databases.selected_location(), self.pool.as_ref())
+        {
+            self.create_table.open(database, schema, pool.dialect());
+        }
+    }
+
+    /// Asks to create `table`, showing the statement that does it.
+    fn confirm_create_table(&mut self, table: NewTable) {
+        let dialect = self.pool.as_ref().unwrap().dialect();
+        let table_name = qualified_table_name(
+            &table.database,
+            &Table {
+                name: table.name.clone(),
+                create_time: None,
+                update_time: None,
+                engine: None,
+                schema: table.schema.clone(),
+            },
+            dialect,
+        );
+        let statement = ddl::create_table(&table_name, &table.columns, dialect);
+        self.confirm.open(
+            Action::ChangeTree {
+                message: format!("Create table {}?", table.name),
+                statements: vec![statement.clone()],
+                refresh: TreeRefresh::Database {
+                    database: table.database.name,
+                    schema: table.schema,
+                    table: Some(table.name),
+                    changed: None,
+                },
+            },
+            statement,
+        );
+    }
+
+    /// Opens the menu of what can be done to the item selected in the tree.
+    fn open_tree_menu(&mut self) {
+        let (pool, item) = match (self.pool.as_ref(), self.databases.tree().selected_item()) {
+            (Some(pool), Some(item)) => (pool, item.kind().name()),
+            _ => return,
+        };
+        let actions = TreeAction::for_item(
+            self.databases.tree().selected_table().is_some(),
+            matches!(self.databases.selected_location(), Some((_, Some(_)))),
+            pool.dialect(),
+        );
+        self.tree_menu.open(item, actions);
+    }
+
+    /// Asks to do `action` to the item selected in the tree, with `name`
+    /// for the actions asking for one, showing the statements that do it.
+    async fn confirm_tree_action(
+        &mut self,
+        action: TreeAction,
+        name: String,
+    ) -> anyhow::Result<()> {
+        let (database, schema) = match self.databases.selected_location() {
+            Some(location) => location,
+            None => return Ok(()),
+        };
+        let pool = self.pool.as_ref().unwrap();
+        let dialect = pool.dialect();
+        let identifier = |name: &str| quote::identifier(name, dialect);
+        let refresh = |table: Option<String>, changed: Option<Table>| TreeRefresh::Database {
+            database: database.name.clone(),
+            schema: schema.clone(),
+            table,
+            changed,
+        };
+        let selected = self
+            .databases
+            .tree()
+            .selected_table()
+            .map(|(_, table)| table);
+        let (message, statements, refresh) = match (action, selected) {
+            (TreeAction::RenameTable, Some(table)) => (
+                format!("Rename table {} to {}?", table.name, name),
+                vec![ddl::rename_table(
+                    &qualified_table_name(&database, &table, dialect),
+                    &database.name,
+                    &name,
+                    dialect,
+                )],
+                refresh(Some(name), Some(table)),
+            ),
+            (TreeAction::DuplicateTable { with_data }, Some(table)) => (
+                format!(
+                    "Copy {} to {}{}?",
+                    table.name,
+                    name,
+                    if with_data { " with its rows" } else { "" }
+                ),
+                pool.duplicate_table_statements(&database, &table, &name, with_data)
+                    .await?,
+                refresh(Some(name), None),
+            ),
+            (TreeAction::EmptyTable, Some(table)) => (
+                format!("Delete all the rows of {}?", table.name),
+                vec![format!(
+                    "DELETE FROM {}",
+                    qualified_table_name(&database, &table, dialect)
+                )],
+                refresh(Some(table.name.clone()), Some(table)),
+            ),
+            (TreeAction::TruncateTable, Some(table)) => (
+                format!("Truncate {}?", table.name),
+                vec![ddl::truncate_table(
+                    &qualified_table_name(&database, &table, dialect),
+                    dialect,
+                )],
+                refresh(Some(table.name.clone()), Some(table)),
+            ),
+            (TreeAction::DropTable, Some(table)) => (
+                format!("Drop table {}?", table.name),
+                vec![format!(
+                    "DROP TABLE {}",
+                    qualified_table_name(&database, &table, dialect)
+                )],
+                refresh(None, Some(table)),
+            ),
+            (TreeAction::CreateSchema, _) => (
+                format!("Create schema {}?", name),
+                vec![format!("CREATE SCHEMA {}", identifier(&name))],
+                refresh(None, None),
+            ),
+            (TreeAction::DropSchema, _) => {
+                let schema = schema.clone().unwrap_or_default();
+                (
+                    format!("Drop schema {}?", schema),
+                    vec![format!("DROP SCHEMA {}", identifier(&schema))],
+                    refresh(None, None),
+                )
+            }
+            (TreeAction::CreateDatabase, _) => (
+                format!("Create database {}?", name),
+                vec![format!("CREATE DATABASE {}", identifier(&name))],
+                TreeRefresh::CreatedDatabase { database: name },
+            ),
+            (TreeAction::DropDatabase, _) => (
+                format!("Drop database {}?", database.name),
+                vec![format!("DROP DATABASE {}", identifier(&database.name))],
+                TreeRefresh::DroppedDatabase {
+                    database: database.name.clone(),
+                },
+            ),
+            (_, None) => return Ok(()),
+        };
+        if matches!(self.opened_table(), Some((opened, _)) if opened.name == database.name) {
+            // The opened table may be dropped, renamed or emptied.
+            self.ensure_no_pending_changes()?;
+        }
+        self.confirm.open(
+            Action::ChangeTree {
+                message,
+                statements: statements.clone(),
+                refresh,
+            },
+            statements.join(";\n"),
+        );
+        Ok(())
+    }
+
+    /// Shows the database tree as changed, opening the changed table again
+    /// if it is the opened one.
+    async fn refresh_tree(&mut self, refresh: TreeRefresh) -> anyhow::Result<()> {
+        let opened = self.opened_table();
+        match refresh {
+            TreeRefresh::Database {
+                database,
+                schema,
+                table,
+                changed,
+            } => {
+                let pool = self.pool.as_ref().unwrap();
+                let database = self
+                    .databases
+                    .reload_database(
+                        &database,
+                        schema.as_deref(),
+                        table.as_deref(),
+                        pool.as_ref(),
+                    )
+                    .await?;
+                let (changed, (opened_database, opened)) = match (changed, opened) {
+                    (Some(changed), Some(opened)) => (changed, opened),
+                    _ => return Ok(()),
+                };
+                if opened_database.name != database.name
+                    || opened.name != changed.name
+                    || opened.schema != changed.schema
+                {
+                    return Ok(());
+                }
+                match table
+                    .as_deref()
+                    .and_then(|name| find_table(&database, changed.schema.as_deref(), name))
+                {
+                    Some(table) => {
+                        let table = table.clone();
+                        self.open_table(database, table, String::new()).await?;
+                    }
+                    None => self.close_table(),
+                }
+            }
+            TreeRefresh::CreatedDatabase { database } => {
+                self.databases.add_database(&database)?;
+            }
+            TreeRefresh::DroppedDatabase { database } => {
+                self.databases.remove_database(&database)?;
+                if matches!(opened, Some((opened, _)) if opened.name == database) {
+                    self.close_table();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stops showing the opened table, once it is gone.
+    fn close_table(&mut self) {
+        self.record_table.reset();
+        self.back_stack.clear();
+        self.tab.reset();
+    }
+
+    /// Stages a confirmed cell edit, showing the new value in the record
+    /// table.
+    fn stage_update(&mut self, update: CellUpdate) {
+        if let Some(cell) = self
+            .record_table
+            .table
+            .rows
+            .get_mut(update.row)
+            .and_then(|row| row.get_mut(update.column_index))
+        {
+            let original =
+                std::mem::replace(cell, update.value.unwrap_or_else(|| "NULL".to_string()));
+            self.changes
+                .update(update.row, update.column_index, original, update.statement);
+        }
+        self.cell_editor.close();
+        self.update_marks();
+    }
+
+    /// Opens a form of the selected table's columns to insert a row.
+    async fn open_insert_row(&mut self) -> anyhow::Result<()> {
+        let (database, table) = match self.opened_table() {
+            Some(selected) => selected,
+            None => return Ok(()),
+        };
+        let pool = self.pool.as_ref().unwrap();
+        let fields = pool
+            .get_columns(&database, &table)
+            .await?
+            .iter()
+            .filter_map(|column| FormField::from_column(column.as_ref()))
+            .collect();
+        let dialect = pool.dialect();
+        self.insert_row.open(
+            qualified_table_name(&database, &table, dialect),
+            dialect,
+            fields,
+        );
+        Ok(())
+    }
+
+    /// Stages a confirmed insert, showing the new row at the top of the
+    /// record table. Databases that can't return the row once it is
+    /// committed have it looked up by its key.
+    async fn stage_insert(
+        &mut self,
+        statement: String,
+        params: Vec<BindValue>,
+    ) -> anyhow::Result<()> {
+        let pool = self.pool.as_ref().unwrap();
+        let read_back = match pool.dialect() {
+            Dialect::MySql => match self.opened_table() {
+                Some((database, table)) => {
+                    let key_columns = key_columns(&pool.get_constraints(&database, &table).await?);
+                    self.insert_row
+                        .select_inserted(&key_columns)
+                        .map(ReadBack::Selected)
+                }
+                None => None,
+            },
+            Dialect::Postgres | Dialect::Sqlite => Some(ReadBack::Returned),
+        };
+        let records = &mut self.record_table.table;
+        if records.headers.is_empty() {
+            records.headers = self.insert_row.columns();
+        }
+        records.prepend_row(self.insert_row.row(&records.headers));
+        self.changes.insert(statement, params, read_back);
+        self.insert_row.close();
+        self.update_marks();
+        Ok(())
+    }
+
+    /// Runs the pending changes in one transaction and shows them as
+    /// stored, inserted rows as the database read them back. If one of
+    /// those couldn't be read, the records are reloaded.
+    async fn commit_changes(&mut self) -> anyhow::Result<()> {
+        let inserted = self
+            .pool
+            .as_ref()
+            .unwrap()
+            .execute_batch(&self.changes.statements())
+            .await?;
+        let selected = self.record_table.table.selected_row.selected();
+        let records = &mut self.record_table.table;
+        let read_back = inserted
+            .into_iter()
+            .map(|inserted| {
+                inserted.map(|inserted| {
+                    records
+                        .headers
+                        .iter()
+                        .map(|header| {
+                            inserted
+                                .headers
+                                .iter()
+                                .position(|h| h == header)
+                                .map(|i| inserted.row[i].clone())
+                                .unwrap_or_default()
+                        })
+                        .collect()
+                })
+            })
+            .collect();
+        if !self.changes.commit(&mut records.rows, read_back) {
+            self.update_record_table().await?;
+        }
+        self.update_marks();
+        let records = &mut self.record_table.table;
+        if let Some(selected) = selected {
+            if !records.rows.is_empty() {
+                records
+                    .selected_row
+                    .select(Some(selected.min(records.rows.len() - 1)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Undoes the pending changes in the record table.
+    fn discard_changes(&mut self) {
+        let records = &mut self.record_table.table;
+        self.changes.discard(&mut records.rows);
+        if let Some(selected) = records.selected_row.selected() {
+            records.selected_row.select(if records.rows.is_empty() {
+                None
+            } else {
+                Some(selected.min(records.rows.len() - 1))
+            });
+        }
+        self.update_marks();
+    }
+
+    fn update_marks(&mut self) {
+        self.record_table
+            .table
+            .set_marks(self.changes.marks(), self.changes.len());
+    }
+
+    /// Keeps the records with pending changes from being replaced.
+    fn ensure_no_pending_changes(&self) -> anyhow::Result<()> {
+        if !self.changes.is_empty() {
+            anyhow::bail!("commit or discard the pending changes first");
         }
         Ok(())
     }
 
+    /// Restores the columns hidden, moved or frozen in the record table the
+    /// last time this table was open.
+    fn update_column_layout(&mut self, database: &Database, table: &Table) -> anyhow::Result<()> {
+        if let Some(conn) = self.connections.selected_connection() {
+            self.record_table.table.set_layout_key(format!(
+                "{} {}.{}{}",
+                conn.identifier()?,
+                database.name,
+                table
+                    .schema
+                    .as_ref()
+                    .map_or(String::new(), |schema| format!("{}.", schema)),
+                table.name
+            ));
+        }
+        Ok(())
+    }
+
+    /// Looks up the types of the record table's columns, which its record
+    /// view shows.
+    async fn update_column_types(
+        &mut self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<()> {
+        let columns = self
+            .pool
+            .as_ref()
+            .unwrap()
+            .get_columns(database, table)
+            .await?;
+        let field = |column: &dyn TableRow, name: &str| {
+            column
+                .fields()
+                .iter()
+                .position(|field| field == name)
+                .and_then(|i| column.columns().get(i).cloned())
+        };
+        let types = self
+            .record_table
+            .table
+            .headers
+            .iter()
+            .map(|header| {
+                columns
+                    .iter()
+                    .find(|column| field(column.as_ref(), "name").as_ref() == Some(header))
+                    .and_then(|column| field(column.as_ref(), "type"))
+                    .unwrap_or_default()
+            })
+            .collect();
+        self.record_table.table.set_column_types(types);
+        Ok(())
+    }
+
     pub async fn event(&mut self, key: Key) -> anyhow::Result<EventState> {
         self.update_commands();
 
@@ -208,6 +1206,91 @@ impl App {
             return Ok(EventState::Consumed);
         }
 
+        if self.table_value.event(key)?.is_consumed() {
+            return Ok(EventState::Consumed);
+        }
+
+        if self.column_stats.event(key)?.is_consumed() {
+            return Ok(EventState::Consumed);
+        }
+
+        if self.cell_editor.event(key)?.is_consumed() {
+            if let Some(update) = self.cell_editor.take_update() {
+                self.stage_update(update);
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.insert_row.event(key)?.is_consumed() {
+            if let Some((statement, params)) = self.insert_row.take_insert() {
+                self.stage_insert(statement, params).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.column_form.event(key)?.is_consumed() {
+            if let Some(change) = self.column_form.take_change() {
+                if let Err(err) = self.confirm_column_change(change).await {
+                    self.column_form.set_message(err.to_string());
+                }
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.index_form.event(key)?.is_consumed() {
+            if let Some(index) = self.index_form.take_index() {
+                self.confirm_index_change(Some(index))?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.create_table.event(key)?.is_consumed() {
+            if let Some((database, table)) = self.create_table.take_columns_request() {
+                let columns = self
+                    .pool
+                    .as_ref()
+                    .unwrap()
+                    .get_columns(&database, &table)
+                    .await?;
+                self.create_table
+                    .set_referenced_columns(field_values(&columns, "name"));
+            }
+            if let Some(table) = self.create_table.take_table() {
+                self.confirm_create_table(table);
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.tree_menu.event(key)?.is_consumed() {
+            if let Some((action, name)) = self.tree_menu.take_chosen() {
+                self.confirm_tree_action(action, name).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.confirm.event(key)?.is_consumed() {
+            if let Some((action, statement)) = self.confirm.take_confirmed() {
+                self.run_confirmed(action, statement).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.references.event(key)?.is_consumed() {
+            if let Some(rows) = self.references.take_selected() {
+                self.open_related_table(rows.table, rows.filter).await?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        if self.changes_review.event(key)?.is_consumed() {
+            match self.changes_review.take_decision() {
+                Some(Decision::Commit) => self.commit_changes().await?,
+                Some(Decision::Discard) => self.discard_changes(),
+                None => (),
+            }
+            return Ok(EventState::Consumed);
+        }
+
         match self.focus {
             Focus::ConnectionList => {
                 if self.connections.event(key)?.is_consumed() {
@@ -224,32 +1307,104 @@ impl App {
                     return Ok(EventState::Consumed);
                 }
 
+                if key == self.config.key_config.tree_actions && self.databases.tree_focused() {
+                    self.open_tree_menu();
+                    return Ok(EventState::Consumed);
+                }
+
+                if key == self.config.key_config.create_table && self.databases.tree_focused() {
+                    self.open_create_table();
+                    return Ok(EventState::Consumed);
+                }
+
                 if key == self.config.key_config.enter && self.databases.tree_focused() {
                     if let Some((database, table)) = self.databases.tree().selected_table() {
-                        self.record_table.reset();
-                        let (headers, records) = self
-                            .pool
-                            .as_ref()
-                            .unwrap()
-                            .get_records(&database, &table, 0, None)
-                            .await?;
-                        self.record_table
-                            .update(records, headers, database.clone(), table.clone());
-                        self.properties
-                            .update(database.clone(), table.clone(), self.pool.as_ref().unwrap())
-                            .await?;
+                        self.open_table(database, table, String::new()).await?;
+                        self.back_stack.clear();
                         self.focus = Focus::Table;
                     }
                     return Ok(EventState::Consumed);
                 }
             }
             Focus::Table => {
+                if key == self.config.key_config.inspect_cell {
+                    let value = match self.tab.selected_tab {
+                        Tab::Records
+                            if !self.record_table.filter_focused()
+                                && !self.record_table.table.searching() =>
+                        {
+                            self.record_table.table.selected_cells()
+                        }
+                        Tab::Sql => self.sql_editor.selected_cells(),
+                        Tab::Properties => self.properties.selected_cells(),
+                        _ => None,
+                    };
+                    if let Some(value) = value {
+                        self.table_value.open(value);
+                        return Ok(EventState::Consumed);
+                    }
+                }
+
                 match self.tab.selected_tab {
                     Tab::Records => {
                         if self.record_table.event(key)?.is_consumed() {
                             return Ok(EventState::Consumed);
                         };
 
+                        if key == self.config.key_config.quick_filter
+                            || key == self.config.key_config.quick_filter_not
+                        {
+                            let dialect = self.pool.as_ref().unwrap().dialect();
+                            if self.record_table.add_quick_filter(
+                                key == self.config.key_config.quick_filter_not,
+                                dialect,
+                            ) {
+                                self.update_record_table().await?;
+                            }
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.config.key_config.column_stats {
+                            self.show_column_stats().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.config.key_config.edit_cell {
+                            self.edit_cell().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.config.key_config.insert_row {
+                            self.open_insert_row().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.config.key_config.delete_rows {
+                            self.delete_rows().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.config.key_config.review_changes && !self.changes.is_empty()
+                        {
+                            self.changes_review.open(self.changes.statements());
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.config.key_config.follow_foreign_key {
+                            self.follow_foreign_key().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.config.key_config.show_references {
+                            self.show_references().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
+                        if key == self.config.key_config.go_back {
+                            self.go_back().await?;
+                            return Ok(EventState::Consumed);
+                        }
+
                         if key == self.config.key_config.copy {
                             if let Some(text) = self.record_table.table.selected_cells() {
                                 copy_to_clipboard(text.as_str())?
@@ -268,9 +1423,15 @@ impl App {
 
                         if let Some(index) = self.record_table.table.selected_row.selected() {
                             if index.saturating_add(1) % RECORDS_LIMIT_PER_PAGE as usize == 0 {
-                                if let Some((database, table)) =
-                                    self.databases.tree().selected_table()
-                                {
+                                if let Some((database, table)) = self.opened_table() {
+                                    // Rows inserted but not committed aren't on any
+                                    // page, while those deleted still are.
+                                    let loaded = self
+                                        .record_table
+                                        .table
+                                        .rows
+                                        .len()
+                                        .saturating_sub(self.changes.inserted());
                                     let (_, records) = self
                                         .pool
                                         .as_ref()
@@ -278,7 +1439,7 @@ impl App {
                                         .get_records(
                                             &database,
                                             &table,
-                                            index as u16,
+                                            loaded as u16,
                                             if self.record_table.filter.input_str().is_empty() {
                                                 None
                                             } else {
@@ -310,6 +1471,37 @@ impl App {
                         if self.properties.event(key)?.is_consumed() {
                             return Ok(EventState::Consumed);
                         };
+
+                        if self.properties.columns_focused() {
+                            let key_config = &self.config.key_config;
+                            if key == key_config.add_column
+                                || key == key_config.rename_column
+                                || key == key_config.change_column_type
+                            {
+                                self.open_column_form(key)?;
+                                return Ok(EventState::Consumed);
+                            }
+
+                            if key == key_config.drop_column {
+                                if let Some((column, _)) = self.properties.selected_column() {
+                                    self.confirm_column_change(ColumnChange::Drop { column })
+                                        .await?;
+                                }
+                                return Ok(EventState::Consumed);
+                            }
+                        }
+
+                        if self.properties.indexes_focused() {
+                            if key == self.config.key_config.create_index {
+                                self.open_index_form()?;
+                                return Ok(EventState::Consumed);
+                            }
+
+                            if key == self.config.key_config.drop_index {
+                                self.confirm_index_change(None)?;
+                                return Ok(EventState::Consumed);
+                            }
+                        }
                     }
                 };
             }
@@ -376,9 +1568,38 @@ impl App {
     }
 }
 
+/// The values of the field `name` of `rows`
+fn field_values(rows: &[Box<dyn TableRow>], name: &str) -> Vec<String> {
+    rows.iter()
+        .filter_map(|row| {
+            let i = row.fields().iter().position(|field| field == name)?;
+            row.columns().get(i).cloned()
+        })
+        .collect()
+}
+
+/// The table called `name` in `database`, looked for in `schema` first.
+fn find_table<'a>(database: &'a Database, schema: Option<&str>, name: &str) -> Option<&'a Table> {
+    let tables = database
+        .children
+        .iter()
+        .flat_map(|child| match child {
+            Child::Table(table) => std::slice::from_ref(table).iter(),
+            Child::Schema(schema) => schema.tables.iter(),
+        })
+        .filter(|table| table.name == name)
+        .collect::<Vec<&Table>>();
+    tables
+        .iter()
+        .find(|table| table.schema.as_deref() == schema)
+        .or_else(|| tables.first())
+        .copied()
+}
+
 #[cfg(test)]
 mod test {
-    use super::{App, Config, EventState, Key};
+    use super::{find_table, App, Config, EventState, Key};
+    use database_tree::{Child, Database, Schema, Table};
 
     #[test]
     fn test_extend_or_shorten_widget_width() {
@@ -409,4 +1630,49 @@ mod test {
         );
         assert_eq!(app.left_main_chunk_percentage, 15);
     }
+
+    fn table(name: &str, schema: Option<&str>) -> Table {
+        Table {
+            name: name.to_string(),
+            create_time: None,
+            update_time: None,
+            engine: None,
+            schema: schema.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_find_table() {
+        let database = Database::new(
+            "db".to_string(),
+            vec![
+                Child::Schema(Schema {
+                    name: "audit".to_string(),
+                    tables: vec![table("users", Some("audit"))],
+                }),
+                Child::Schema(Schema {
+                    name: "public".to_string(),
+                    tables: vec![
+                        table("users", Some("public")),
+                        table("posts", Some("public")),
+                    ],
+                }),
+            ],
+        );
+        assert_eq!(
+            find_table(&database, Some("public"), "users"),
+            Some(&table("users", Some("public")))
+        );
+        assert_eq!(
+            find_table(&database, Some("audit"), "posts"),
+            Some(&table("posts", Some("public")))
+        );
+        assert_eq!(find_table(&database, None, "comments"), None);
+
+        let database = Database::new("db".to_string(), vec![Child::Table(table("users", None))]);
+        assert_eq!(
+            find_table(&database, None, "users"),
+            Some(&table("users", None))
+        );
+    }
 }
@@ -0,0 +1,295 @@
+use crate::components::table::Mark;
+use crate::database::{BatchStatement, BindValue, ReadBack};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A change made in the record table that hasn't been sent to the database.
+/// Rows are indices into the record table's rows.
+#[derive(Debug, Clone, PartialEq)]
+enum Change {
+    Update {
+        row: usize,
+        column: usize,
+        /// The value the cell had before, restored if the change is discarded
+        original: String,
+        statement: String,
+    },
+    Insert {
+        row: usize,
+        statement: String,
+        params: Vec<BindValue>,
+        read_back: Option<ReadBack>,
+    },
+    Delete {
+        rows: Range<usize>,
+        statement: String,
+    },
+}
+
+/// The changes made in the record table, in order, until they are committed
+/// in one transaction or discarded.
+#[derive(Default)]
+pub struct ChangeSet {
+    changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Adds a cell set to a new value, which is already shown in `rows`.
+    pub fn update(&mut self, row: usize, column: usize, original: String, statement: String) {
+        self.changes.push(Change::Update {
+            row,
+            column,
+            original,
+            statement,
+        });
+    }
+
+    /// Adds a row, shown at the top of the table, so the rows already
+    /// changed move down by one.
+    pub fn insert(
+        &mut self,
+        statement: String,
+        params: Vec<BindValue>,
+        read_back: Option<ReadBack>,
+    ) {
+        self.shift(0, true);
+        self.changes.push(Change::Insert {
+            row: 0,
+            statement,
+            params,
+            read_back,
+        });
+    }
+
+    pub fn delete(&mut self, rows: Range<usize>, statement: String) {
+        self.changes.push(Change::Delete { rows, statement });
+    }
+
+    /// Moves the rows of all changes from `from` on by one.
+    fn shift(&mut self, from: usize, down: bool) {
+        let shift = |row: &mut usize| {
+            if *row >= from {
+                if down {
+                    *row += 1
+                } else {
+                    *row -= 1
+                }
+            }
+        };
+        for change in &mut self.changes {
+            match change {
+                Change::Update { row, .. } | Change::Insert { row, .. } => shift(row),
+                Change::Delete { rows, .. } => {
+                    let (mut start, mut end) = (rows.start, rows.end);
+                    shift(&mut start);
+                    shift(&mut end);
+                    *rows = start..end;
+                }
+            }
+        }
+    }
+
+    /// How many rows were inserted
+    pub fn inserted(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| matches!(change, Change::Insert { .. }))
+            .count()
+    }
+
+    /// Whether `row` was inserted and so isn't in the database yet
+    pub fn is_inserted(&self, row: usize) -> bool {
+        self.changes.iter().any(
+            |change| matches!(change, Change::Insert { row: inserted, .. } if *inserted == row),
+        )
+    }
+
+    pub fn is_deleted(&self, row: usize) -> bool {
+        self.changes
+            .iter()
+            .any(|change| matches!(change, Change::Delete { rows, .. } if rows.contains(&row)))
+    }
+
+    /// The statements to run, in the order the changes were made
+    pub fn statements(&self) -> Vec<BatchStatement> {
+        self.changes
+            .iter()
+            .map(|change| match change {
+                Change::Update { statement, .. } => BatchStatement {
+                    query: statement.clone(),
+                    params: vec![],
+                    rows: 1,
+                    read_back: None,
+                },
+                Change::Insert {
+                    statement,
+                    params,
+                    read_back,
+                    ..
+                } => BatchStatement {
+                    query: statement.clone(),
+                    params: params.clone(),
+                    rows: 1,
+                    read_back: read_back.clone(),
+                },
+                Change::Delete { rows, statement } => BatchStatement {
+                    query: statement.clone(),
+                    params: vec![],
+                    rows: rows.len() as u64,
+                    read_back: None,
+                },
+            })
+            .collect()
+    }
+
+    /// How rows and cells of the record table are highlighted, keyed by row
+    /// and, for changed cells, column
+    pub fn marks(&self) -> HashMap<(usize, Option<usize>), Mark> {
+        let mut marks = HashMap::new();
+        for change in &self.changes {
+            match change {
+                Change::Update { row, column, .. } => {
+                    marks.insert((*row, Some(*column)), Mark::Updated);
+                }
+                Change::Insert { row, .. } => {
+                    marks.insert((*row, None), Mark::Inserted);
+                }
+                Change::Delete { rows, .. } => {
+                    for row in rows.clone() {
+                        marks.insert((row, None), Mark::Deleted);
+                    }
+                }
+            }
+        }
+        marks
+    }
+
+    /// Shows the committed changes in `rows`, the record table's rows:
+    /// inserted rows become the rows read back for them, one per change,
+    /// and deleted rows are removed. Returns whether every inserted row was
+    /// read back.
+    pub fn commit(
+        &mut self,
+        rows: &mut Vec<Vec<String>>,
+        read_back: Vec<Option<Vec<String>>>,
+    ) -> bool {
+        let mut complete = true;
+        let mut deleted = vec![];
+        for (change, read) in self.changes.drain(..).zip(read_back) {
+            match change {
+                Change::Insert { row, .. } => match (rows.get_mut(row), read) {
+                    (Some(row), Some(read)) => *row = read,
+                    _ => complete = false,
+                },
+                Change::Delete { rows, .. } => deleted.push(rows),
+                Change::Update { .. } => (),
+            }
+        }
+        deleted.sort_by_key(|rows| rows.start);
+        for range in deleted.into_iter().rev() {
+            if range.end <= rows.len() {
+                rows.drain(range);
+            }
+        }
+        complete
+    }
+
+    /// Undoes all changes in `rows`, the record table's rows.
+    pub fn discard(&mut self, rows: &mut Vec<Vec<String>>) {
+        while let Some(change) = self.changes.pop() {
+            match change {
+                Change::Update {
+                    row,
+                    column,
+                    original,
+                    ..
+                } => {
+                    if let Some(cell) = rows.get_mut(row).and_then(|row| row.get_mut(column)) {
+                        *cell = original;
+                    }
+                }
+                Change::Insert { row, .. } => {
+                    if row < rows.len() {
+                        rows.remove(row);
+                    }
+                    self.shift(row, false);
+                }
+                Change::Delete { .. } => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ChangeSet, Mark, ReadBack};
+
+    #[test]
+    fn test_discard() {
+        let mut rows = vec![
+            vec!["1".to_string(), "a".to_string()],
+            vec!["2".to_string(), "b".to_string()],
+        ];
+        let mut changes = ChangeSet::default();
+        rows[1][1] = "x".to_string();
+        changes.update(1, 1, "b".to_string(), "UPDATE".to_string());
+        rows.insert(0, vec!["3".to_string(), "c".to_string()]);
+        changes.insert("INSERT".to_string(), vec![], None);
+        changes.delete(1..2, "DELETE".to_string());
+
+        assert!(changes.is_inserted(0));
+        assert_eq!(changes.inserted(), 1);
+        assert!(changes.is_deleted(1));
+        assert!(!changes.is_deleted(2));
+        let marks = changes.marks();
+        assert_eq!(marks.get(&(2, Some(1))), Some(&Mark::Updated));
+        assert_eq!(marks.get(&(0, None)), Some(&Mark::Inserted));
+        assert_eq!(marks.get(&(1, None)), Some(&Mark::Deleted));
+        assert_eq!(
+            changes
+                .statements()
+                .iter()
+                .map(|statement| (statement.query.as_str(), statement.rows))
+                .collect::<Vec<_>>(),
+            vec![("UPDATE", 1), ("INSERT", 1), ("DELETE", 1)]
+        );
+
+        changes.discard(&mut rows);
+        assert!(changes.is_empty());
+        assert_eq!(
+            rows,
+            vec![
+                vec!["1".to_string(), "a".to_string()],
+                vec!["2".to_string(), "b".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commit() {
+        let row = |values: &[&str]| values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let mut rows = vec![row(&["1", "a"]), row(&["2", "b"]), row(&["3", "c"])];
+        let mut changes = ChangeSet::default();
+        changes.delete(1..3, "DELETE".to_string());
+        rows.insert(0, row(&["", "d"]));
+        changes.insert("INSERT".to_string(), vec![], Some(ReadBack::Returned));
+        rows[1][1] = "x".to_string();
+        changes.update(1, 1, "a".to_string(), "UPDATE".to_string());
+
+        assert!(changes.commit(&mut rows, vec![None, Some(row(&["4", "d"])), None]));
+        assert!(changes.is_empty());
+        assert_eq!(rows, vec![row(&["4", "d"]), row(&["1", "x"])]);
+
+        rows.insert(0, row(&["", "e"]));
+        changes.insert("INSERT".to_string(), vec![], None);
+        assert!(!changes.commit(&mut rows, vec![None]));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::config::CliConfig;
+use crate::sql::Dialect;
 use structopt::StructOpt;
 
 /// A cross-platform TUI database management tool written in Rust
@@ -7,6 +8,19 @@ use structopt::StructOpt;
 pub struct Cli {
     #[structopt(flatten)]
     pub config: CliConfig,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+pub enum Command {
+    /// Format SQL read from stdin and write it to stdout
+    Fmt {
+        /// SQL dialect: mysql, postgres or sqlite
+        #[structopt(long, short, default_value = "mysql")]
+        dialect: Dialect,
+    },
 }
 
 pub fn parse() -> Cli {
@@ -1,7 +1,7 @@
 use anyhow::{anyhow, Result};
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
 use std::ffi::OsStr;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::process::{Command, Stdio};
 
 fn execute_copy_command(command: Command, text: &str) -> Result<()> {
@@ -27,6 +27,30 @@ fn execute_copy_command(command: Command, text: &str) -> Result<()> {
     Ok(())
 }
 
+fn execute_paste_command(command: Command) -> Result<String> {
+    let mut command = command;
+
+    let mut process = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("`{:?}`: {}", command, e))?;
+
+    let mut text = String::new();
+    process
+        .stdout
+        .as_mut()
+        .ok_or_else(|| anyhow!("`{:?}`", command))?
+        .read_to_string(&mut text)
+        .map_err(|e| anyhow!("`{:?}`: {}", command, e))?;
+
+    process
+        .wait()
+        .map_err(|e| anyhow!("`{:?}`: {}", command, e))?;
+
+    Ok(text)
+}
+
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
 fn gen_command(path: impl AsRef<OsStr>, xclip_syntax: bool) -> Command {
     let mut c = Command::new(path);
@@ -57,12 +81,43 @@ pub fn copy_to_clipboard(string: &str) -> Result<()> {
     execute_copy_command(cmd, string)
 }
 
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+pub fn paste_from_clipboard() -> Result<String> {
+    use std::path::PathBuf;
+    use which::which;
+    let (path, xclip_syntax) = which("xclip").ok().map_or_else(
+        || {
+            (
+                which("xsel").ok().unwrap_or_else(|| PathBuf::from("xsel")),
+                false,
+            )
+        },
+        |path| (path, true),
+    );
+
+    let mut cmd = gen_command(path, xclip_syntax);
+    cmd.arg(if xclip_syntax { "-out" } else { "--output" });
+    execute_paste_command(cmd)
+}
+
 #[cfg(target_os = "macos")]
 pub fn copy_to_clipboard(string: &str) -> Result<()> {
     execute_copy_command(Command::new("pbcopy"), string)
 }
 
+#[cfg(target_os = "macos")]
+pub fn paste_from_clipboard() -> Result<String> {
+    execute_paste_command(Command::new("pbpaste"))
+}
+
 #[cfg(windows)]
 pub fn copy_to_clipboard(string: &str) -> Result<()> {
     execute_copy_command(Command::new("clip"), string)
 }
+
+#[cfg(windows)]
+pub fn paste_from_clipboard() -> Result<String> {
+    let mut command = Command::new("powershell");
+    command.args(&["-NoProfile", "-Command", "Get-Clipboard"]);
+    execute_paste_command(command).map(|text| text.trim_end_matches(&['\r', '\n'][..]).to_string())
+}
@@ -0,0 +1,101 @@
+use crate::json_store::JsonStore;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const COLUMN_LAYOUTS_FILE: &str = "column_layouts.json";
+
+/// Which columns of a table are shown and in what order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnLayout {
+    /// Column names in the order they are shown. Columns not listed follow in
+    /// the order the database returns them.
+    pub order: Vec<String>,
+    pub hidden: Vec<String>,
+    /// How many of the first shown columns stay in place while scrolling
+    /// horizontally
+    pub frozen: usize,
+    /// Widths set by hand, by column name
+    #[serde(default)]
+    pub widths: HashMap<String, usize>,
+}
+
+impl ColumnLayout {
+    /// Indices into `headers` of all columns in order, hidden ones included.
+    pub fn order(&self, headers: &[String]) -> Vec<usize> {
+        let mut order = vec![];
+        for name in &self.order {
+            if let Some(i) = (0..headers.len()).find(|i| headers[*i] == *name && !order.contains(i))
+            {
+                order.push(i);
+            }
+        }
+        for i in 0..headers.len() {
+            if !order.contains(&i) {
+                order.push(i);
+            }
+        }
+        order
+    }
+
+    /// Indices into `headers` of the shown columns, in order.
+    pub fn columns(&self, headers: &[String]) -> Vec<usize> {
+        self.order(headers)
+            .into_iter()
+            .filter(|i| !self.hidden.contains(&headers[*i]))
+            .collect()
+    }
+}
+
+/// The column layout of each table that has one
+#[derive(Default)]
+pub struct ColumnLayouts {
+    tables: JsonStore<HashMap<String, ColumnLayout>>,
+}
+
+impl ColumnLayouts {
+    pub fn new() -> Self {
+        Self {
+            tables: JsonStore::new(COLUMN_LAYOUTS_FILE),
+        }
+    }
+
+    fn tables(&mut self) -> &mut HashMap<String, ColumnLayout> {
+        self.tables.get()
+    }
+
+    pub fn get(&mut self, table: &str) -> ColumnLayout {
+        self.tables().get(table).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, table: &str, layout: ColumnLayout) -> Result<()> {
+        if layout == ColumnLayout::default() {
+            self.tables().remove(table);
+        } else {
+            self.tables().insert(table.to_string(), layout);
+        }
+        self.tables.save()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ColumnLayout;
+
+    #[test]
+    fn test_columns() {
+        let headers = ["id", "name", "email", "id"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect::<Vec<_>>();
+        let layout = ColumnLayout {
+            order: vec!["email".to_string(), "id".to_string(), "gone".to_string()],
+            hidden: vec!["name".to_string()],
+            frozen: 1,
+            ..ColumnLayout::default()
+        };
+        assert_eq!(layout.order(&headers), vec![2, 0, 1, 3]);
+        assert_eq!(layout.columns(&headers), vec![2, 0, 3]);
+        assert_eq!(ColumnLayout::default().columns(&headers), vec![0, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,298 @@
+use super::{utils::input::Input, Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::{BindValue, ParamType};
+use crate::event::Key;
+use crate::history::Param;
+use crate::sql::params::Placeholders;
+use anyhow::Result;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Prompts for the values of the placeholders in a query before it is run.
+pub struct BindParamsComponent {
+    /// The query as written in the editor, which recent values are kept for
+    query: String,
+    placeholders: Placeholders,
+    params: Vec<(ParamType, Input)>,
+    selected: usize,
+    recent: Vec<Vec<Param>>,
+    recent_index: usize,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl BindParamsComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            query: String::new(),
+            placeholders: Placeholders {
+                query: String::new(),
+                names: vec![],
+                binds: vec![],
+            },
+            params: vec![],
+            selected: 0,
+            recent: vec![],
+            recent_index: 0,
+            visible: false,
+            key_config,
+        }
+    }
+
+    /// Shows the prompt for `placeholders`, filled in with the most recent of
+    /// `recent` values.
+    pub fn open(&mut self, query: String, placeholders: Placeholders, recent: Vec<Vec<Param>>) {
+        self.params = placeholders
+            .names
+            .iter()
+            .map(|_| (ParamType::Text, Input::new()))
+            .collect();
+        self.query = query;
+        self.placeholders = placeholders;
+        self.selected = 0;
+        self.recent = recent;
+        self.recent_index = 0;
+        self.apply_recent();
+        self.visible = true;
+    }
+
+    pub fn query(&self) -> &str {
+        self.query.as_str()
+    }
+
+    /// The entered values, to be remembered in the history.
+    pub fn params(&self) -> Vec<Param> {
+        self.params
+            .iter()
+            .map(|(r#type, input)| Param {
+                r#type: *r#type,
+                value: input.value_str().to_string(),
+            })
+            .collect()
+    }
+
+    /// The query to run and its bind values, in bind order.
+    pub fn bind_values(&self) -> Result<(String, Vec<BindValue>)> {
+        let values = self
+            .params
+            .iter()
+            .zip(self.placeholders.names.iter())
+            .map(|((r#type, input), name)| {
+                r#type
+                    .parse(input.value_str())
+                    .map_err(|err| anyhow::anyhow!("{}: {}", name, err))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((
+            self.placeholders.query.clone(),
+            self.placeholders
+                .binds
+                .iter()
+                .map(|index| values[*index].clone())
+                .collect(),
+        ))
+    }
+
+    fn apply_recent(&mut self) {
+        if let Some(recent) = self.recent.get(self.recent_index) {
+            if recent.len() == self.params.len() {
+                for ((r#type, input), param) in self.params.iter_mut().zip(recent.iter()) {
+                    *r#type = param.r#type;
+                    input.set_value(param.value.as_str());
+                }
+            }
+        }
+    }
+
+    fn label_width(&self) -> usize {
+        self.placeholders
+            .names
+            .iter()
+            .map(|name| name.width())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl DrawableComponent for BindParamsComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let width = 65;
+        let height = self.params.len() as u16 + 4;
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width.min(f.size().width),
+            height.min(f.size().height),
+        );
+
+        let label_width = self.label_width();
+        let mut lines = vec![];
+        for (i, ((r#type, input), name)) in self
+            .params
+            .iter()
+            .zip(self.placeholders.names.iter())
+            .enumerate()
+        {
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:width$} ", name, width = label_width),
+                    if i == self.selected {
+                        Style::default().fg(Color::Blue)
+                    } else {
+                        Style::default()
+                    },
+                ),
+                Span::styled(
+                    format!("{:9} ", format!("[{}]", r#type)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ];
+            spans.extend(input.spans());
+            lines.push(Spans::from(spans));
+        }
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "{} run, {} type, {} recent values, {} cancel",
+                self.key_config.enter,
+                self.key_config.toggle_param_type,
+                self.key_config.recent_params,
+                self.key_config.exit_popup
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .title("Bind parameters")
+                    .borders(Borders::ALL),
+            ),
+            area,
+        );
+        if let Some((_, input)) = self.params.get(self.selected) {
+            f.set_cursor(
+                (area.x + 1 + label_width as u16 + 11)
+                    .saturating_add(input.cursor_position())
+                    .min(area.right().saturating_sub(2)),
+                area.y + 1 + self.selected as u16,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for BindParamsComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible || key == self.key_config.enter {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit_popup {
+            self.hide();
+        } else if key == self.key_config.move_down || key == Key::Tab {
+            self.selected = (self.selected + 1) % self.params.len().max(1);
+        } else if key == self.key_config.move_up {
+            self.selected = self
+                .selected
+                .checked_sub(1)
+                .unwrap_or_else(|| self.params.len().saturating_sub(1));
+        } else if key == self.key_config.toggle_param_type {
+            if let Some((r#type, _)) = self.params.get_mut(self.selected) {
+                *r#type = r#type.next();
+            }
+        } else if key == self.key_config.recent_params {
+            if !self.recent.is_empty() {
+                self.recent_index = (self.recent_index + 1) % self.recent.len();
+                self.apply_recent();
+            }
+        } else if let Some((_, input)) = self.params.get_mut(self.selected) {
+            return input.event(key);
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BindParamsComponent, BindValue, Component as _, Key, KeyConfig, Param, ParamType};
+    use crate::sql::{placeholders, Dialect};
+
+    #[test]
+    fn test_bind_values() {
+        let mut prompt = BindParamsComponent::new(KeyConfig::default());
+        let query = "select * from t where a = :a and b = ? and c = :a";
+        prompt.open(
+            query.to_string(),
+            placeholders(query, Dialect::MySql),
+            vec![],
+        );
+        assert!(prompt.is_visible());
+
+        prompt.event(Key::Char('1')).unwrap();
+        prompt
+            .event(KeyConfig::default().toggle_param_type)
+            .unwrap();
+        prompt.event(Key::Down).unwrap();
+        prompt.event(Key::Char('x')).unwrap();
+        assert_eq!(
+            prompt.bind_values().unwrap(),
+            (
+                "select * from t where a = ? and b = ? and c = ?".to_string(),
+                vec![
+                    BindValue::Integer(1),
+                    BindValue::Text("x".to_string()),
+                    BindValue::Integer(1)
+                ]
+            )
+        );
+
+        prompt.event(Key::Up).unwrap();
+        prompt.event(Key::Char('a')).unwrap();
+        assert!(prompt.bind_values().is_err());
+    }
+
+    #[test]
+    fn test_recent_values() {
+        let mut prompt = BindParamsComponent::new(KeyConfig::default());
+        let recent = |value: &str| {
+            vec![Param {
+                r#type: ParamType::Boolean,
+                value: value.to_string(),
+            }]
+        };
+        prompt.open(
+            "select $1".to_string(),
+            placeholders("select $1", Dialect::Postgres),
+            vec![recent("true"), recent("false")],
+        );
+        assert_eq!(prompt.params(), recent("true"));
+        prompt.event(KeyConfig::default().recent_params).unwrap();
+        assert_eq!(prompt.params(), recent("false"));
+        assert_eq!(
+            prompt.bind_values().unwrap().1,
+            vec![BindValue::Boolean(false)]
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use super::{utils::input::Input, Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::event::Key;
+use crate::sql::dml::{self, ColumnValue};
+use crate::sql::Dialect;
+use anyhow::Result;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+const WIDTH: u16 = 80;
+
+/// The cell being edited and how to find its row in the database
+pub struct CellTarget {
+    /// The quoted name of the table
+    pub table: String,
+    pub dialect: Dialect,
+    pub column: String,
+    pub type_name: String,
+    /// The values of the row's primary or unique key
+    pub key: Vec<ColumnValue>,
+    pub row: usize,
+    /// The index of the column in the loaded headers
+    pub column_index: usize,
+}
+
+/// An edit that has been confirmed, to be staged and shown in the table
+pub struct CellUpdate {
+    pub statement: String,
+    pub row: usize,
+    pub column_index: usize,
+    pub value: Option<String>,
+}
+
+#[derive(PartialEq)]
+enum Stage {
+    Edit,
+    /// The statement is shown for confirmation
+    Preview,
+}
+
+/// Edits one value of the record table in a popup, showing the `UPDATE` it
+/// results in before it is staged.
+pub struct CellEditorComponent {
+    target: Option<CellTarget>,
+    input: Input,
+    /// Whether the value is NULL, as opposed to what `input` holds
+    null: bool,
+    stage: Stage,
+    confirmed: bool,
+    key_config: KeyConfig,
+}
+
+impl CellEditorComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            target: None,
+            input: Input::new(),
+            null: false,
+            stage: Stage::Edit,
+            confirmed: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, target: CellTarget, value: Option<&str>) {
+        self.input.set_value(value.unwrap_or_default());
+        self.null = value.is_none();
+        self.target = Some(target);
+        self.stage = Stage::Edit;
+        self.confirmed = false;
+    }
+
+    pub fn close(&mut self) {
+        self.target = None;
+    }
+
+    fn value(&self) -> Option<String> {
+        if self.null {
+            None
+        } else {
+            Some(self.input.value_str().to_string())
+        }
+    }
+
+    fn statement(&self) -> Option<String> {
+        let target = self.target.as_ref()?;
+        Some(dml::update(
+            &target.table,
+            &[ColumnValue {
+                column: target.column.clone(),
+                type_name: target.type_name.clone(),
+                value: self.value(),
+            }],
+            &target.key,
+            target.dialect,
+        ))
+    }
+
+    /// The edit, once it has been confirmed.
+    pub fn take_update(&mut self) -> Option<CellUpdate> {
+        if !self.confirmed {
+            return None;
+        }
+        self.confirmed = false;
+        self.stage = Stage::Edit;
+        let target = self.target.as_ref()?;
+        Some(CellUpdate {
+            statement: self.statement()?,
+            row: target.row,
+            column_index: target.column_index,
+            value: self.value(),
+        })
+    }
+}
+
+impl DrawableComponent for CellEditorComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        let target = match &self.target {
+            Some(target) => target,
+            None => return Ok(()),
+        };
+        let statement = self.statement().unwrap_or_default();
+        let width = WIDTH.min(f.size().width.saturating_sub(4));
+        let inner_width = width.saturating_sub(2).max(1) as usize;
+        let statement_height = (statement.width().max(1) - 1) / inner_width + 1;
+        let height = (statement_height as u16 + 6).min(f.size().height);
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+
+        let placeholder = Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::ITALIC);
+        let value = if self.null {
+            Spans::from(Span::styled("NULL", placeholder))
+        } else if self.input.is_empty() {
+            Spans::from(Span::styled("(empty string)", placeholder))
+        } else {
+            Spans::from(self.input.spans())
+        };
+        let (statement_style, hint) = match self.stage {
+            Stage::Edit => (
+                Style::default().fg(Color::DarkGray),
+                format!(
+                    "{} preview, {} NULL, {} cancel",
+                    self.key_config.enter, self.key_config.set_null, self.key_config.exit_popup
+                ),
+            ),
+            Stage::Preview => (
+                Style::default().fg(Color::Yellow),
+                format!(
+                    "{} stage, {} back",
+                    self.key_config.enter, self.key_config.exit_popup
+                ),
+            ),
+        };
+        let paragraph = Paragraph::new(vec![
+            value,
+            Spans::from(""),
+            Spans::from(Span::styled(statement, statement_style)),
+            Spans::from(""),
+            Spans::from(Span::styled(hint, Style::default().fg(Color::DarkGray))),
+        ])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Edit {} ({})", target.column, target.type_name)),
+        )
+        .wrap(Wrap { trim: false });
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+        if self.stage == Stage::Edit && !self.null {
+            f.set_cursor(area.x + 1 + self.input.cursor_position(), area.y + 1);
+        }
+        Ok(())
+    }
+}
+
+impl Component for CellEditorComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::edit_cell(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.target.is_none() {
+            return Ok(EventState::NotConsumed);
+        }
+        match self.stage {
+            Stage::Edit => {
+                if key == self.key_config.exit_popup {
+                    self.close();
+                } else if key == self.key_config.enter {
+                    self.stage = Stage::Preview;
+                } else if key == self.key_config.set_null {
+                    self.null = !self.null;
+                } else if self.null {
+                    // Typing replaces NULL with text.
+                    if let Key::Char(_) = key {
+                        self.null = false;
+                        self.input.reset();
+                        self.input.event(key)?;
+                    }
+                } else {
+                    self.input.event(key)?;
+                }
+            }
+            Stage::Preview => {
+                if key == self.key_config.enter {
+                    self.confirmed = true;
+                } else if key == self.key_config.exit_popup {
+                    self.stage = Stage::Edit;
+                }
+            }
+        }
+        Ok(EventState::Consumed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CellEditorComponent, CellTarget, ColumnValue, Component as _, Dialect, KeyConfig};
+    use crate::event::Key;
+
+    fn target() -> CellTarget {
+        CellTarget {
+            table: "\"users\"".to_string(),
+            dialect: Dialect::Sqlite,
+            column: "name".to_string(),
+            type_name: "TEXT".to_string(),
+            key: vec![ColumnValue::new("id", "INTEGER", Some("1"))],
+            row: 3,
+            column_index: 1,
+        }
+    }
+
+    #[test]
+    fn test_null_and_empty_string() {
+        let key_config = KeyConfig::default();
+        let mut editor = CellEditorComponent::new(key_config.clone());
+        editor.open(target(), None);
+        assert_eq!(
+            editor.statement().unwrap(),
+            "UPDATE \"users\" SET \"name\" = NULL WHERE \"id\" = 1"
+        );
+
+        editor.event(Key::Char('a')).unwrap();
+        editor.event(Key::Backspace).unwrap();
+        assert_eq!(
+            editor.statement().unwrap(),
+            "UPDATE \"users\" SET \"name\" = '' WHERE \"id\" = 1"
+        );
+
+        editor.event(key_config.set_null).unwrap();
+        assert_eq!(editor.value(), None);
+    }
+
+    #[test]
+    fn test_confirm() {
+        let key_config = KeyConfig::default();
+        let mut editor = CellEditorComponent::new(key_config.clone());
+        editor.open(target(), Some("bob"));
+        editor.event(Key::Char('!')).unwrap();
+        editor.event(key_config.enter).unwrap();
+        assert!(editor.take_update().is_none());
+
+        editor.event(key_config.enter).unwrap();
+        let update = editor.take_update().unwrap();
+        assert_eq!(
+            update.statement,
+            "UPDATE \"users\" SET \"name\" = 'bob!' WHERE \"id\" = 1"
+        );
+        assert_eq!((update.row, update.column_index), (3, 1));
+        assert_eq!(update.value, Some("bob!".to_string()));
+
+        editor.event(key_config.exit_popup).unwrap();
+        assert!(editor.target.is_none());
+    }
+}
@@ -0,0 +1,172 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::database::{BatchStatement, BindValue};
+use crate::event::Key;
+use anyhow::Result;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+/// What the user decided about the pending changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    Commit,
+    Discard,
+}
+
+/// Lists the statements of the pending changes, to commit or discard them.
+pub struct ChangesComponent {
+    statements: Vec<BatchStatement>,
+    visible: bool,
+    scroll: u16,
+    decision: Option<Decision>,
+    key_config: KeyConfig,
+}
+
+impl ChangesComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            statements: vec![],
+            visible: false,
+            scroll: 0,
+            decision: None,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, statements: Vec<BatchStatement>) {
+        self.statements = statements;
+        self.visible = true;
+        self.scroll = 0;
+        self.decision = None;
+    }
+
+    /// The decision, once made. The list is closed then.
+    pub fn take_decision(&mut self) -> Option<Decision> {
+        let decision = self.decision.take()?;
+        self.hide();
+        Some(decision)
+    }
+
+    fn lines(&self) -> Vec<Spans<'_>> {
+        let mut lines = vec![];
+        for (i, statement) in self.statements.iter().enumerate() {
+            lines.push(Spans::from(vec![
+                Span::styled(
+                    format!("{:>3} ", i + 1),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(statement.query.as_str(), Style::default().fg(Color::Yellow)),
+            ]));
+            if !statement.params.is_empty() {
+                lines.push(Spans::from(Span::styled(
+                    format!(
+                        "    with {}",
+                        statement
+                            .params
+                            .iter()
+                            .map(|param| match param {
+                                BindValue::Null => "NULL".to_string(),
+                                BindValue::Text(value) => format!("'{}'", value),
+                                BindValue::Integer(value) => value.to_string(),
+                                BindValue::Float(value) => value.to_string(),
+                                BindValue::Boolean(value) => value.to_string(),
+                            })
+                            .collect::<Vec<String>>()
+                            .join(", ")
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+        lines
+    }
+}
+
+impl DrawableComponent for ChangesComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let size = f.size();
+        let area = Rect::new(
+            2.min(size.width),
+            1.min(size.height),
+            size.width.saturating_sub(4),
+            size.height.saturating_sub(2),
+        );
+        let title = format!(
+            "{} pending changes [{} commit, {} discard, {} close]",
+            self.statements.len(),
+            self.key_config.enter,
+            self.key_config.discard_changes,
+            self.key_config.exit_popup
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(self.lines())
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            area,
+        );
+        Ok(())
+    }
+}
+
+impl Component for ChangesComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::review_changes(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit_popup {
+            self.hide();
+        } else if key == self.key_config.enter {
+            self.decision = Some(Decision::Commit);
+        } else if key == self.key_config.discard_changes {
+            self.decision = Some(Decision::Discard);
+        } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            self.scroll = self.scroll.saturating_add(1);
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BatchStatement, ChangesComponent, Component as _, Decision, KeyConfig};
+
+    #[test]
+    fn test_decision() {
+        let key_config = KeyConfig::default();
+        let mut changes = ChangesComponent::new(key_config.clone());
+        changes.open(vec![BatchStatement {
+            query: "DELETE FROM \"t\" WHERE \"id\" = 1".to_string(),
+            params: vec![],
+            rows: 1,
+            read_back: None,
+        }]);
+        changes.event(key_config.scroll_down).unwrap();
+        assert_eq!(changes.take_decision(), None);
+
+        changes.event(key_config.discard_changes).unwrap();
+        assert_eq!(changes.take_decision(), Some(Decision::Discard));
+        assert!(!changes.visible);
+    }
+}
@@ -0,0 +1,347 @@
+use super::{utils::input::Input, Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::event::Key;
+use crate::sql::ddl::{ColumnChange, ColumnDefinition};
+use anyhow::Result;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+const WIDTH: u16 = 70;
+const LABEL_WIDTH: usize = 10;
+
+/// What the form changes
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnForm {
+    Add,
+    Rename { column: String },
+    ChangeType { column: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Name,
+    Type,
+    Nullable,
+    Default,
+}
+
+impl Field {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Type => "Type",
+            Self::Nullable => "Nullable",
+            Self::Default => "Default",
+        }
+    }
+}
+
+/// Asks for what a column is added, renamed or changed to, in a popup.
+pub struct ColumnFormComponent {
+    form: Option<ColumnForm>,
+    name: Input,
+    type_name: Input,
+    nullable: bool,
+    default: Input,
+    selected: usize,
+    message: Option<String>,
+    change: Option<ColumnChange>,
+    key_config: KeyConfig,
+}
+
+impl ColumnFormComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            form: None,
+            name: Input::new(),
+            type_name: Input::new(),
+            nullable: true,
+            default: Input::new(),
+            selected: 0,
+            message: None,
+            change: None,
+            key_config,
+        }
+    }
+
+    /// Opens the form, filled in with the column's name and type when it
+    /// changes an existing column.
+    pub fn open(&mut self, form: ColumnForm, name: &str, type_name: &str) {
+        self.name.set_value(name);
+        self.type_name.set_value(type_name);
+        self.nullable = true;
+        self.default.reset();
+        self.selected = 0;
+        self.message = None;
+        self.change = None;
+        self.form = Some(form);
+    }
+
+    pub fn close(&mut self) {
+        self.form = None;
+    }
+
+    /// Shows why the change can't be made, leaving the form open.
+    pub fn set_message(&mut self, message: String) {
+        self.message = Some(message);
+    }
+
+    fn fields(&self) -> &'static [Field] {
+        match self.form {
+            Some(ColumnForm::Add) => &[Field::Name, Field::Type, Field::Nullable, Field::Default],
+            Some(ColumnForm::Rename { .. }) => &[Field::Name],
+            Some(ColumnForm::ChangeType { .. }) => &[Field::Type],
+            None => &[],
+        }
+    }
+
+    fn input(&self, field: Field) -> Option<&Input> {
+        match field {
+            Field::Name => Some(&self.name),
+            Field::Type => Some(&self.type_name),
+            Field::Default => Some(&self.default),
+            Field::Nullable => None,
+        }
+    }
+
+    fn build(&self) -> Result<ColumnChange> {
+        let value = |input: &Input, field: Field| {
+            let value = input.value_str().trim();
+            if value.is_empty() {
+                anyhow::bail!("{} needs a value", field.label());
+            }
+            Ok(value.to_string())
+        };
+        Ok(match &self.form {
+            Some(ColumnForm::Add) => {
+                let default = self.default.value_str().trim();
+                ColumnChange::Add(ColumnDefinition {
+                    name: value(&self.name, Field::Name)?,
+                    type_name: value(&self.type_name, Field::Type)?,
+                    nullable: self.nullable,
+                    default: Some(default.to_string()).filter(|default| !default.is_empty()),
+                })
+            }
+            Some(ColumnForm::Rename { column }) => ColumnChange::Rename {
+                column: column.clone(),
+                new_name: value(&self.name, Field::Name)?,
+            },
+            Some(ColumnForm::ChangeType { column }) => ColumnChange::ChangeType {
+                column: column.clone(),
+                type_name: value(&self.type_name, Field::Type)?,
+            },
+            None => anyhow::bail!("no column is being changed"),
+        })
+    }
+
+    /// The change, once the form is filled in. The form stays open until it
+    /// is closed, in case the change can't be made.
+    pub fn take_change(&mut self) -> Option<ColumnChange> {
+        self.change.take()
+    }
+
+    fn title(&self) -> String {
+        match &self.form {
+            Some(ColumnForm::Add) | None => "Add column".to_string(),
+            Some(ColumnForm::Rename { column }) => format!("Rename {}", column),
+            Some(ColumnForm::ChangeType { column }) => format!("Change type of {}", column),
+        }
+    }
+
+    fn field_line(&self, i: usize, field: Field) -> Spans<'_> {
+        let label_style = if i == self.selected {
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![Span::styled(
+            format!("{:<width$} ", field.label(), width = LABEL_WIDTH),
+            label_style,
+        )];
+        match self.input(field) {
+            Some(input) if input.is_empty() && field == Field::Default => spans.push(Span::styled(
+                "none, or an SQL expression",
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            )),
+            Some(input) => spans.extend(input.spans()),
+            None => spans.push(Span::raw(if self.nullable { "yes" } else { "no" })),
+        }
+        Spans::from(spans)
+    }
+}
+
+impl DrawableComponent for ColumnFormComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if self.form.is_none() {
+            return Ok(());
+        }
+        let fields = self.fields();
+        let width = WIDTH.min(f.size().width.saturating_sub(4));
+        let height = (fields.len() as u16 + 5).min(f.size().height);
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+        let mut lines = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| self.field_line(i, *field))
+            .collect::<Vec<Spans>>();
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(Span::styled(
+            self.message.clone().unwrap_or_default(),
+            Style::default().fg(Color::Red),
+        )));
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "{}/{} move, space toggle, {} preview, {} cancel",
+                self.key_config.move_up,
+                self.key_config.move_down,
+                self.key_config.enter,
+                self.key_config.exit_popup
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(self.title())),
+            area,
+        );
+        if let Some(input) = fields
+            .get(self.selected)
+            .and_then(|field| self.input(*field))
+        {
+            f.set_cursor(
+                (area.x + 1 + LABEL_WIDTH as u16 + 1)
+                    .saturating_add(input.cursor_position())
+                    .min(area.right().saturating_sub(2)),
+                area.y + 1 + self.selected as u16,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for ColumnFormComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::alter_columns(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.form.is_none() {
+            return Ok(EventState::NotConsumed);
+        }
+        self.message = None;
+        let field = self.fields()[self.selected];
+        if key == self.key_config.exit_popup {
+            self.close();
+        } else if key == self.key_config.enter {
+            match self.build() {
+                Ok(change) => self.change = Some(change),
+                Err(err) => self.message = Some(err.to_string()),
+            }
+        } else if key == self.key_config.move_down || key == Key::Tab {
+            if self.selected + 1 < self.fields().len() {
+                self.selected += 1;
+            }
+        } else if key == self.key_config.move_up {
+            self.selected = self.selected.saturating_sub(1);
+        } else {
+            match field {
+                Field::Name => {
+                    self.name.event(key)?;
+                }
+                Field::Type => {
+                    self.type_name.event(key)?;
+                }
+                Field::Default => {
+                    self.default.event(key)?;
+                }
+                Field::Nullable => {
+                    if key == Key::Char(' ') {
+                        self.nullable = !self.nullable;
+                    }
+                }
+            }
+        }
+        Ok(EventState::Consumed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        ColumnChange, ColumnDefinition, ColumnForm, ColumnFormComponent, Component as _, KeyConfig,
+    };
+    use crate::event::Key;
+
+    #[test]
+    fn test_add() {
+        let key_config = KeyConfig::default();
+        let mut form = ColumnFormComponent::new(key_config.clone());
+        form.open(ColumnForm::Add, "", "");
+        form.event(Key::Char('a')).unwrap();
+        form.event(key_config.enter).unwrap();
+        assert_eq!(form.take_change(), None);
+        assert_eq!(form.message, Some("Type needs a value".to_string()));
+
+        for key in [
+            Key::Tab,
+            Key::Char('i'),
+            Key::Char('n'),
+            Key::Char('t'),
+            Key::Tab,
+        ] {
+            form.event(key).unwrap();
+        }
+        form.event(Key::Char(' ')).unwrap();
+        form.event(Key::Tab).unwrap();
+        form.event(Key::Char('0')).unwrap();
+        form.event(key_config.enter).unwrap();
+        assert_eq!(
+            form.take_change(),
+            Some(ColumnChange::Add(ColumnDefinition {
+                name: "a".to_string(),
+                type_name: "int".to_string(),
+                nullable: false,
+                default: Some("0".to_string()),
+            }))
+        );
+        assert!(form.form.is_some());
+    }
+
+    #[test]
+    fn test_rename() {
+        let key_config = KeyConfig::default();
+        let mut form = ColumnFormComponent::new(key_config.clone());
+        form.open(
+            ColumnForm::Rename {
+                column: "name".to_string(),
+            },
+            "name",
+            "text",
+        );
+        form.event(Key::Char('s')).unwrap();
+        form.event(key_config.enter).unwrap();
+        assert_eq!(
+            form.take_change(),
+            Some(ColumnChange::Rename {
+                column: "name".to_string(),
+                new_name: "names".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::components::table::truncate;
+use crate::config::KeyConfig;
+use crate::database::ColumnStats;
+use crate::event::Key;
+use anyhow::Result;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+const WIDTH: u16 = 60;
+/// Room left for values, after labels and counts
+const VALUE_WIDTH: usize = 34;
+
+/// Shows how the values of a column are distributed, in a popup.
+pub struct ColumnStatsComponent {
+    column: String,
+    type_name: String,
+    stats: ColumnStats,
+    visible: bool,
+    key_config: KeyConfig,
+}
+
+impl ColumnStatsComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            column: String::new(),
+            type_name: String::new(),
+            stats: ColumnStats::default(),
+            visible: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, column: String, type_name: String, stats: ColumnStats) {
+        self.column = column;
+        self.type_name = type_name;
+        self.stats = stats;
+        self.visible = true;
+    }
+
+    fn percentage(&self, count: i64) -> String {
+        if self.stats.rows == 0 {
+            return "-".to_string();
+        }
+        format!("{:.2}%", count as f64 * 100.0 / self.stats.rows as f64)
+    }
+
+    fn lines(&self) -> Vec<Spans<'_>> {
+        let label = |text: &'static str| {
+            Span::styled(
+                format!("{:<10}", text),
+                Style::default().add_modifier(Modifier::BOLD),
+            )
+        };
+        let value = |value: &Option<String>| match value {
+            Some(value) => Span::raw(truncate(value, VALUE_WIDTH + 10)),
+            None => Span::styled("NULL", Style::default().fg(Color::DarkGray)),
+        };
+
+        let mut lines = vec![
+            Spans::from(vec![label("Rows"), Span::raw(self.stats.rows.to_string())]),
+            Spans::from(vec![
+                label("Distinct"),
+                Span::raw(self.stats.distinct.to_string()),
+            ]),
+            Spans::from(vec![
+                label("Nulls"),
+                Span::raw(format!(
+                    "{} ({})",
+                    self.stats.nulls,
+                    self.percentage(self.stats.nulls)
+                )),
+            ]),
+            Spans::from(vec![label("Min"), value(&self.stats.min)]),
+            Spans::from(vec![label("Max"), value(&self.stats.max)]),
+        ];
+        if self.stats.average.is_some() {
+            lines.push(Spans::from(vec![
+                label("Average"),
+                value(&self.stats.average),
+            ]));
+        }
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(label("Most frequent")));
+        for (top_value, count) in &self.stats.top_values {
+            let text = top_value
+                .as_deref()
+                .map_or("NULL".to_string(), |v| truncate(v, VALUE_WIDTH));
+            lines.push(Spans::from(vec![
+                Span::styled(
+                    format!(
+                        "  {}{}",
+                        text,
+                        " ".repeat(VALUE_WIDTH.saturating_sub(text.width()))
+                    ),
+                    if top_value.is_some() {
+                        Style::default()
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    },
+                ),
+                Span::raw(format!(" {:>8}", count)),
+                Span::styled(
+                    format!(" {:>8}", self.percentage(*count)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]));
+        }
+        lines
+    }
+}
+
+impl DrawableComponent for ColumnStatsComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let lines = self.lines();
+        let height = lines.len() as u16 + 2;
+        let area = Rect::new(
+            (f.size().width.saturating_sub(WIDTH)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            WIDTH.min(f.size().width),
+            height.min(f.size().height),
+        );
+        let title = if self.type_name.is_empty() {
+            self.column.clone()
+        } else {
+            format!("{} ({})", self.column, self.type_name)
+        };
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL)),
+            area,
+        );
+        Ok(())
+    }
+}
+
+impl Component for ColumnStatsComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::column_stats(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit_popup || key == self.key_config.column_stats {
+            self.hide();
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ColumnStats, ColumnStatsComponent, KeyConfig};
+
+    #[test]
+    fn test_lines() {
+        let mut component = ColumnStatsComponent::new(KeyConfig::default());
+        component.open(
+            "name".to_string(),
+            "text".to_string(),
+            ColumnStats {
+                rows: 4,
+                distinct: 2,
+                nulls: 1,
+                min: Some("a".to_string()),
+                max: Some("b".to_string()),
+                average: None,
+                top_values: vec![(Some("a".to_string()), 2), (None, 1)],
+            },
+        );
+        let lines = component
+            .lines()
+            .iter()
+            .map(|spans| {
+                spans
+                    .0
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lines[2], "Nulls     1 (25.00%)");
+        assert_eq!(lines.len(), 9);
+        assert!(lines[7].starts_with("  a   "));
+        assert!(lines[7].ends_with("       2   50.00%"));
+        assert!(lines[8].starts_with("  NULL"));
+    }
+}
@@ -4,6 +4,7 @@ static CMD_GROUP_GENERAL: &str = "-- General --";
 static CMD_GROUP_TABLE: &str = "-- Table --";
 static CMD_GROUP_DATABASES: &str = "-- Databases --";
 static CMD_GROUP_PROPERTIES: &str = "-- Properties --";
+static CMD_GROUP_SQL_EDITOR: &str = "-- SQL Editor --";
 
 #[derive(Clone, PartialEq, PartialOrd, Ord, Eq)]
 pub struct CommandText {
@@ -69,6 +70,23 @@ pub fn expand_collapse(key: &KeyConfig) -> CommandText {
     )
 }
 
+pub fn create_table(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Create table [{}]", key.create_table),
+        CMD_GROUP_DATABASES,
+    )
+}
+
+pub fn tree_actions(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Rename/duplicate/empty/drop table, create/drop schema or database [{}]",
+            key.tree_actions
+        ),
+        CMD_GROUP_DATABASES,
+    )
+}
+
 pub fn filter(key: &KeyConfig) -> CommandText {
     CommandText::new(format!("Filter [{}]", key.filter), CMD_GROUP_GENERAL)
 }
@@ -96,6 +114,135 @@ pub fn extend_selection_by_one_cell(key: &KeyConfig) -> CommandText {
     )
 }
 
+pub fn inspect_cell(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Inspect cell value [{}]", key.inspect_cell),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn toggle_record_view(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Toggle record view [{}]", key.toggle_record_view),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn hide_column(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Hide column/show all columns [{},{}]",
+            key.hide_column, key.show_all_columns
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn move_column(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Move column left/right [{},{}]",
+            key.move_column_left, key.move_column_right
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn freeze_columns(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Freeze columns up to here [{}]", key.freeze_columns),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn resize_column(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Widen/narrow/fit column [{},{},{}]",
+            key.widen_column, key.narrow_column, key.fit_column
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn search(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Search loaded cells/next/previous match [{},{},{}]",
+            key.search, key.search_next, key.search_previous
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn quick_filter(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Filter by/out selected value [{},{}]",
+            key.quick_filter, key.quick_filter_not
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn column_stats(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Show column statistics [{}]", key.column_stats),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn edit_cell(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Edit selected cell/set it to NULL [{},{}]",
+            key.edit_cell, key.set_null
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn insert_row(key: &KeyConfig) -> CommandText {
+    CommandText::new(format!("Insert row [{}]", key.insert_row), CMD_GROUP_TABLE)
+}
+
+pub fn delete_rows(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Delete selected rows [{}]", key.delete_rows),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn review_changes(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Review pending changes to commit/discard them [{},{}]",
+            key.review_changes, key.discard_changes
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn follow_foreign_key(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Follow foreign key/go back [{},{}]",
+            key.follow_foreign_key, key.go_back
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
+pub fn show_references(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Show rows referencing selected row [{}]",
+            key.show_references
+        ),
+        CMD_GROUP_TABLE,
+    )
+}
+
 pub fn extend_or_shorten_widget_width(key: &KeyConfig) -> CommandText {
     CommandText::new(
         format!(
@@ -166,6 +313,26 @@ pub fn toggle_property_tabs(key_config: &KeyConfig) -> CommandText {
     )
 }
 
+pub fn alter_columns(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Add/rename/change type of/drop column [{},{},{},{}]",
+            key.add_column, key.rename_column, key.change_column_type, key.drop_column
+        ),
+        CMD_GROUP_PROPERTIES,
+    )
+}
+
+pub fn create_or_drop_index(key: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Create/drop index [{},{}]",
+            key.create_index, key.drop_index
+        ),
+        CMD_GROUP_PROPERTIES,
+    )
+}
+
 pub fn help(key_config: &KeyConfig) -> CommandText {
     CommandText::new(
         format!("Help [{}]", key_config.open_help),
@@ -179,3 +346,57 @@ pub fn exit_pop_up(key_config: &KeyConfig) -> CommandText {
         CMD_GROUP_GENERAL,
     )
 }
+
+pub fn execute_query(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Run query or selection/current statement [{},{}]",
+            key_config.enter, key_config.execute_statement
+        ),
+        CMD_GROUP_SQL_EDITOR,
+    )
+}
+
+pub fn format_query(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Format query [{}]", key_config.format_query),
+        CMD_GROUP_SQL_EDITOR,
+    )
+}
+
+pub fn editor_tabs(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "New/close/rename tab [{},{},{}]",
+            key_config.new_editor_tab, key_config.close_editor_tab, key_config.rename_editor_tab
+        ),
+        CMD_GROUP_SQL_EDITOR,
+    )
+}
+
+pub fn cycle_editor_tabs(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Previous/next tab [{},{}]",
+            key_config.previous_editor_tab, key_config.next_editor_tab
+        ),
+        CMD_GROUP_SQL_EDITOR,
+    )
+}
+
+pub fn toggle_messages(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!("Show/hide server messages [{}]", key_config.toggle_messages),
+        CMD_GROUP_SQL_EDITOR,
+    )
+}
+
+pub fn explain_query(key_config: &KeyConfig) -> CommandText {
+    CommandText::new(
+        format!(
+            "Explain query [{}], with ANALYZE [{}]",
+            key_config.explain_query, key_config.explain_analyze_query
+        ),
+        CMD_GROUP_SQL_EDITOR,
+    )
+}
@@ -0,0 +1,194 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::event::Key;
+use anyhow::Result;
+use database_tree::Table;
+use std::ops::Range;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+const WIDTH: u16 = 70;
+
+/// What a statement waiting for confirmation does, which decides what is
+/// refreshed once it has run
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    DeleteRows {
+        rows: Range<usize>,
+    },
+    /// Changes the schema of the opened table, running `statements` in order
+    AlterTable {
+        message: String,
+        statements: Vec<String>,
+    },
+    /// Changes the tables, schemas or databases of the database tree,
+    /// running `statements` in order
+    ChangeTree {
+        message: String,
+        statements: Vec<String>,
+        refresh: TreeRefresh,
+    },
+}
+
+/// What is loaded again once the database tree has changed
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeRefresh {
+    /// The tables of `database`, selecting `table` of `schema` if there is
+    /// one. If `changed`, a table whose rows or name changed, is open, it is
+    /// opened again as `table`, or closed if there is no `table`.
+    Database {
+        database: String,
+        schema: Option<String>,
+        table: Option<String>,
+        changed: Option<Table>,
+    },
+    /// Adds the created `database` to the tree, without reading its tables:
+    /// it has none, and Postgres only reads those of the connected database
+    CreatedDatabase { database: String },
+    /// Removes the dropped `database` from the tree
+    DroppedDatabase { database: String },
+}
+
+impl Action {
+    fn message(&self) -> String {
+        match self {
+            Self::DeleteRows { rows } if rows.len() == 1 => "Delete 1 row?".to_string(),
+            Self::DeleteRows { rows } => format!("Delete {} rows?", rows.len()),
+            Self::AlterTable { message, .. } => message.clone(),
+            Self::ChangeTree { message, .. } => message.clone(),
+        }
+    }
+}
+
+/// Shows a statement that changes data, going ahead with it only once
+/// confirmed.
+pub struct ConfirmComponent {
+    pending: Option<(Action, String)>,
+    confirmed: bool,
+    key_config: KeyConfig,
+}
+
+impl ConfirmComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            pending: None,
+            confirmed: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, action: Action, statement: String) {
+        self.pending = Some((action, statement));
+        self.confirmed = false;
+    }
+
+    /// The action and its statement, once confirmed.
+    pub fn take_confirmed(&mut self) -> Option<(Action, String)> {
+        if !self.confirmed {
+            return None;
+        }
+        self.confirmed = false;
+        self.pending.take()
+    }
+}
+
+impl DrawableComponent for ConfirmComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        let (action, statement) = match &self.pending {
+            Some(pending) => pending,
+            None => return Ok(()),
+        };
+        let width = WIDTH.min(f.size().width.saturating_sub(4));
+        let inner_width = width.saturating_sub(2).max(1) as usize;
+        let statement_height = statement
+            .lines()
+            .map(|line| (line.width().max(1) - 1) / inner_width + 1)
+            .sum::<usize>()
+            .max(1);
+        let height = (statement_height as u16 + 6).min(f.size().height.saturating_sub(2));
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+        let mut lines = vec![
+            Spans::from(Span::styled(
+                action.message(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Spans::from(""),
+        ];
+        // Statements are shown a line each.
+        lines.extend(
+            statement
+                .lines()
+                .map(|line| Spans::from(Span::styled(line, Style::default().fg(Color::Yellow)))),
+        );
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "{} confirm, {} cancel",
+                self.key_config.enter, self.key_config.exit_popup
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Confirm"))
+            .wrap(Wrap { trim: false });
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+        Ok(())
+    }
+}
+
+impl Component for ConfirmComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.pending.is_none() {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.enter {
+            self.confirmed = true;
+        } else if key == self.key_config.exit_popup {
+            self.pending = None;
+        }
+        Ok(EventState::Consumed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Action, Component as _, ConfirmComponent, KeyConfig};
+    use crate::event::Key;
+
+    #[test]
+    fn test_confirm() {
+        let key_config = KeyConfig::default();
+        let mut confirm = ConfirmComponent::new(key_config.clone());
+        let statement = "DELETE FROM \"t\" WHERE \"id\" = 1".to_string();
+
+        confirm.open(Action::DeleteRows { rows: 0..1 }, statement.clone());
+        confirm.event(Key::Char('d')).unwrap();
+        assert!(confirm.take_confirmed().is_none());
+        confirm.event(key_config.exit_popup).unwrap();
+        assert!(confirm.pending.is_none());
+
+        confirm.open(Action::DeleteRows { rows: 0..1 }, statement.clone());
+        confirm.event(key_config.enter).unwrap();
+        assert_eq!(
+            confirm.take_confirmed(),
+            Some((Action::DeleteRows { rows: 0..1 }, statement))
+        );
+        assert!(confirm.pending.is_none());
+    }
+}
@@ -0,0 +1,593 @@
+use super::{utils::input::Input, Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::database::qualified_table_name;
+use crate::event::Key;
+use crate::sql::ddl::{column_types, ColumnDefinition, NewColumn};
+use crate::sql::Dialect;
+use anyhow::Result;
+use database_tree::{Child, Database, Table};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+const WIDTH: u16 = 74;
+const LABEL_WIDTH: usize = 12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Table,
+    Column,
+    Name,
+    Type,
+    PrimaryKey,
+    NotNull,
+    Unique,
+    Default,
+    References,
+    ReferencedColumn,
+}
+
+const FIELDS: [Field; 10] = [
+    Field::Table,
+    Field::Column,
+    Field::Name,
+    Field::Type,
+    Field::PrimaryKey,
+    Field::NotNull,
+    Field::Unique,
+    Field::Default,
+    Field::References,
+    Field::ReferencedColumn,
+];
+
+impl Field {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Table => "Table",
+            Self::Column => "Column",
+            Self::Name => "Name",
+            Self::Type => "Type",
+            Self::PrimaryKey => "Primary key",
+            Self::NotNull => "Not null",
+            Self::Unique => "Unique",
+            Self::Default => "Default",
+            Self::References => "References",
+            Self::ReferencedColumn => "Ref. column",
+        }
+    }
+}
+
+/// A column being defined
+struct ColumnDraft {
+    name: Input,
+    type_index: usize,
+    primary_key: bool,
+    not_null: bool,
+    unique: bool,
+    default: Input,
+    /// The referenced table, an index into the tables of the database
+    references: Option<usize>,
+    referenced_columns: Vec<String>,
+    referenced_column: usize,
+}
+
+impl ColumnDraft {
+    fn new() -> Self {
+        Self {
+            name: Input::new(),
+            type_index: 0,
+            primary_key: false,
+            not_null: false,
+            unique: false,
+            default: Input::new(),
+            references: None,
+            referenced_columns: vec![],
+            referenced_column: 0,
+        }
+    }
+}
+
+/// A new table as defined in the form
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewTable {
+    pub database: Database,
+    pub schema: Option<String>,
+    pub name: String,
+    pub columns: Vec<NewColumn>,
+}
+
+/// Defines the columns and constraints of a table to create in the selected
+/// database or schema, in a popup.
+pub struct CreateTableComponent {
+    visible: bool,
+    database: Database,
+    schema: Option<String>,
+    /// The tables of the database, to pick referenced ones from
+    tables: Vec<Table>,
+    dialect: Dialect,
+    name: Input,
+    columns: Vec<ColumnDraft>,
+    current: usize,
+    selected: usize,
+    message: Option<String>,
+    columns_request: Option<Table>,
+    table: Option<NewTable>,
+    key_config: KeyConfig,
+}
+
+impl CreateTableComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            visible: false,
+            database: Database::new(String::new(), vec![]),
+            schema: None,
+            tables: vec![],
+            dialect: Dialect::MySql,
+            name: Input::new(),
+            columns: vec![],
+            current: 0,
+            selected: 0,
+            message: None,
+            columns_request: None,
+            table: None,
+            key_config,
+        }
+    }
+
+    /// Opens the form to create a table in `database`, in `schema` if it
+    /// has schemas.
+    pub fn open(&mut self, database: Database, schema: Option<String>, dialect: Dialect) {
+        self.tables = database
+            .children
+            .iter()
+            .flat_map(|child| match child {
+                Child::Table(table) => vec![table.clone()],
+                Child::Schema(schema) => schema.tables.clone(),
+            })
+            .collect();
+        self.database = database;
+        self.schema = schema;
+        self.dialect = dialect;
+        self.name.reset();
+        self.columns = vec![ColumnDraft::new()];
+        self.current = 0;
+        self.selected = 0;
+        self.message = None;
+        self.columns_request = None;
+        self.table = None;
+        self.visible = true;
+    }
+
+    /// The table, and its database, whose columns are needed to pick the
+    /// referenced column from, once a referenced table is picked.
+    pub fn take_columns_request(&mut self) -> Option<(Database, Table)> {
+        let table = self.columns_request.take()?;
+        Some((self.database.clone(), table))
+    }
+
+    /// Sets the columns of the referenced table, picking `id` if there is
+    /// one.
+    pub fn set_referenced_columns(&mut self, columns: Vec<String>) {
+        let column = &mut self.columns[self.current];
+        column.referenced_column = columns
+            .iter()
+            .position(|column| column == "id")
+            .unwrap_or(0);
+        column.referenced_columns = columns;
+    }
+
+    /// The table, once the form is filled in. The form is closed then.
+    pub fn take_table(&mut self) -> Option<NewTable> {
+        let table = self.table.take()?;
+        self.hide();
+        Some(table)
+    }
+
+    fn table_label(table: &Table) -> String {
+        match &table.schema {
+            Some(schema) => format!("{}.{}", schema, table.name),
+            None => table.name.clone(),
+        }
+    }
+
+    /// Moves `index` through `len` items, one to the right or the left
+    fn cycle(index: usize, len: usize, right: bool) -> usize {
+        if len == 0 {
+            0
+        } else if right {
+            (index + 1) % len
+        } else {
+            (index + len - 1) % len
+        }
+    }
+
+    fn build(&self) -> Result<NewTable> {
+        let name = self.name.value_str().trim();
+        if name.is_empty() {
+            anyhow::bail!("Table needs a name");
+        }
+        let types = column_types(self.dialect);
+        let mut columns = vec![];
+        for (i, column) in self.columns.iter().enumerate() {
+            let column_name = column.name.value_str().trim();
+            if column_name.is_empty() {
+                anyhow::bail!("column {} needs a name", i + 1);
+            }
+            let references = match column.references {
+                Some(table) => {
+                    let referenced = column
+                        .referenced_columns
+                        .get(column.referenced_column)
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("pick the column {} references", column_name)
+                        })?;
+                    Some((
+                        qualified_table_name(&self.database, &self.tables[table], self.dialect),
+                        referenced.clone(),
+                    ))
+                }
+                None => None,
+            };
+            let default = column.default.value_str().trim();
+            columns.push(NewColumn {
+                definition: ColumnDefinition {
+                    name: column_name.to_string(),
+                    type_name: types[column.type_index].to_string(),
+                    nullable: !column.not_null && !column.primary_key,
+                    default: Some(default.to_string()).filter(|default| !default.is_empty()),
+                },
+                primary_key: column.primary_key,
+                unique: column.unique,
+                references,
+            });
+        }
+        Ok(NewTable {
+            database: self.database.clone(),
+            schema: self.schema.clone(),
+            name: name.to_string(),
+            columns,
+        })
+    }
+
+    fn field_value(&self, field: Field) -> Vec<Span<'_>> {
+        let column = &self.columns[self.current];
+        let yes_no = |value: bool| vec![Span::raw(if value { "yes" } else { "no" })];
+        let hint = |text: String| {
+            vec![Span::styled(
+                text,
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            )]
+        };
+        match field {
+            Field::Table => self.name.spans(),
+            Field::Column => vec![
+                Span::raw(format!("{} of {} ", self.current + 1, self.columns.len())),
+                Span::styled(
+                    format!(
+                        "{}/{} switch, + add, - remove",
+                        self.key_config.focus_left, self.key_config.focus_right
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ],
+            Field::Name => column.name.spans(),
+            Field::Type => vec![Span::raw(format!(
+                "< {} >",
+                column_types(self.dialect)[column.type_index]
+            ))],
+            Field::PrimaryKey => yes_no(column.primary_key),
+            Field::NotNull => yes_no(column.not_null || column.primary_key),
+            Field::Unique => yes_no(column.unique),
+            Field::Default if column.default.is_empty() => {
+                hint("none, or an SQL expression".to_string())
+            }
+            Field::Default => column.default.spans(),
+            Field::References => match column.references {
+                Some(table) => vec![Span::raw(format!(
+                    "< {} >",
+                    Self::table_label(&self.tables[table])
+                ))],
+                None => hint("< none >".to_string()),
+            },
+            Field::ReferencedColumn => {
+                match column.referenced_columns.get(column.referenced_column) {
+                    Some(referenced) => vec![Span::raw(format!("< {} >", referenced))],
+                    None => hint("pick a table first".to_string()),
+                }
+            }
+        }
+    }
+
+    fn summary_line(&self, i: usize, column: &ColumnDraft) -> Spans<'_> {
+        let mut text = format!(
+            "{} {}",
+            if column.name.is_empty() {
+                "?"
+            } else {
+                column.name.value_str()
+            },
+            column_types(self.dialect)[column.type_index]
+        );
+        if column.primary_key {
+            text.push_str(" PK");
+        } else if column.not_null {
+            text.push_str(" NOT NULL");
+        }
+        if column.unique {
+            text.push_str(" UNIQUE");
+        }
+        if let Some(table) = column.references {
+            text.push_str(&format!(" -> {}", Self::table_label(&self.tables[table])));
+        }
+        Spans::from(Span::styled(
+            text,
+            if i == self.current {
+                Style::default().fg(Color::Blue)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            },
+        ))
+    }
+
+    fn input_mut(&mut self, field: Field) -> Option<&mut Input> {
+        match field {
+            Field::Table => Some(&mut self.name),
+            Field::Name => Some(&mut self.columns[self.current].name),
+            Field::Default => Some(&mut self.columns[self.current].default),
+            _ => None,
+        }
+    }
+
+    fn cursor_position(&self, field: Field) -> Option<u16> {
+        match field {
+            Field::Table => Some(self.name.cursor_position()),
+            Field::Name => Some(self.columns[self.current].name.cursor_position()),
+            Field::Default => Some(self.columns[self.current].default.cursor_position()),
+            _ => None,
+        }
+    }
+
+    /// Handles a key on a field that is picked from, not typed in
+    fn pick(&mut self, field: Field, key: Key) {
+        let right = key == self.key_config.focus_right;
+        let left = key == self.key_config.focus_left;
+        let space = key == Key::Char(' ');
+        let types = column_types(self.dialect).len();
+        let tables = self.tables.len();
+        match field {
+            Field::Column if left || right => {
+                self.current = Self::cycle(self.current, self.columns.len(), right);
+            }
+            Field::Column if key == Key::Char('+') => {
+                self.current += 1;
+                self.columns.insert(self.current, ColumnDraft::new());
+            }
+            Field::Column if key == Key::Char('-') && self.columns.len() > 1 => {
+                self.columns.remove(self.current);
+                self.current = self.current.min(self.columns.len() - 1);
+            }
+            Field::Type if left || right => {
+                let column = &mut self.columns[self.current];
+                column.type_index = Self::cycle(column.type_index, types, right);
+            }
+            Field::PrimaryKey if space => {
+                let column = &mut self.columns[self.current];
+                column.primary_key = !column.primary_key;
+            }
+            Field::NotNull if space => {
+                let column = &mut self.columns[self.current];
+                column.not_null = !column.not_null;
+            }
+            Field::Unique if space => {
+                let column = &mut self.columns[self.current];
+                column.unique = !column.unique;
+            }
+            // The tables are cycled through with "none" before the first.
+            Field::References if left || right => {
+                let column = &mut self.columns[self.current];
+                let index = column.references.map_or(0, |table| table + 1);
+                column.references = match Self::cycle(index, tables + 1, right) {
+                    0 => None,
+                    index => Some(index - 1),
+                };
+                column.referenced_columns = vec![];
+                column.referenced_column = 0;
+                self.columns_request = column.references.map(|table| self.tables[table].clone());
+            }
+            Field::ReferencedColumn if left || right => {
+                let column = &mut self.columns[self.current];
+                column.referenced_column = Self::cycle(
+                    column.referenced_column,
+                    column.referenced_columns.len(),
+                    right,
+                );
+            }
+            _ => (),
+        }
+    }
+}
+
+impl DrawableComponent for CreateTableComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let width = WIDTH.min(f.size().width.saturating_sub(4));
+        let height = (FIELDS.len() as u16 + self.columns.len() as u16 + 6).min(f.size().height);
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+        let mut lines = FIELDS
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let label_style = if i == self.selected {
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let mut spans = vec![Span::styled(
+                    format!("{:<width$} ", field.label(), width = LABEL_WIDTH),
+                    label_style,
+                )];
+                spans.extend(self.field_value(*field));
+                Spans::from(spans)
+            })
+            .collect::<Vec<Spans>>();
+        lines.push(Spans::from(""));
+        lines.extend(
+            self.columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| self.summary_line(i, column)),
+        );
+        lines.push(Spans::from(Span::styled(
+            self.message.clone().unwrap_or_default(),
+            Style::default().fg(Color::Red),
+        )));
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "{}/{} move, {}/{} pick, space toggle, {} preview, {} cancel",
+                self.key_config.move_up,
+                self.key_config.move_down,
+                self.key_config.focus_left,
+                self.key_config.focus_right,
+                self.key_config.enter,
+                self.key_config.exit_popup
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        let title = match &self.schema {
+            Some(schema) => format!("Create table in {}.{}", self.database.name, schema),
+            None => format!("Create table in {}", self.database.name),
+        };
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)),
+            area,
+        );
+        if let Some(position) = self.cursor_position(FIELDS[self.selected]) {
+            f.set_cursor(
+                (area.x + 1 + LABEL_WIDTH as u16 + 1)
+                    .saturating_add(position)
+                    .min(area.right().saturating_sub(2)),
+                area.y + 1 + self.selected as u16,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for CreateTableComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::create_table(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        self.message = None;
+        let field = FIELDS[self.selected];
+        if key == self.key_config.exit_popup {
+            self.hide();
+        } else if key == self.key_config.enter {
+            match self.build() {
+                Ok(table) => self.table = Some(table),
+                Err(err) => self.message = Some(err.to_string()),
+            }
+        } else if key == self.key_config.move_down || key == Key::Tab {
+            if self.selected + 1 < FIELDS.len() {
+                self.selected += 1;
+            }
+        } else if key == self.key_config.move_up {
+            self.selected = self.selected.saturating_sub(1);
+        } else if let Some(input) = self.input_mut(field) {
+            input.event(key)?;
+        } else {
+            self.pick(field, key);
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Component as _, CreateTableComponent, Database, Dialect, KeyConfig, Table};
+    use crate::event::Key;
+
+    fn table(name: &str) -> Table {
+        Table {
+            name: name.to_string(),
+            create_time: None,
+            update_time: None,
+            engine: None,
+            schema: None,
+        }
+    }
+
+    #[test]
+    fn test_take_table() {
+        let key_config = KeyConfig::default();
+        let mut form = CreateTableComponent::new(key_config.clone());
+        form.open(
+            Database::new("db".to_string(), vec![table("customers").into()]),
+            None,
+            Dialect::Sqlite,
+        );
+        form.event(key_config.enter).unwrap();
+        assert_eq!(form.take_table(), None);
+        assert_eq!(form.message, Some("Table needs a name".to_string()));
+
+        let keys = |text: &str| text.chars().map(Key::Char).collect::<Vec<Key>>();
+        let mut events = keys("orders");
+        events.extend([key_config.move_down, key_config.move_down]);
+        events.extend(keys("id"));
+        events.extend([key_config.move_down, key_config.move_down, Key::Char(' ')]);
+        events.extend([key_config.move_up, key_config.move_up, key_config.move_up]);
+        events.extend([Key::Char('+'), key_config.move_down]);
+        events.extend(keys("customer_id"));
+        for _ in 0..6 {
+            events.push(key_config.move_down);
+        }
+        events.push(key_config.focus_right);
+        for key in events {
+            form.event(key).unwrap();
+        }
+        assert_eq!(
+            form.take_columns_request().map(|(_, table)| table),
+            Some(table("customers"))
+        );
+        form.set_referenced_columns(vec!["name".to_string(), "id".to_string()]);
+        form.event(key_config.enter).unwrap();
+
+        let new_table = form.take_table().unwrap();
+        assert_eq!(new_table.name, "orders");
+        assert_eq!(new_table.columns.len(), 2);
+        assert!(new_table.columns[0].primary_key);
+        assert!(!new_table.columns[0].definition.nullable);
+        assert_eq!(new_table.columns[1].definition.name, "customer_id");
+        assert_eq!(
+            new_table.columns[1].references,
+            Some(("\"customers\"".to_string(), "id".to_string()))
+        );
+        assert!(!form.visible);
+    }
+}
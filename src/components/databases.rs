@@ -34,6 +34,7 @@ pub enum Focus {
 }
 
 pub struct DatabasesComponent {
+    databases: Vec<Database>,
     tree: DatabaseTree,
     filter: DatabaseFilterComponent,
     filterd_tree: Option<DatabaseTree>,
@@ -45,6 +46,7 @@ pub struct DatabasesComponent {
 impl DatabasesComponent {
     pub fn new(key_config: KeyConfig) -> Self {
         Self {
+            databases: vec![],
             tree: DatabaseTree::default(),
             filter: DatabaseFilterComponent::new(),
             filterd_tree: None,
@@ -63,11 +65,74 @@ impl DatabasesComponent {
             None => pool.get_databases().await?,
         };
         self.tree = DatabaseTree::new(databases.as_slice(), &BTreeSet::new())?;
+        self.databases = databases;
         self.filterd_tree = None;
         self.filter.reset();
         Ok(())
     }
 
+    /// Loads the tables of `database` again, selecting `table` of `schema`
+    /// afterwards, or the database if there is no table to select.
+    pub async fn reload_database(
+        &mut self,
+        database: &str,
+        schema: Option<&str>,
+        table: Option<&str>,
+        pool: &dyn Pool,
+    ) -> Result<Database> {
+        let children = pool.get_tables(database.to_string()).await?;
+        match self.databases.iter_mut().find(|db| db.name == database) {
+            Some(db) => db.children = children.clone(),
+            None => self
+                .databases
+                .push(Database::new(database.to_string(), children.clone())),
+        }
+        self.rebuild_tree()?;
+        if !self.tree.select(database, schema, table) {
+            self.tree.select(database, None, None);
+        }
+        Ok(Database::new(database.to_string(), children))
+    }
+
+    /// Adds the new, empty `database` to the tree and selects it.
+    pub fn add_database(&mut self, database: &str) -> Result<()> {
+        if !self.databases.iter().any(|db| db.name == database) {
+            self.databases
+                .push(Database::new(database.to_string(), vec![]));
+        }
+        self.rebuild_tree()?;
+        self.tree.select(database, None, None);
+        Ok(())
+    }
+
+    /// Removes the dropped `database` from the tree.
+    pub fn remove_database(&mut self, database: &str) -> Result<()> {
+        self.databases.retain(|db| db.name != database);
+        self.rebuild_tree()
+    }
+
+    /// Shows `databases` as changed, keeping what is expanded and selected.
+    fn rebuild_tree(&mut self) -> Result<()> {
+        self.tree = self.tree.rebuild(self.databases.as_slice())?;
+        self.filterd_tree = None;
+        self.filter.reset();
+        Ok(())
+    }
+
+    /// The database of the selected item, and its schema if it is in one
+    pub fn selected_location(&self) -> Option<(Database, Option<String>)> {
+        let kind = self.tree().selected_item()?.kind();
+        let (database, schema) = if kind.is_database() {
+            (kind.name(), None)
+        } else if kind.is_schema() {
+            (kind.database_name()?, Some(kind.name()))
+        } else {
+            (kind.database_name()?, kind.schema_name())
+        };
+        let database = self.databases.iter().find(|db| db.name == database)?;
+        Some((database.clone(), schema))
+    }
+
     pub fn tree_focused(&self) -> bool {
         matches!(self.focus, Focus::Tree)
     }
@@ -189,7 +189,7 @@ mod test {
                     " Scroll up/down/left/right [k,j,h,l]  3",
                     Style::default().bg(Color::Blue)
                 )),
-                Spans::from(Span::styled(" Filter [/]  3", Style::default()))
+                Spans::from(Span::styled(" Filter [<Ctrl+f>]  3", Style::default()))
             ]
         );
     }
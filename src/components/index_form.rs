@@ -0,0 +1,384 @@
+use super::{utils::input::Input, Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::event::Key;
+use crate::sql::ddl::{index_methods, IndexDefinition};
+use crate::sql::Dialect;
+use anyhow::Result;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+const WIDTH: u16 = 70;
+const LABEL_WIDTH: usize = 13;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Name,
+    Columns,
+    Unique,
+    Method,
+    Concurrently,
+}
+
+impl Field {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Columns => "Columns",
+            Self::Unique => "Unique",
+            Self::Method => "Method",
+            Self::Concurrently => "Concurrently",
+        }
+    }
+}
+
+/// Asks for the columns, uniqueness and method of an index to create on a
+/// table, in a popup.
+pub struct IndexFormComponent {
+    visible: bool,
+    table: String,
+    dialect: Dialect,
+    name: Input,
+    /// The columns of the table, to pick the indexed ones from
+    columns: Vec<String>,
+    column_cursor: usize,
+    /// The picked columns, in the order they are indexed
+    picked: Vec<usize>,
+    unique: bool,
+    method: usize,
+    concurrently: bool,
+    selected: usize,
+    message: Option<String>,
+    index: Option<IndexDefinition>,
+    key_config: KeyConfig,
+}
+
+impl IndexFormComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            visible: false,
+            table: String::new(),
+            dialect: Dialect::MySql,
+            name: Input::new(),
+            columns: vec![],
+            column_cursor: 0,
+            picked: vec![],
+            unique: false,
+            method: 0,
+            concurrently: false,
+            selected: 0,
+            message: None,
+            index: None,
+            key_config,
+        }
+    }
+
+    /// Opens the form to index some of `columns`, those of `table`.
+    pub fn open(&mut self, table: String, columns: Vec<String>, dialect: Dialect) {
+        self.table = table;
+        self.column_cursor = 0;
+        self.columns = columns;
+        self.dialect = dialect;
+        self.name.reset();
+        self.picked = vec![];
+        self.unique = false;
+        self.method = 0;
+        self.concurrently = false;
+        self.selected = 0;
+        self.message = None;
+        self.index = None;
+        self.visible = true;
+    }
+
+    /// The index, once the form is filled in. The form is closed then.
+    pub fn take_index(&mut self) -> Option<IndexDefinition> {
+        let index = self.index.take()?;
+        self.hide();
+        Some(index)
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        let mut fields = vec![Field::Name, Field::Columns, Field::Unique];
+        if !index_methods(self.dialect).is_empty() {
+            fields.push(Field::Method);
+        }
+        if self.dialect == Dialect::Postgres {
+            fields.push(Field::Concurrently);
+        }
+        fields
+    }
+
+    fn picked_columns(&self) -> Vec<String> {
+        self.picked
+            .iter()
+            .map(|i| self.columns[*i].clone())
+            .collect()
+    }
+
+    /// The name given, or one made of the table and the indexed columns
+    fn index_name(&self) -> String {
+        let name = self.name.value_str().trim();
+        if name.is_empty() {
+            format!("{}_{}_idx", self.table, self.picked_columns().join("_"))
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn toggle_column(&mut self) {
+        if self.columns.is_empty() {
+            return;
+        }
+        match self.picked.iter().position(|i| *i == self.column_cursor) {
+            Some(position) => {
+                self.picked.remove(position);
+            }
+            None => self.picked.push(self.column_cursor),
+        }
+    }
+
+    fn build(&self) -> Result<IndexDefinition> {
+        if self.picked.is_empty() {
+            anyhow::bail!("pick the columns to index with space");
+        }
+        Ok(IndexDefinition {
+            name: self.index_name(),
+            columns: self.picked_columns(),
+            unique: self.unique,
+            method: index_methods(self.dialect)
+                .get(self.method)
+                .map(|method| method.to_string()),
+            concurrently: self.concurrently,
+        })
+    }
+
+    fn columns_spans(&self) -> Vec<Span<'_>> {
+        let mut spans = vec![];
+        for (i, column) in self.columns.iter().enumerate() {
+            let picked = self.picked.iter().position(|picked| *picked == i);
+            let mut style = match picked {
+                Some(_) => Style::default().fg(Color::Green),
+                None => Style::default().fg(Color::DarkGray),
+            };
+            if i == self.column_cursor && self.fields()[self.selected] == Field::Columns {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            spans.push(Span::styled(
+                match picked {
+                    Some(position) => format!("{}:{}", position + 1, column),
+                    None => column.clone(),
+                },
+                style,
+            ));
+            spans.push(Span::raw(" "));
+        }
+        spans
+    }
+
+    fn field_line(&self, i: usize, field: Field) -> Spans<'_> {
+        let label_style = if i == self.selected {
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![Span::styled(
+            format!("{:<width$} ", field.label(), width = LABEL_WIDTH),
+            label_style,
+        )];
+        let yes_no = |value: bool| Span::raw(if value { "yes" } else { "no" });
+        match field {
+            Field::Name if self.name.is_empty() => spans.push(Span::styled(
+                self.index_name(),
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            )),
+            Field::Name => spans.extend(self.name.spans()),
+            Field::Columns => spans.extend(self.columns_spans()),
+            Field::Unique => spans.push(yes_no(self.unique)),
+            Field::Method => spans.push(Span::raw(index_methods(self.dialect)[self.method])),
+            Field::Concurrently => spans.push(yes_no(self.concurrently)),
+        }
+        Spans::from(spans)
+    }
+}
+
+impl DrawableComponent for IndexFormComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let fields = self.fields();
+        let width = WIDTH.min(f.size().width.saturating_sub(4));
+        let columns_width = self
+            .columns
+            .iter()
+            .map(|column| column.len() + 3)
+            .sum::<usize>() as u16;
+        let columns_height = (columns_width.max(1) - 1) / width.saturating_sub(3).max(1) + 1;
+        let height = (fields.len() as u16 + columns_height + 4).min(f.size().height);
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+        let mut lines = fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| self.field_line(i, *field))
+            .collect::<Vec<Spans>>();
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(Span::styled(
+            self.message.clone().unwrap_or_default(),
+            Style::default().fg(Color::Red),
+        )));
+        lines.push(Spans::from(Span::styled(
+            format!(
+                "{}/{} move, {}/{} column, space pick/toggle, {} preview, {} cancel",
+                self.key_config.move_up,
+                self.key_config.move_down,
+                self.key_config.focus_left,
+                self.key_config.focus_right,
+                self.key_config.enter,
+                self.key_config.exit_popup
+            ),
+            Style::default().fg(Color::DarkGray),
+        )));
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format!("Create index on {}", self.table)),
+                )
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+        if fields[self.selected] == Field::Name {
+            f.set_cursor(
+                (area.x + 1 + LABEL_WIDTH as u16 + 1)
+                    .saturating_add(self.name.cursor_position())
+                    .min(area.right().saturating_sub(2)),
+                area.y + 1,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Component for IndexFormComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::create_or_drop_index(
+            &self.key_config,
+        )));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        self.message = None;
+        let fields = self.fields();
+        if key == self.key_config.exit_popup {
+            self.hide();
+        } else if key == self.key_config.enter {
+            match self.build() {
+                Ok(index) => self.index = Some(index),
+                Err(err) => self.message = Some(err.to_string()),
+            }
+        } else if key == self.key_config.move_down || key == Key::Tab {
+            if self.selected + 1 < fields.len() {
+                self.selected += 1;
+            }
+        } else if key == self.key_config.move_up {
+            self.selected = self.selected.saturating_sub(1);
+        } else {
+            match fields[self.selected] {
+                Field::Name => {
+                    self.name.event(key)?;
+                }
+                Field::Columns => {
+                    if key == self.key_config.focus_right
+                        && self.column_cursor + 1 < self.columns.len()
+                    {
+                        self.column_cursor += 1;
+                    } else if key == self.key_config.focus_left {
+                        self.column_cursor = self.column_cursor.saturating_sub(1);
+                    } else if key == Key::Char(' ') {
+                        self.toggle_column();
+                    }
+                }
+                Field::Unique if key == Key::Char(' ') => self.unique = !self.unique,
+                Field::Method if key == Key::Char(' ') => {
+                    self.method = (self.method + 1) % index_methods(self.dialect).len();
+                }
+                Field::Concurrently if key == Key::Char(' ') => {
+                    self.concurrently = !self.concurrently;
+                }
+                _ => (),
+            }
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Component as _, Dialect, IndexDefinition, IndexFormComponent, KeyConfig};
+    use crate::event::Key;
+
+    #[test]
+    fn test_take_index() {
+        let key_config = KeyConfig::default();
+        let mut form = IndexFormComponent::new(key_config.clone());
+        form.open(
+            "users".to_string(),
+            vec!["id".to_string(), "name".to_string(), "age".to_string()],
+            Dialect::Postgres,
+        );
+        form.event(key_config.move_down).unwrap();
+        form.event(key_config.enter).unwrap();
+        assert_eq!(form.take_index(), None);
+        assert!(form.message.is_some());
+
+        for key in [
+            key_config.focus_right,
+            key_config.focus_right,
+            Key::Char(' '),
+            key_config.focus_left,
+            Key::Char(' '),
+            key_config.move_down,
+            Key::Char(' '),
+            key_config.move_down,
+            Key::Char(' '),
+        ] {
+            form.event(key).unwrap();
+        }
+        form.event(key_config.enter).unwrap();
+        assert_eq!(
+            form.take_index(),
+            Some(IndexDefinition {
+                name: "users_age_name_idx".to_string(),
+                columns: vec!["age".to_string(), "name".to_string()],
+                unique: true,
+                method: Some("hash".to_string()),
+                concurrently: false,
+            })
+        );
+        assert!(!form.visible);
+    }
+}
@@ -0,0 +1,573 @@
+use super::{utils::input::Input, Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::components::table::truncate;
+use crate::config::KeyConfig;
+use crate::database::{BindValue, TableRow};
+use crate::event::Key;
+use crate::sql::dml::{self, ColumnValue};
+use crate::sql::Dialect;
+use anyhow::Result;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+const WIDTH: u16 = 80;
+const NAME_WIDTH: usize = 20;
+const TYPE_WIDTH: usize = 16;
+
+/// A column of the table to insert into, with the value typed in for it
+pub struct FormField {
+    column: String,
+    type_name: String,
+    nullable: bool,
+    default: Option<String>,
+    auto_increment: bool,
+    input: Input,
+    /// Whether NULL is inserted, as opposed to what `input` holds
+    null: bool,
+}
+
+impl FormField {
+    /// Builds a field from a row of what `Pool::get_columns` returns.
+    pub fn from_column(column: &dyn TableRow) -> Option<Self> {
+        let null = column.field("null").unwrap_or_default();
+        Some(Self {
+            column: column.field("name")?,
+            type_name: column.field("type").unwrap_or_default(),
+            nullable: null == "YES" || null == "✔︎",
+            default: column
+                .field("default")
+                .filter(|default| !default.is_empty()),
+            auto_increment: matches!(column.field("extra"), Some(extra) if extra.contains("auto_increment")),
+            input: Input::new(),
+            null: false,
+        })
+    }
+
+    /// Whether the database fills the column in when it is left out
+    fn optional(&self) -> bool {
+        self.nullable || self.default.is_some() || self.auto_increment
+    }
+
+    /// The value to insert, checked against the column's type. `None` if
+    /// the column is left out.
+    fn value(&self) -> Result<Option<BindValue>> {
+        if self.null {
+            return Ok(Some(BindValue::Null));
+        }
+        if self.input.is_empty() {
+            if self.optional() {
+                return Ok(None);
+            }
+            anyhow::bail!("{} needs a value", self.column);
+        }
+        dml::param_type(&self.type_name)
+            .parse(self.input.value_str())
+            .map(Some)
+            .map_err(|err| anyhow::anyhow!("{}: {}", self.column, err))
+    }
+
+    /// What is shown while nothing is typed in
+    fn placeholder(&self) -> String {
+        if let Some(default) = &self.default {
+            format!("(default {})", default)
+        } else if self.auto_increment {
+            "(auto increment)".to_string()
+        } else if self.nullable {
+            "(NULL)".to_string()
+        } else {
+            "(required)".to_string()
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Stage {
+    Edit,
+    /// The statement and its parameters are shown for confirmation
+    Preview,
+}
+
+/// A form of all columns of a table that inserts a row. Columns left blank
+/// are left out of the `INSERT` for the database to fill in.
+pub struct InsertRowComponent {
+    /// The quoted name of the table, while the form is open
+    table: Option<(String, Dialect)>,
+    fields: Vec<FormField>,
+    selected: usize,
+    stage: Stage,
+    /// The statement and parameters being previewed
+    insert: Option<(String, Vec<BindValue>)>,
+    confirmed: bool,
+    message: Option<String>,
+    key_config: KeyConfig,
+}
+
+impl InsertRowComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            table: None,
+            fields: vec![],
+            selected: 0,
+            stage: Stage::Edit,
+            insert: None,
+            confirmed: false,
+            message: None,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, table: String, dialect: Dialect, fields: Vec<FormField>) {
+        self.table = Some((table, dialect));
+        self.fields = fields;
+        self.selected = 0;
+        self.stage = Stage::Edit;
+        self.insert = None;
+        self.confirmed = false;
+        self.message = None;
+    }
+
+    pub fn close(&mut self) {
+        self.table = None;
+    }
+
+    /// Checks every field and builds the statement with its parameters. On
+    /// the first invalid field, that field is selected.
+    fn build(&mut self) -> Result<(String, Vec<BindValue>)> {
+        let (table, dialect) = match &self.table {
+            Some(table) => table,
+            None => anyhow::bail!("no table to insert into"),
+        };
+        let mut columns = vec![];
+        let mut params = vec![];
+        for (i, field) in self.fields.iter().enumerate() {
+            match field.value() {
+                Ok(Some(value)) => {
+                    columns.push((field.column.as_str(), field.type_name.as_str()));
+                    params.push(value);
+                }
+                Ok(None) => (),
+                Err(err) => {
+                    self.selected = i;
+                    return Err(err);
+                }
+            }
+        }
+        Ok((dml::insert(table, &columns, *dialect), params))
+    }
+
+    /// The insert, once it has been confirmed.
+    pub fn take_insert(&mut self) -> Option<(String, Vec<BindValue>)> {
+        if !self.confirmed {
+            return None;
+        }
+        self.confirmed = false;
+        self.stage = Stage::Edit;
+        self.insert.take()
+    }
+
+    /// A query selecting the inserted row by `key_columns` once MySQL has
+    /// inserted it. A key column left blank has to be the auto-increment one.
+    pub fn select_inserted(&self, key_columns: &[String]) -> Option<String> {
+        let (table, _) = self.table.as_ref()?;
+        if key_columns.is_empty() {
+            return None;
+        }
+        let mut key = vec![];
+        let mut generated = None;
+        for name in key_columns {
+            let field = self.fields.iter().find(|field| field.column == *name)?;
+            if field.null {
+                return None;
+            }
+            if field.input.is_empty() {
+                if !field.auto_increment || generated.is_some() {
+                    return None;
+                }
+                generated = Some(name.as_str());
+            } else {
+                key.push(ColumnValue::new(
+                    name,
+                    &field.type_name,
+                    Some(field.input.value_str()),
+                ));
+            }
+        }
+        Some(dml::select_inserted(table, &key, generated))
+    }
+
+    /// The names of all columns, in order
+    pub fn columns(&self) -> Vec<String> {
+        self.fields
+            .iter()
+            .map(|field| field.column.clone())
+            .collect()
+    }
+
+    /// The inserted row as shown until it is committed, in the order of
+    /// `headers`. Columns left blank are shown empty.
+    pub fn row(&self, headers: &[String]) -> Vec<String> {
+        headers
+            .iter()
+            .map(
+                |header| match self.fields.iter().find(|field| field.column == *header) {
+                    Some(field) if field.null => "NULL".to_string(),
+                    Some(field) => field.input.value_str().to_string(),
+                    None => String::new(),
+                },
+            )
+            .collect()
+    }
+
+    fn field_line<'a>(&self, i: usize, field: &'a FormField) -> Spans<'a> {
+        let selected = i == self.selected && self.stage == Stage::Edit;
+        let name_style = if selected {
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let placeholder = Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::ITALIC);
+        let mut spans = vec![
+            Span::styled(
+                format!(
+                    "{:<width$} ",
+                    truncate(&field.column, NAME_WIDTH),
+                    width = NAME_WIDTH
+                ),
+                name_style,
+            ),
+            Span::styled(
+                format!(
+                    "{:<width$} ",
+                    truncate(&field.type_name, TYPE_WIDTH),
+                    width = TYPE_WIDTH
+                ),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+        if field.null {
+            spans.push(Span::styled("NULL", placeholder));
+        } else if field.input.is_empty() {
+            spans.push(Span::styled(field.placeholder(), placeholder));
+        } else {
+            spans.extend(field.input.spans());
+        }
+        Spans::from(spans)
+    }
+
+    fn preview_lines(&self) -> Vec<Spans<'_>> {
+        let (statement, params) = match &self.insert {
+            Some(insert) => insert,
+            None => return vec![],
+        };
+        let mut lines = vec![
+            Spans::from(Span::styled(
+                statement.as_str(),
+                Style::default().fg(Color::Yellow),
+            )),
+            Spans::from(""),
+        ];
+        for (i, param) in params.iter().enumerate() {
+            let value = match param {
+                BindValue::Null => "NULL".to_string(),
+                BindValue::Text(value) => format!("'{}'", value),
+                BindValue::Integer(value) => value.to_string(),
+                BindValue::Float(value) => value.to_string(),
+                BindValue::Boolean(value) => value.to_string(),
+            };
+            lines.push(Spans::from(vec![
+                Span::styled(
+                    format!("{:>3} ", i + 1),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(value),
+            ]));
+        }
+        lines
+    }
+}
+
+impl DrawableComponent for InsertRowComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        let table = match &self.table {
+            Some((table, _)) => table,
+            None => return Ok(()),
+        };
+        let width = WIDTH.min(f.size().width.saturating_sub(4));
+        let height = (self.fields.len() as u16 + 6).min(f.size().height.saturating_sub(2));
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+        // Room for the fields, leaving the message and hint lines
+        let rows = height.saturating_sub(6).max(1) as usize;
+
+        let mut lines = match self.stage {
+            Stage::Edit => {
+                let start = (self.selected + 1).saturating_sub(rows);
+                self.fields
+                    .iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(rows)
+                    .map(|(i, field)| self.field_line(i, field))
+                    .collect()
+            }
+            Stage::Preview => self.preview_lines(),
+        };
+        lines.push(Spans::from(""));
+        lines.push(Spans::from(Span::styled(
+            self.message.clone().unwrap_or_default(),
+            Style::default().fg(Color::Red),
+        )));
+        let hint = match self.stage {
+            Stage::Edit => format!(
+                "{}/{} move, {} NULL, {} preview, {} cancel",
+                self.key_config.move_up,
+                self.key_config.move_down,
+                self.key_config.set_null,
+                self.key_config.enter,
+                self.key_config.exit_popup
+            ),
+            Stage::Preview => format!(
+                "{} stage, {} back",
+                self.key_config.enter, self.key_config.exit_popup
+            ),
+        };
+        lines.push(Spans::from(Span::styled(
+            hint,
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let mut paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Insert into {}", table)),
+        );
+        if self.stage == Stage::Preview {
+            paragraph = paragraph.wrap(Wrap { trim: false });
+        }
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+
+        if let Some(field) = self.fields.get(self.selected) {
+            if self.stage == Stage::Edit && !field.null {
+                let row = self.selected - (self.selected + 1).saturating_sub(rows);
+                f.set_cursor(
+                    (area.x + 1 + (NAME_WIDTH + TYPE_WIDTH + 2) as u16)
+                        .saturating_add(field.input.cursor_position())
+                        .min(area.right().saturating_sub(2)),
+                    area.y + 1 + row as u16,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Component for InsertRowComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::insert_row(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.table.is_none() {
+            return Ok(EventState::NotConsumed);
+        }
+        match self.stage {
+            Stage::Edit => {
+                self.message = None;
+                if key == self.key_config.exit_popup {
+                    self.close();
+                } else if key == self.key_config.enter {
+                    match self.build() {
+                        Ok(insert) => {
+                            self.insert = Some(insert);
+                            self.stage = Stage::Preview;
+                        }
+                        Err(err) => self.message = Some(err.to_string()),
+                    }
+                } else if key == self.key_config.move_down || key == Key::Tab {
+                    if self.selected + 1 < self.fields.len() {
+                        self.selected += 1;
+                    }
+                } else if key == self.key_config.move_up {
+                    self.selected = self.selected.saturating_sub(1);
+                } else if let Some(field) = self.fields.get_mut(self.selected) {
+                    if key == self.key_config.set_null {
+                        if field.nullable {
+                            field.null = !field.null;
+                        } else {
+                            self.message = Some(format!("{} can't be NULL", field.column));
+                        }
+                    } else if field.null {
+                        // Typing replaces NULL with text.
+                        if let Key::Char(_) = key {
+                            field.null = false;
+                            field.input.reset();
+                            field.input.event(key)?;
+                        }
+                    } else {
+                        field.input.event(key)?;
+                    }
+                }
+            }
+            Stage::Preview => {
+                if key == self.key_config.enter {
+                    self.confirmed = true;
+                } else if key == self.key_config.exit_popup {
+                    self.stage = Stage::Edit;
+                }
+            }
+        }
+        Ok(EventState::Consumed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        BindValue, Component as _, Dialect, FormField, Input, InsertRowComponent, KeyConfig,
+    };
+    use crate::database::TableRow;
+    use crate::event::Key;
+
+    struct Column(&'static str, &'static str, &'static str, &'static str);
+
+    impl TableRow for Column {
+        fn fields(&self) -> Vec<String> {
+            vec![
+                "name".to_string(),
+                "type".to_string(),
+                "null".to_string(),
+                "default".to_string(),
+            ]
+        }
+
+        fn columns(&self) -> Vec<String> {
+            vec![
+                self.0.to_string(),
+                self.1.to_string(),
+                self.2.to_string(),
+                self.3.to_string(),
+            ]
+        }
+    }
+
+    fn form() -> InsertRowComponent {
+        let mut form = InsertRowComponent::new(KeyConfig::default());
+        form.open(
+            "\"users\"".to_string(),
+            Dialect::Postgres,
+            [
+                Column("id", "integer", "NO", "nextval('users_id_seq'::regclass)"),
+                Column("name", "text", "NO", ""),
+                Column("age", "integer", "YES", ""),
+            ]
+            .iter()
+            .filter_map(|column| FormField::from_column(column))
+            .collect(),
+        );
+        form
+    }
+
+    fn type_in(form: &mut InsertRowComponent, text: &str) {
+        for c in text.chars() {
+            form.event(Key::Char(c)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let key_config = KeyConfig::default();
+        let mut form = form();
+        form.event(key_config.enter).unwrap();
+        assert_eq!(form.message.as_deref(), Some("name needs a value"));
+        assert_eq!(form.selected, 1);
+
+        type_in(&mut form, "bob");
+        form.event(key_config.move_down).unwrap();
+        type_in(&mut form, "old");
+        form.event(key_config.enter).unwrap();
+        assert_eq!(
+            form.message.as_deref(),
+            Some("age: `old` is not a valid integer")
+        );
+        assert_eq!(form.selected, 2);
+        assert!(form.insert.is_none());
+    }
+
+    #[test]
+    fn test_insert() {
+        let key_config = KeyConfig::default();
+        let mut form = form();
+        form.event(key_config.set_null).unwrap();
+        assert_eq!(form.message.as_deref(), Some("id can't be NULL"));
+
+        form.event(key_config.move_down).unwrap();
+        type_in(&mut form, "bob");
+        form.event(key_config.move_down).unwrap();
+        form.event(key_config.set_null).unwrap();
+        form.event(key_config.enter).unwrap();
+        assert!(form.take_insert().is_none());
+
+        form.event(key_config.enter).unwrap();
+        assert_eq!(
+            form.take_insert(),
+            Some((
+                "INSERT INTO \"users\" (\"name\", \"age\") VALUES ($1::text, $2::integer) RETURNING *"
+                    .to_string(),
+                vec![BindValue::Text("bob".to_string()), BindValue::Null]
+            ))
+        );
+
+        assert_eq!(
+            form.row(&["name".to_string(), "age".to_string(), "id".to_string()]),
+            vec!["bob".to_string(), "NULL".to_string(), String::new()]
+        );
+    }
+
+    #[test]
+    fn test_select_inserted() {
+        let key_config = KeyConfig::default();
+        let field = |column: &str, auto_increment: bool| FormField {
+            column: column.to_string(),
+            type_name: "int(11)".to_string(),
+            nullable: false,
+            default: None,
+            auto_increment,
+            input: Input::new(),
+            null: false,
+        };
+        let mut form = InsertRowComponent::new(key_config.clone());
+        form.open(
+            "`db`.`orders`".to_string(),
+            Dialect::MySql,
+            vec![field("id", true), field("shop", false)],
+        );
+        let key = ["id".to_string(), "shop".to_string()];
+        assert_eq!(form.select_inserted(&key), None);
+
+        form.event(key_config.move_down).unwrap();
+        type_in(&mut form, "3");
+        assert_eq!(
+            form.select_inserted(&key),
+            Some(
+                "SELECT * FROM `db`.`orders` WHERE `shop` = 3 AND `id` = LAST_INSERT_ID()"
+                    .to_string()
+            )
+        );
+        assert_eq!(form.select_inserted(&[]), None);
+    }
+}
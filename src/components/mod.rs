@@ -1,38 +1,62 @@
+pub mod bind_params;
+pub mod cell_editor;
+pub mod changes;
+pub mod column_form;
+pub mod column_stats;
 pub mod command;
 pub mod completion;
+pub mod confirm;
 pub mod connections;
+pub mod create_table;
 pub mod database_filter;
 pub mod databases;
 pub mod error;
 pub mod help;
+pub mod index_form;
+pub mod insert_row;
+pub mod plan;
 pub mod properties;
 pub mod record_table;
+pub mod references;
 pub mod sql_editor;
 pub mod tab;
 pub mod table;
 pub mod table_filter;
 pub mod table_status;
 pub mod table_value;
+pub mod tree_menu;
 pub mod utils;
 
 #[cfg(debug_assertions)]
 pub mod debug;
 
+pub use bind_params::BindParamsComponent;
+pub use cell_editor::CellEditorComponent;
+pub use changes::ChangesComponent;
+pub use column_form::ColumnFormComponent;
+pub use column_stats::ColumnStatsComponent;
 pub use command::{CommandInfo, CommandText};
 pub use completion::CompletionComponent;
+pub use confirm::ConfirmComponent;
 pub use connections::ConnectionsComponent;
+pub use create_table::CreateTableComponent;
 pub use database_filter::DatabaseFilterComponent;
 pub use databases::DatabasesComponent;
 pub use error::ErrorComponent;
 pub use help::HelpComponent;
+pub use index_form::IndexFormComponent;
+pub use insert_row::InsertRowComponent;
+pub use plan::PlanComponent;
 pub use properties::PropertiesComponent;
 pub use record_table::RecordTableComponent;
+pub use references::ReferencesComponent;
 pub use sql_editor::SqlEditorComponent;
 pub use tab::TabComponent;
 pub use table::TableComponent;
 pub use table_filter::TableFilterComponent;
 pub use table_status::TableStatusComponent;
 pub use table_value::TableValueComponent;
+pub use tree_menu::TreeMenuComponent;
 
 #[cfg(debug_assertions)]
 pub use debug::DebugComponent;
@@ -40,9 +64,7 @@ pub use debug::DebugComponent;
 use crate::database::Pool;
 use anyhow::Result;
 use async_trait::async_trait;
-use std::convert::TryInto;
 use tui::{backend::Backend, layout::Rect, Frame};
-use unicode_width::UnicodeWidthChar;
 
 #[derive(PartialEq, Debug)]
 pub enum EventState {
@@ -125,7 +147,3 @@ pub trait Component {
         }
     }
 }
-
-fn compute_character_width(c: char) -> u16 {
-    UnicodeWidthChar::width(c).unwrap().try_into().unwrap()
-}
@@ -0,0 +1,234 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::CommandInfo;
+use crate::config::KeyConfig;
+use crate::database::PlanNode;
+use crate::event::Key;
+use anyhow::Result;
+use std::collections::HashSet;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+/// Shows a query plan as a tree whose nodes can be collapsed.
+pub struct PlanComponent {
+    root: Option<PlanNode>,
+    analyzed: bool,
+    /// Collapsed nodes, as the child indices leading to them from the root
+    collapsed: HashSet<Vec<usize>>,
+    selected: usize,
+    key_config: KeyConfig,
+}
+
+impl PlanComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            root: None,
+            analyzed: false,
+            collapsed: HashSet::new(),
+            selected: 0,
+            key_config,
+        }
+    }
+
+    pub fn update(&mut self, root: PlanNode, analyzed: bool) {
+        self.root = Some(root);
+        self.analyzed = analyzed;
+        self.collapsed.clear();
+        self.selected = 0;
+    }
+
+    /// The nodes that are not hidden in a collapsed one, with their paths
+    fn visible_nodes(&self) -> Vec<(Vec<usize>, &PlanNode)> {
+        fn walk<'a>(
+            node: &'a PlanNode,
+            path: Vec<usize>,
+            collapsed: &HashSet<Vec<usize>>,
+            out: &mut Vec<(Vec<usize>, &'a PlanNode)>,
+        ) {
+            let expanded = !collapsed.contains(&path);
+            out.push((path.clone(), node));
+            if expanded {
+                for (i, child) in node.children.iter().enumerate() {
+                    let mut path = path.clone();
+                    path.push(i);
+                    walk(child, path, collapsed, out);
+                }
+            }
+        }
+
+        let mut nodes = vec![];
+        if let Some(root) = &self.root {
+            walk(root, vec![], &self.collapsed, &mut nodes);
+        }
+        nodes
+    }
+
+    fn selected_node(&self) -> Option<(Vec<usize>, bool)> {
+        self.visible_nodes()
+            .into_iter()
+            .nth(self.selected)
+            .map(|(path, node)| (path, node.children.is_empty()))
+    }
+
+    fn expand(&mut self) {
+        if let Some((path, _)) = self.selected_node() {
+            self.collapsed.remove(&path);
+        }
+    }
+
+    /// Collapses the selected node, or moves to its parent if there is
+    /// nothing to collapse.
+    fn collapse(&mut self) {
+        if let Some((path, leaf)) = self.selected_node() {
+            if !leaf && !self.collapsed.contains(&path) {
+                self.collapsed.insert(path);
+            } else if let Some((_, parent)) = path.split_last() {
+                if let Some(index) = self
+                    .visible_nodes()
+                    .iter()
+                    .position(|(path, _)| path == parent)
+                {
+                    self.selected = index;
+                }
+            }
+        }
+    }
+
+    fn next(&mut self, lines: usize) {
+        let last = self.visible_nodes().len().saturating_sub(1);
+        self.selected = (self.selected + lines).min(last);
+    }
+
+    fn previous(&mut self, lines: usize) {
+        self.selected = self.selected.saturating_sub(lines);
+    }
+}
+
+impl DrawableComponent for PlanComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, area: Rect, focused: bool) -> Result<()> {
+        let items = self
+            .visible_nodes()
+            .into_iter()
+            .map(|(path, node)| {
+                let marker = if node.children.is_empty() {
+                    " "
+                } else if self.collapsed.contains(&path) {
+                    "▸"
+                } else {
+                    "▾"
+                };
+                ListItem::new(Spans::from(vec![
+                    Span::raw(format!("{}{} ", "  ".repeat(path.len()), marker)),
+                    Span::styled(
+                        node.label.as_str(),
+                        if node.expensive {
+                            Style::default().fg(Color::Red)
+                        } else {
+                            Style::default()
+                        },
+                    ),
+                    Span::styled(
+                        format!("  {}", node.stats()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        let mut state = ListState::default();
+        state.select(Some(self.selected));
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(if self.analyzed {
+                        "Plan (analyzed)"
+                    } else {
+                        "Plan"
+                    })
+                    .style(if focused {
+                        Style::default()
+                    } else {
+                        Style::default().fg(Color::DarkGray)
+                    }),
+            )
+            .highlight_style(if focused {
+                Style::default().bg(Color::Blue)
+            } else {
+                Style::default().bg(Color::DarkGray)
+            });
+        f.render_stateful_widget(list, area, &mut state);
+        Ok(())
+    }
+}
+
+impl Component for PlanComponent {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            self.next(1);
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.previous(1);
+        } else if key == self.key_config.scroll_down_multiple_lines {
+            self.next(10);
+        } else if key == self.key_config.scroll_up_multiple_lines {
+            self.previous(10);
+        } else if key == self.key_config.scroll_to_top {
+            self.selected = 0;
+        } else if key == self.key_config.scroll_to_bottom {
+            self.next(usize::MAX / 2);
+        } else if key == self.key_config.scroll_right {
+            self.expand();
+        } else if key == self.key_config.scroll_left {
+            self.collapse();
+        } else {
+            return Ok(EventState::NotConsumed);
+        }
+        Ok(EventState::Consumed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Component as _, KeyConfig, PlanComponent};
+    use crate::database::plan::from_sqlite_rows;
+
+    #[test]
+    fn test_collapse_and_expand() {
+        let key_config = KeyConfig::default();
+        let mut plan = PlanComponent::new(key_config.clone());
+        plan.update(
+            from_sqlite_rows(&[
+                (1, 0, "SCAN a".to_string()),
+                (2, 1, "SCAN b".to_string()),
+                (3, 0, "SCAN c".to_string()),
+            ]),
+            false,
+        );
+        assert_eq!(plan.visible_nodes().len(), 4);
+
+        plan.event(key_config.scroll_down).unwrap();
+        plan.event(key_config.scroll_left).unwrap();
+        let labels = |plan: &PlanComponent| {
+            plan.visible_nodes()
+                .iter()
+                .map(|(_, node)| node.label.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(&plan), vec!["QUERY PLAN", "SCAN a", "SCAN c"]);
+
+        plan.event(key_config.scroll_right).unwrap();
+        plan.event(key_config.scroll_down).unwrap();
+        assert_eq!(plan.selected, 2);
+        plan.event(key_config.scroll_left).unwrap();
+        assert_eq!(plan.selected, 1);
+        plan.event(key_config.scroll_to_bottom).unwrap();
+        assert_eq!(plan.selected, 3);
+    }
+}
@@ -51,6 +51,17 @@ impl PropertiesComponent {
         }
     }
 
+    pub fn set_max_column_width(&mut self, width: usize) {
+        for table in [
+            &mut self.column_table,
+            &mut self.constraint_table,
+            &mut self.foreign_key_table,
+            &mut self.index_table,
+        ] {
+            table.set_max_column_width(width);
+        }
+    }
+
     fn focused_component(&mut self) -> &mut TableComponent {
         match self.focus {
             Focus::Column => &mut self.column_table,
@@ -60,6 +71,71 @@ impl PropertiesComponent {
         }
     }
 
+    /// The selected cells of the table shown.
+    pub fn selected_cells(&self) -> Option<String> {
+        let table = match self.focus {
+            Focus::Column => &self.column_table,
+            Focus::Constraint => &self.constraint_table,
+            Focus::ForeignKey => &self.foreign_key_table,
+            Focus::Index => &self.index_table,
+        };
+        if table.searching() {
+            return None;
+        }
+        table.selected_cells()
+    }
+
+    /// Whether the columns of the table are shown
+    pub fn columns_focused(&self) -> bool {
+        self.focus == Focus::Column
+    }
+
+    /// The name and type of the column selected in the columns tab
+    pub fn selected_column(&self) -> Option<(String, String)> {
+        if !self.columns_focused() {
+            return None;
+        }
+        let table = &self.column_table;
+        let row = table.rows.get(table.selected_row.selected()?)?;
+        let field = |name: &str| {
+            table
+                .headers
+                .iter()
+                .position(|header| header == name)
+                .and_then(|i| row.get(i).cloned())
+        };
+        Some((field("name")?, field("type").unwrap_or_default()))
+    }
+
+    /// Whether the indexes of the table are shown
+    pub fn indexes_focused(&self) -> bool {
+        self.focus == Focus::Index
+    }
+
+    /// The name of the index selected in the indexes tab
+    pub fn selected_index(&self) -> Option<String> {
+        if !self.indexes_focused() {
+            return None;
+        }
+        let table = &self.index_table;
+        let row = table.rows.get(table.selected_row.selected()?)?;
+        let name = table.headers.iter().position(|header| header == "name")?;
+        row.get(name).cloned().filter(|name| !name.is_empty())
+    }
+
+    /// The names of the columns of the table
+    pub fn column_names(&self) -> Vec<String> {
+        let table = &self.column_table;
+        match table.headers.iter().position(|header| header == "name") {
+            Some(name) => table
+                .rows
+                .iter()
+                .filter_map(|row| row.get(name).cloned())
+                .collect(),
+            None => vec![],
+        }
+    }
+
     pub async fn update(
         &mut self,
         database: Database,
@@ -177,9 +253,16 @@ impl Component for PropertiesComponent {
         out.push(CommandInfo::new(command::toggle_property_tabs(
             &self.key_config,
         )));
+        out.push(CommandInfo::new(command::alter_columns(&self.key_config)));
+        out.push(CommandInfo::new(command::create_or_drop_index(
+            &self.key_config,
+        )));
     }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.focused_component().searching() {
+            return self.focused_component().event(key);
+        }
         self.focused_component().event(key)?;
 
         if key == self.key_config.copy {
@@ -1,8 +1,9 @@
 use super::{Component, EventState, StatefulDrawableComponent};
-use crate::components::command::CommandInfo;
+use crate::components::command::{self, CommandInfo};
 use crate::components::{TableComponent, TableFilterComponent};
 use crate::config::KeyConfig;
 use crate::event::Key;
+use crate::sql::{quote, Dialect};
 use anyhow::Result;
 use database_tree::{Database, Table as DTable};
 use tui::{
@@ -52,6 +53,41 @@ impl RecordTableComponent {
     pub fn filter_focused(&self) -> bool {
         matches!(self.focus, Focus::Filter)
     }
+
+    /// Adds a condition matching the selected cell's value, or everything
+    /// but it, to the filter. Returns whether there was a cell to filter by.
+    pub fn add_quick_filter(&mut self, negate: bool, dialect: Dialect) -> bool {
+        match self.table.selected_field() {
+            Some((column, value, type_name)) => {
+                let condition = quick_filter_condition(column, value, type_name, negate, dialect);
+                self.filter.add_condition(&condition);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// `NULL` is how null values are shown, so such cells are matched with
+/// `IS NULL` as `=` would never hold.
+pub fn quick_filter_condition(
+    column: &str,
+    value: &str,
+    type_name: &str,
+    negate: bool,
+    dialect: Dialect,
+) -> String {
+    let column = quote::identifier(column, dialect);
+    match (value, negate) {
+        ("NULL", false) => format!("{} IS NULL", column),
+        ("NULL", true) => format!("{} IS NOT NULL", column),
+        (value, false) => format!("{} = {}", column, quote::literal(value, type_name, dialect)),
+        (value, true) => format!(
+            "{} <> {}",
+            column,
+            quote::literal(value, type_name, dialect)
+        ),
+    }
 }
 
 impl StatefulDrawableComponent for RecordTableComponent {
@@ -72,10 +108,18 @@ impl StatefulDrawableComponent for RecordTableComponent {
 
 impl Component for RecordTableComponent {
     fn commands(&self, out: &mut Vec<CommandInfo>) {
-        self.table.commands(out)
+        self.table.commands(out);
+        out.push(CommandInfo::new(command::quick_filter(&self.key_config)));
+        out.push(CommandInfo::new(command::delete_rows(&self.key_config)));
+        out.push(CommandInfo::new(command::follow_foreign_key(
+            &self.key_config,
+        )));
     }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
+        if matches!(self.focus, Focus::Table) && self.table.searching() {
+            return self.table.event(key);
+        }
         if key == self.key_config.filter {
             self.focus = Focus::Filter;
             return Ok(EventState::Consumed);
@@ -88,3 +132,28 @@ impl Component for RecordTableComponent {
         Ok(EventState::NotConsumed)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{quick_filter_condition, Dialect};
+
+    #[test]
+    fn test_quick_filter_condition() {
+        assert_eq!(
+            quick_filter_condition("id", "42", "int(11)", false, Dialect::MySql),
+            "`id` = 42"
+        );
+        assert_eq!(
+            quick_filter_condition("name", "O'Brien", "text", true, Dialect::Postgres),
+            "\"name\" <> 'O''Brien'"
+        );
+        assert_eq!(
+            quick_filter_condition("deleted_at", "NULL", "timestamp", false, Dialect::Sqlite),
+            "\"deleted_at\" IS NULL"
+        );
+        assert_eq!(
+            quick_filter_condition("deleted_at", "NULL", "timestamp", true, Dialect::Sqlite),
+            "\"deleted_at\" IS NOT NULL"
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use super::{Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::event::Key;
+use anyhow::Result;
+use database_tree::Table;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState},
+    Frame,
+};
+
+const WIDTH: u16 = 60;
+
+/// The rows of a table whose foreign key references a row
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferencingRows {
+    pub table: Table,
+    /// The columns of the foreign key
+    pub columns: Vec<String>,
+    /// The filter that shows the rows in the record table
+    pub filter: String,
+    pub rows: i64,
+}
+
+/// Lists the tables with rows referencing the selected row, to open one of
+/// them filtered to those rows.
+pub struct ReferencesComponent {
+    table: String,
+    references: Vec<ReferencingRows>,
+    state: ListState,
+    visible: bool,
+    selected: bool,
+    key_config: KeyConfig,
+}
+
+impl ReferencesComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            table: String::new(),
+            references: vec![],
+            state: ListState::default(),
+            visible: false,
+            selected: false,
+            key_config,
+        }
+    }
+
+    pub fn open(&mut self, table: String, references: Vec<ReferencingRows>) {
+        self.table = table;
+        self.state
+            .select(if references.is_empty() { None } else { Some(0) });
+        self.references = references;
+        self.visible = true;
+        self.selected = false;
+    }
+
+    /// The rows chosen to open, if any. The list is closed then.
+    pub fn take_selected(&mut self) -> Option<ReferencingRows> {
+        if !self.selected {
+            return None;
+        }
+        self.selected = false;
+        self.hide();
+        self.references.get(self.state.selected()?).cloned()
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        if let Some(selected) = self.state.selected() {
+            self.state.select(Some(if down {
+                (selected + 1).min(self.references.len() - 1)
+            } else {
+                selected.saturating_sub(1)
+            }));
+        }
+    }
+
+    fn label(reference: &ReferencingRows) -> String {
+        let table = match &reference.table.schema {
+            Some(schema) => format!("{}.{}", schema, reference.table.name),
+            None => reference.table.name.clone(),
+        };
+        match reference.columns.as_slice() {
+            [column] => format!("{}.{}", table, column),
+            columns => format!("{}.({})", table, columns.join(", ")),
+        }
+    }
+}
+
+impl DrawableComponent for ReferencesComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let width = WIDTH.min(f.size().width);
+        let inner_width = width.saturating_sub(2) as usize;
+        let items = if self.references.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "No foreign keys reference this table",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            self.references
+                .iter()
+                .map(|reference| {
+                    let count = match reference.rows {
+                        1 => "1 row".to_string(),
+                        rows => format!("{} rows", rows),
+                    };
+                    let label = Self::label(reference);
+                    ListItem::new(Spans::from(vec![
+                        Span::raw(format!(
+                            "{:<width$}",
+                            label,
+                            width = inner_width.saturating_sub(count.len() + 1)
+                        )),
+                        Span::styled(
+                            format!(" {}", count),
+                            Style::default().fg(if reference.rows == 0 {
+                                Color::DarkGray
+                            } else {
+                                Color::Reset
+                            }),
+                        ),
+                    ]))
+                })
+                .collect()
+        };
+        let height = (items.len() as u16 + 2).min(f.size().height);
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+        let title = format!(
+            "Rows referencing {} [{} open, {} close]",
+            self.table, self.key_config.enter, self.key_config.exit_popup
+        );
+        let mut state = self.state.clone();
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .highlight_style(
+                    Style::default()
+                        .bg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            area,
+            &mut state,
+        );
+        Ok(())
+    }
+}
+
+impl Component for ReferencesComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::show_references(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit_popup {
+            self.hide();
+        } else if key == self.key_config.enter {
+            self.selected = true;
+        } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            self.move_selection(true);
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.move_selection(false);
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Component as _, KeyConfig, ReferencesComponent, ReferencingRows, Table};
+
+    fn references(table: &str, rows: i64) -> ReferencingRows {
+        ReferencingRows {
+            table: Table {
+                name: table.to_string(),
+                create_time: None,
+                update_time: None,
+                engine: None,
+                schema: None,
+            },
+            columns: vec!["customer_id".to_string()],
+            filter: "\"customer_id\" = 1".to_string(),
+            rows,
+        }
+    }
+
+    #[test]
+    fn test_take_selected() {
+        let key_config = KeyConfig::default();
+        let mut component = ReferencesComponent::new(key_config.clone());
+        component.open(
+            "customers".to_string(),
+            vec![references("orders", 2), references("invoices", 0)],
+        );
+        component.event(key_config.move_down).unwrap();
+        component.event(key_config.move_down).unwrap();
+        assert_eq!(component.take_selected(), None);
+
+        component.event(key_config.enter).unwrap();
+        assert_eq!(component.take_selected(), Some(references("invoices", 0)));
+        assert!(!component.visible);
+
+        component.open("customers".to_string(), vec![]);
+        component.event(key_config.enter).unwrap();
+        assert_eq!(component.take_selected(), None);
+    }
+
+    #[test]
+    fn test_label() {
+        let mut reference = references("orders", 2);
+        assert_eq!(ReferencesComponent::label(&reference), "orders.customer_id");
+
+        reference.columns.push("store_id".to_string());
+        assert_eq!(
+            ReferencesComponent::label(&reference),
+            "orders.(customer_id, store_id)"
+        );
+    }
+}
@@ -1,30 +1,63 @@
 use super::{
-    compute_character_width, CompletionComponent, Component, EventState, MovableComponent,
-    StatefulDrawableComponent, TableComponent,
+    utils::input::Input, BindParamsComponent, CompletionComponent, Component, DrawableComponent,
+    EventState, MovableComponent, PlanComponent, StatefulDrawableComponent, TableComponent,
 };
-use crate::components::command::CommandInfo;
-use crate::config::KeyConfig;
-use crate::database::{ExecuteResult, Pool};
+use crate::components::command::{self, CommandInfo};
+use crate::config::{KeyConfig, DEFAULT_MAX_COLUMN_WIDTH};
+use crate::database::{BindValue, ExecuteResult, Pool, QueryError};
 use crate::event::Key;
+use crate::history::History;
+use crate::saved_tabs::{SavedTab, SavedTabs};
+use crate::sql::{
+    self,
+    token::{tokenize, TokenKind},
+    Dialect,
+};
 use crate::ui::stateful_paragraph::{ParagraphState, StatefulParagraph};
 use anyhow::Result;
 use async_trait::async_trait;
+use std::ops::Range;
+use std::time::{Duration, Instant};
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
 use unicode_width::UnicodeWidthStr;
 
+const HIGHLIGHT_DURATION: Duration = Duration::from_millis(500);
+
+const MAX_MESSAGES_HEIGHT: u16 = 8;
+
+enum QueryStatus {
+    Read { rows: usize },
+    Write { updated_rows: u64 },
+    Explain,
+}
+
 struct QueryResult {
-    updated_rows: u64,
+    status: QueryStatus,
+    elapsed: Duration,
+    /// Notices and warnings the server sent
+    messages: Vec<String>,
 }
 
 impl QueryResult {
     fn result_str(&self) -> String {
-        format!("Query OK, {} row affected", self.updated_rows)
+        let elapsed = self.elapsed.as_secs_f64();
+        match self.status {
+            QueryStatus::Read { rows } => format!("{} rows in set ({:.3} sec)", rows, elapsed),
+            QueryStatus::Explain => format!("Query plan ({:.3} sec)", elapsed),
+            QueryStatus::Write { updated_rows } => {
+                format!(
+                    "Query OK, {} row affected ({:.3} sec)",
+                    updated_rows, elapsed
+                )
+            }
+        }
     }
 }
 
@@ -33,253 +66,721 @@ pub enum Focus {
     Table,
 }
 
-pub struct SqlEditorComponent {
-    input: Vec<char>,
-    input_cursor_position_x: u16,
-    input_idx: usize,
+/// What to do with a statement once its parameters are bound
+#[derive(Clone, Copy)]
+enum Execution {
+    Run,
+    Explain { analyze: bool },
+}
+
+/// A named editor buffer with its own results.
+struct EditorTab {
+    name: String,
+    input: Input,
     table: TableComponent,
+    plan: PlanComponent,
     query_result: Option<QueryResult>,
-    completion: CompletionComponent,
-    key_config: KeyConfig,
     paragraph_state: ParagraphState,
     focus: Focus,
+    /// The statement that was last run and when, so that it can be flashed
+    highlight: Option<(Range<usize>, Instant)>,
+    /// Where the database reported the last error, kept until the text changes
+    error: Option<Range<usize>>,
+}
+
+impl EditorTab {
+    fn new(name: String, query: &str, key_config: KeyConfig) -> Self {
+        Self {
+            name,
+            input: Input::from(query),
+            table: TableComponent::new(key_config.clone()),
+            plan: PlanComponent::new(key_config),
+            query_result: None,
+            paragraph_state: ParagraphState::default(),
+            focus: Focus::Editor,
+            highlight: None,
+            error: None,
+        }
+    }
+
+    async fn run(&mut self, query: &str, params: &[BindValue], pool: &dyn Pool) -> Result<()> {
+        let started = Instant::now();
+        let result = pool.execute(query, params).await?;
+        let elapsed = started.elapsed();
+        self.query_result = Some(match result {
+            ExecuteResult::Read {
+                headers,
+                types,
+                rows,
+                database,
+                table,
+                messages,
+            } => {
+                let status = QueryStatus::Read { rows: rows.len() };
+                self.table.update(rows, headers, database, table);
+                self.table.set_column_types(types);
+                self.focus = Focus::Table;
+                QueryResult {
+                    status,
+                    elapsed,
+                    messages,
+                }
+            }
+            ExecuteResult::Write {
+                updated_rows,
+                messages,
+            } => QueryResult {
+                status: QueryStatus::Write { updated_rows },
+                elapsed,
+                messages,
+            },
+        });
+        Ok(())
+    }
+
+    async fn explain(
+        &mut self,
+        query: &str,
+        params: &[BindValue],
+        analyze: bool,
+        pool: &dyn Pool,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let plan = pool.explain(query, params, analyze).await?;
+        self.plan.update(plan, analyze);
+        self.focus = Focus::Table;
+        self.query_result = Some(QueryResult {
+            status: QueryStatus::Explain,
+            elapsed: started.elapsed(),
+            messages: vec![],
+        });
+        Ok(())
+    }
+
+    fn showing_plan(&self) -> bool {
+        matches!(
+            self.query_result,
+            Some(QueryResult {
+                status: QueryStatus::Explain,
+                ..
+            })
+        )
+    }
+
+    /// Marks the token `err` was found at and moves the cursor there.
+    /// `executed` is what was sent for the statement last run, which may differ
+    /// from the editor text in its placeholders, so the position is mapped
+    /// token by token.
+    fn show_error(&mut self, err: &QueryError, executed: &str, dialect: Dialect) {
+        let range = match &self.highlight {
+            Some((range, _)) => range.clone(),
+            None => return,
+        };
+        let leading = executed.len() - executed.trim_start().len();
+        let offset = match err.offset(executed.trim()) {
+            Some(offset) => leading + offset,
+            None => return,
+        };
+        let statement = &self.input.value_str()[range.clone()];
+        let executed_tokens = tokenize(executed, dialect);
+        let tokens = tokenize(statement, dialect);
+        let error = executed_tokens
+            .iter()
+            .position(|t| {
+                !matches!(t.kind, TokenKind::Whitespace | TokenKind::Comment) && t.end() > offset
+            })
+            .filter(|_| tokens.len() == executed_tokens.len())
+            .and_then(|index| tokens.get(index))
+            .map_or_else(
+                || {
+                    let end = statement.trim_end().len();
+                    end..end
+                },
+                |token| token.offset..token.end(),
+            );
+
+        let error = range.start + error.start..range.start + error.end;
+        self.input.set_cursor(error.start);
+        self.error = Some(error);
+        self.highlight = None;
+        self.focus = Focus::Editor;
+    }
+
+    /// Position of the cursor relative to the editor's inner area, wrapping
+    /// lines longer than `width`.
+    fn cursor_position(&self, width: u16) -> (u16, u16) {
+        if width == 0 {
+            return (0, 0);
+        }
+        let before_cursor = &self.input.value_str()[..self.input.cursor()];
+        let y = before_cursor
+            .split('\n')
+            .rev()
+            .skip(1)
+            .map(|line| (line.width() as u16 / width) + 1)
+            .sum::<u16>();
+        let x = self.input.cursor_position();
+        (x % width, y + x / width)
+    }
+}
+
+pub struct SqlEditorComponent {
+    tabs: Vec<EditorTab>,
+    selected_tab: usize,
+    /// The new name of the selected tab while it is being renamed
+    rename_input: Option<Input>,
+    /// Identifies the connection the tabs are saved for
+    connection: Option<String>,
+    saved_tabs: SavedTabs,
+    completion: CompletionComponent,
+    key_config: KeyConfig,
+    bind_params: BindParamsComponent,
+    history: History,
+    /// What to do with the query in `bind_params` once it is confirmed
+    pending: Execution,
+    /// Whether server messages are listed under the result table
+    show_messages: bool,
+    max_column_width: usize,
 }
 
 impl SqlEditorComponent {
     pub fn new(key_config: KeyConfig) -> Self {
         Self {
-            input: Vec::new(),
-            input_idx: 0,
-            input_cursor_position_x: 0,
-            table: TableComponent::new(key_config.clone()),
+            tabs: vec![EditorTab::new(
+                "Query 1".to_string(),
+                "",
+                key_config.clone(),
+            )],
+            selected_tab: 0,
+            rename_input: None,
+            connection: None,
+            saved_tabs: SavedTabs::new(),
             completion: CompletionComponent::new(key_config.clone(), "", true),
-            focus: Focus::Editor,
-            paragraph_state: ParagraphState::default(),
-            query_result: None,
+            bind_params: BindParamsComponent::new(key_config.clone()),
+            history: History::new(),
+            pending: Execution::Run,
+            show_messages: true,
+            max_column_width: DEFAULT_MAX_COLUMN_WIDTH,
             key_config,
         }
     }
 
-    fn update_completion(&mut self) {
-        let input = &self
-            .input
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| i < &self.input_idx)
-            .map(|(_, i)| i)
-            .collect::<String>()
-            .split(' ')
-            .map(|i| i.to_string())
-            .collect::<Vec<String>>();
-        self.completion
-            .update(input.last().unwrap_or(&String::new()));
+    fn tab(&self) -> &EditorTab {
+        &self.tabs[self.selected_tab]
     }
 
-    fn complete(&mut self) -> anyhow::Result<EventState> {
-        if let Some(candidate) = self.completion.selected_candidate() {
-            let mut input = Vec::new();
-            let first = self
-                .input
-                .iter()
-                .enumerate()
-                .filter(|(i, _)| i < &self.input_idx.saturating_sub(self.completion.word().len()))
-                .map(|(_, c)| c.to_string())
-                .collect::<Vec<String>>();
-            let last = self
-                .input
+    fn tab_mut(&mut self) -> &mut EditorTab {
+        &mut self.tabs[self.selected_tab]
+    }
+
+    /// Saves the tabs of the previous connection and restores the ones last
+    /// used with `connection`.
+    pub fn set_connection(&mut self, connection: String) -> Result<()> {
+        self.save_tabs()?;
+        let saved = self.saved_tabs.get(&connection);
+        self.tabs = saved
+            .into_iter()
+            .map(|tab| EditorTab::new(tab.name, &tab.query, self.key_config.clone()))
+            .collect();
+        if self.tabs.is_empty() {
+            self.tabs.push(EditorTab::new(
+                "Query 1".to_string(),
+                "",
+                self.key_config.clone(),
+            ));
+        }
+        self.selected_tab = 0;
+        self.rename_input = None;
+        self.connection = Some(connection);
+        Ok(())
+    }
+
+    pub fn set_max_column_width(&mut self, width: usize) {
+        self.max_column_width = width;
+    }
+
+    pub fn save_tabs(&mut self) -> Result<()> {
+        if let Some(connection) = &self.connection {
+            let tabs = self
+                .tabs
                 .iter()
-                .enumerate()
-                .filter(|(i, _)| i >= &self.input_idx)
-                .map(|(_, c)| c.to_string())
-                .collect::<Vec<String>>();
-
-            let is_last_word = last.first().map_or(false, |c| c == &" ".to_string());
-
-            let middle = if is_last_word {
-                candidate
-                    .chars()
-                    .map(|c| c.to_string())
-                    .collect::<Vec<String>>()
-            } else {
-                let mut c = candidate
-                    .chars()
-                    .map(|c| c.to_string())
-                    .collect::<Vec<String>>();
-                c.push(" ".to_string());
-                c
-            };
+                .map(|tab| SavedTab {
+                    name: tab.name.clone(),
+                    query: tab.input.value_str().to_string(),
+                })
+                .collect();
+            self.saved_tabs.set(connection, tabs)?;
+        }
+        Ok(())
+    }
 
-            input.extend(first);
-            input.extend(middle.clone());
-            input.extend(last);
+    fn new_tab(&mut self) -> Result<()> {
+        let name = (1..)
+            .map(|n| format!("Query {}", n))
+            .find(|name| self.tabs.iter().all(|tab| tab.name != *name))
+            .unwrap();
+        self.tabs
+            .push(EditorTab::new(name, "", self.key_config.clone()));
+        self.selected_tab = self.tabs.len() - 1;
+        self.save_tabs()
+    }
 
-            self.input = input.join("").chars().collect();
-            self.input_idx += &middle.len();
-            if is_last_word {
-                self.input_idx += 1;
-            }
-            self.input_idx -= self.completion.word().len();
-            self.input_cursor_position_x += middle
-                .join("")
-                .chars()
-                .map(compute_character_width)
-                .sum::<u16>();
-            if is_last_word {
-                self.input_cursor_position_x += " ".to_string().width() as u16
-            }
-            self.input_cursor_position_x -= self
-                .completion
-                .word()
-                .chars()
-                .map(compute_character_width)
-                .sum::<u16>();
+    fn close_tab(&mut self) -> Result<()> {
+        if self.tabs.len() == 1 {
+            self.tabs[0] = EditorTab::new(self.tabs[0].name.clone(), "", self.key_config.clone());
+        } else {
+            self.tabs.remove(self.selected_tab);
+            self.selected_tab = self.selected_tab.min(self.tabs.len() - 1);
+        }
+        self.save_tabs()
+    }
+
+    fn update_completion(&mut self) {
+        let word = self.tab().input.word_before_cursor().to_string();
+        self.completion.update(word);
+    }
+
+    fn complete(&mut self) -> anyhow::Result<EventState> {
+        if let Some(candidate) = self.completion.selected_candidate() {
+            let word = self.completion.word();
+            self.tab_mut().input.complete(&word, &candidate);
             self.update_completion();
             return Ok(EventState::Consumed);
         }
         Ok(EventState::NotConsumed)
     }
+
+    fn tab_titles(&self) -> Spans<'_> {
+        let mut spans = vec![];
+        for (i, tab) in self.tabs.iter().enumerate() {
+            if i == self.selected_tab {
+                match &self.rename_input {
+                    Some(input) => {
+                        spans.push(Span::raw(" "));
+                        spans.extend(input.spans());
+                        spans.push(Span::raw(" "));
+                    }
+                    None => spans.push(Span::styled(
+                        format!(" {} ", tab.name),
+                        Style::default().fg(Color::Blue),
+                    )),
+                }
+            } else {
+                spans.push(Span::styled(
+                    format!(" {} ", tab.name),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+        }
+        Spans::from(spans)
+    }
+
+    /// Runs or explains `query` in the current tab, pointing at the error in
+    /// the editor if the database says where it is.
+    async fn execute(
+        &mut self,
+        query: &str,
+        params: &[BindValue],
+        execution: Execution,
+        pool: &dyn Pool,
+    ) -> Result<()> {
+        let max_column_width = self.max_column_width;
+        let tab = self.tab_mut();
+        tab.error = None;
+        tab.table.set_max_column_width(max_column_width);
+        match execution {
+            Execution::Run => {
+                let result = tab.run(query, params, pool).await;
+                if let Err(err) = &result {
+                    if let Some(err) = err.downcast_ref::<QueryError>() {
+                        tab.show_error(err, query, pool.dialect());
+                    }
+                }
+                result
+            }
+            // Errors point into the EXPLAIN statement, not the editor text.
+            Execution::Explain { analyze } => tab.explain(query, params, analyze, pool).await,
+        }
+    }
+
+    /// The selected cells of the result table, if it has focus.
+    pub fn selected_cells(&self) -> Option<String> {
+        let tab = self.tab();
+        if matches!(tab.focus, Focus::Table) && !tab.showing_plan() && !tab.table.searching() {
+            tab.table.selected_cells()
+        } else {
+            None
+        }
+    }
+
+    /// Width of the titles before the selected one, to place the cursor while
+    /// renaming
+    fn selected_title_offset(&self) -> u16 {
+        self.tabs[..self.selected_tab]
+            .iter()
+            .map(|tab| tab.name.width() as u16 + 2)
+            .sum::<u16>()
+            + 1
+    }
 }
 
 impl StatefulDrawableComponent for SqlEditorComponent {
     fn draw<B: Backend>(&mut self, f: &mut Frame<B>, area: Rect, focused: bool) -> Result<()> {
         let layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(if matches!(self.focus, Focus::Table) {
+            .constraints(if matches!(self.tab().focus, Focus::Table) {
                 vec![Constraint::Length(7), Constraint::Min(1)]
             } else {
                 vec![Constraint::Percentage(50), Constraint::Min(1)]
             })
             .split(area);
 
-        let editor = StatefulParagraph::new(self.input.iter().collect::<String>())
+        let mut paragraph_state = self.tab().paragraph_state;
+        let titles = self.tab_titles();
+        let tab = &self.tabs[self.selected_tab];
+        let mut highlights = match &tab.highlight {
+            Some((range, at)) if at.elapsed() < HIGHLIGHT_DURATION => {
+                vec![(range.clone(), Style::default().bg(Color::DarkGray))]
+            }
+            _ => vec![],
+        };
+        if let Some(range) = &tab.error {
+            highlights.push((
+                range.clone(),
+                Style::default()
+                    .fg(Color::Red)
+                    .add_modifier(Modifier::UNDERLINED),
+            ));
+        }
+        let editor = StatefulParagraph::new(Spans::from(tab.input.highlighted_spans(&highlights)))
             .wrap(Wrap { trim: true })
-            .block(Block::default().borders(Borders::ALL));
-
-        f.render_stateful_widget(editor, layout[0], &mut self.paragraph_state);
-
-        if let Some(result) = self.query_result.as_ref() {
-            let result = Paragraph::new(result.result_str())
-                .block(Block::default().borders(Borders::ALL).style(
-                    if focused && matches!(self.focus, Focus::Editor) {
-                        Style::default()
-                    } else {
-                        Style::default().fg(Color::DarkGray)
-                    },
-                ))
-                .wrap(Wrap { trim: true });
-            f.render_widget(result, layout[1]);
-        } else {
-            self.table
-                .draw(f, layout[1], focused && matches!(self.focus, Focus::Table))?;
+            .block(Block::default().borders(Borders::ALL).title(titles));
+
+        f.render_stateful_widget(editor, layout[0], &mut paragraph_state);
+        let tab = &mut self.tabs[self.selected_tab];
+        tab.paragraph_state = paragraph_state;
+
+        let table_focused = focused && matches!(tab.focus, Focus::Table);
+        match tab.query_result.as_ref() {
+            Some(result) => {
+                let expanded = self.show_messages && !result.messages.is_empty();
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([
+                        Constraint::Min(1),
+                        Constraint::Length(if expanded {
+                            (result.messages.len() as u16).min(MAX_MESSAGES_HEIGHT) + 3
+                        } else {
+                            1
+                        }),
+                    ])
+                    .split(layout[1]);
+                match result.status {
+                    QueryStatus::Write { .. } => {
+                        let summary = Paragraph::new(result.result_str())
+                            .block(Block::default().borders(Borders::ALL).style(
+                                if focused && matches!(tab.focus, Focus::Editor) {
+                                    Style::default()
+                                } else {
+                                    Style::default().fg(Color::DarkGray)
+                                },
+                            ))
+                            .wrap(Wrap { trim: true });
+                        f.render_widget(summary, chunks[0]);
+                    }
+                    QueryStatus::Explain => tab.plan.draw(f, chunks[0], table_focused)?,
+                    _ => tab.table.draw(f, chunks[0], table_focused)?,
+                }
+
+                if expanded {
+                    let mut lines = vec![Spans::from(result.result_str())];
+                    lines.extend(result.messages.iter().map(|m| Spans::from(m.as_str())));
+                    let messages = Paragraph::new(lines)
+                        .block(
+                            Block::default()
+                                .borders(Borders::ALL)
+                                .title(format!("Messages [{}]", self.key_config.toggle_messages)),
+                        )
+                        .wrap(Wrap { trim: true });
+                    f.render_widget(messages, chunks[1]);
+                } else {
+                    let mut summary = result.result_str();
+                    if !result.messages.is_empty() {
+                        summary.push_str(&format!(
+                            ", {} messages [{}]",
+                            result.messages.len(),
+                            self.key_config.toggle_messages
+                        ));
+                    }
+                    f.render_widget(
+                        Paragraph::new(summary).style(Style::default().fg(Color::DarkGray)),
+                        chunks[1],
+                    );
+                }
+            }
+            None => tab.table.draw(f, layout[1], table_focused)?,
         }
 
-        if focused && matches!(self.focus, Focus::Editor) {
+        let (cursor_x, cursor_y) = tab.cursor_position(layout[0].width.saturating_sub(2));
+        let editor_focused = focused && matches!(tab.focus, Focus::Editor);
+        if let Some(input) = self.rename_input.as_ref() {
+            if focused {
+                f.set_cursor(
+                    (layout[0].x + 1 + self.selected_title_offset())
+                        .saturating_add(input.cursor_position())
+                        .min(area.right().saturating_sub(2)),
+                    layout[0].y,
+                )
+            }
+        } else if editor_focused {
             f.set_cursor(
                 (layout[0].x + 1)
-                    .saturating_add(
-                        self.input_cursor_position_x % layout[0].width.saturating_sub(2),
-                    )
+                    .saturating_add(cursor_x)
                     .min(area.right().saturating_sub(2)),
-                (layout[0].y
-                    + 1
-                    + self.input_cursor_position_x / layout[0].width.saturating_sub(2))
-                .min(layout[0].bottom()),
-            )
+                (layout[0].y + 1 + cursor_y).min(layout[0].bottom()),
+            );
+            self.completion
+                .draw(f, area, false, cursor_x + 1, cursor_y)?;
         }
 
-        if focused && matches!(self.focus, Focus::Editor) {
-            self.completion.draw(
-                f,
-                area,
-                false,
-                self.input_cursor_position_x % layout[0].width.saturating_sub(2) + 1,
-                self.input_cursor_position_x / layout[0].width.saturating_sub(2),
-            )?;
-        };
+        self.bind_params.draw(f, area, false)?;
         Ok(())
     }
 }
 
 #[async_trait]
 impl Component for SqlEditorComponent {
-    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::execute_query(&self.key_config)));
+        out.push(CommandInfo::new(command::format_query(&self.key_config)));
+        out.push(CommandInfo::new(command::editor_tabs(&self.key_config)));
+        out.push(CommandInfo::new(command::cycle_editor_tabs(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::toggle_messages(&self.key_config)));
+        out.push(CommandInfo::new(command::explain_query(&self.key_config)));
+    }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
-        let input_str: String = self.input.iter().collect();
+        if self.bind_params.is_visible() {
+            return self.bind_params.event(key);
+        }
+
+        if let Some(input) = self.rename_input.as_mut() {
+            if key == self.key_config.enter {
+                let name = input.value_str().trim().to_string();
+                if !name.is_empty() {
+                    self.tab_mut().name = name;
+                }
+                self.rename_input = None;
+                self.save_tabs()?;
+            } else if key == self.key_config.exit_popup {
+                self.rename_input = None;
+            } else {
+                input.event(key)?;
+            }
+            return Ok(EventState::Consumed);
+        }
+
+        let tab = self.tab_mut();
+        if matches!(tab.focus, Focus::Table) && !tab.showing_plan() && tab.table.searching() {
+            return tab.table.event(key);
+        }
 
-        if key == self.key_config.focus_above && matches!(self.focus, Focus::Table) {
-            self.focus = Focus::Editor
+        if key == self.key_config.toggle_messages {
+            self.show_messages = !self.show_messages;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.new_editor_tab {
+            self.new_tab()?;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.close_editor_tab {
+            self.close_tab()?;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.next_editor_tab {
+            self.selected_tab = (self.selected_tab + 1) % self.tabs.len();
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.previous_editor_tab {
+            self.selected_tab = self
+                .selected_tab
+                .checked_sub(1)
+                .unwrap_or(self.tabs.len() - 1);
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.rename_editor_tab {
+            self.rename_input = Some(Input::from(self.tab().name.as_str()));
+            return Ok(EventState::Consumed);
+        }
+
+        if key == self.key_config.focus_above && matches!(self.tab().focus, Focus::Table) {
+            self.tab_mut().focus = Focus::Editor
         } else if key == self.key_config.enter {
             return self.complete();
         }
 
-        match key {
-            Key::Char(c) if matches!(self.focus, Focus::Editor) => {
-                self.input.insert(self.input_idx, c);
-                self.input_idx += 1;
-                self.input_cursor_position_x += compute_character_width(c);
+        let tab = self.tab_mut();
+        let value_len = tab.input.value_str().len();
+        if matches!(tab.focus, Focus::Editor) && tab.input.event(key)?.is_consumed() {
+            tab.highlight = None;
+            if tab.input.value_str().len() != value_len {
+                tab.error = None;
+            }
+            if let Key::Char(_) = key {
                 self.update_completion();
-
-                return Ok(EventState::Consumed);
+            } else {
+                self.completion.update("");
             }
-            Key::Esc if matches!(self.focus, Focus::Editor) => self.focus = Focus::Table,
-            Key::Delete | Key::Backspace if matches!(self.focus, Focus::Editor) => {
-                if input_str.width() > 0 && !self.input.is_empty() && self.input_idx > 0 {
-                    let last_c = self.input.remove(self.input_idx - 1);
-                    self.input_idx -= 1;
-                    self.input_cursor_position_x -= compute_character_width(last_c);
-                    self.completion.update("");
-                }
+            return Ok(EventState::Consumed);
+        }
 
-                return Ok(EventState::Consumed);
-            }
-            Key::Left if matches!(self.focus, Focus::Editor) => {
-                if !self.input.is_empty() && self.input_idx > 0 {
-                    self.input_idx -= 1;
-                    self.input_cursor_position_x = self
-                        .input_cursor_position_x
-                        .saturating_sub(compute_character_width(self.input[self.input_idx]));
-                    self.completion.update("");
-                }
-                return Ok(EventState::Consumed);
-            }
-            Key::Right if matches!(self.focus, Focus::Editor) => {
-                if self.input_idx < self.input.len() {
-                    let next_c = self.input[self.input_idx];
-                    self.input_idx += 1;
-                    self.input_cursor_position_x += compute_character_width(next_c);
-                    self.completion.update("");
-                }
-                return Ok(EventState::Consumed);
+        match key {
+            Key::Esc if matches!(tab.focus, Focus::Editor) => tab.focus = Focus::Table,
+            key if matches!(tab.focus, Focus::Table) && tab.showing_plan() => {
+                return tab.plan.event(key)
             }
-            key if matches!(self.focus, Focus::Table) => return self.table.event(key),
+            key if matches!(tab.focus, Focus::Table) => return tab.table.event(key),
             _ => (),
         }
         return Ok(EventState::NotConsumed);
     }
 
     async fn async_event(&mut self, key: Key, pool: &Box<dyn Pool>) -> Result<EventState> {
-        if key == self.key_config.enter && matches!(self.focus, Focus::Editor) {
-            let query = self.input.iter().collect();
-            let result = pool.execute(&query).await?;
-            match result {
-                ExecuteResult::Read {
-                    headers,
-                    rows,
-                    database,
-                    table,
-                } => {
-                    self.table.update(rows, headers, database, table);
-                    self.focus = Focus::Table;
-                    self.query_result = None;
-                }
-                ExecuteResult::Write { updated_rows } => {
-                    self.query_result = Some(QueryResult { updated_rows })
-                }
+        if self.bind_params.is_visible() {
+            if key == self.key_config.enter {
+                let (query, params) = self.bind_params.bind_values()?;
+                self.history
+                    .push(self.bind_params.query(), self.bind_params.params())?;
+                self.bind_params.hide();
+                self.execute(&query, &params, self.pending, pool.as_ref())
+                    .await?;
+                return Ok(EventState::Consumed);
             }
+            return Ok(EventState::NotConsumed);
+        }
+
+        if key == self.key_config.format_query && matches!(self.tab().focus, Focus::Editor) {
+            let tab = self.tab_mut();
+            let formatted = sql::format(tab.input.value_str(), pool.dialect());
+            tab.input.set_value(formatted);
+            tab.highlight = None;
+            tab.error = None;
+            self.completion.update("");
+            return Ok(EventState::Consumed);
+        }
+
+        let execution = if key == self.key_config.explain_query {
+            Execution::Explain { analyze: false }
+        } else if key == self.key_config.explain_analyze_query {
+            Execution::Explain { analyze: true }
+        } else if key == self.key_config.enter || key == self.key_config.execute_statement {
+            Execution::Run
+        } else {
+            return Ok(EventState::NotConsumed);
+        };
+        if matches!(self.tab().focus, Focus::Editor) {
+            let tab = &mut self.tabs[self.selected_tab];
+            let value = tab.input.value_str();
+            let range = if key == self.key_config.enter {
+                Some(tab.input.selection().unwrap_or(0..value.len()))
+            } else {
+                tab.input
+                    .selection()
+                    .or_else(|| sql::statement_at(value, pool.dialect(), tab.input.cursor()))
+            };
+            let range = match range {
+                Some(range) => range,
+                None => return Ok(EventState::Consumed),
+            };
+            let query = value[range.clone()].to_string();
+            tab.highlight = Some((range, Instant::now()));
+
+            let placeholders = sql::placeholders(&query, pool.dialect());
+            if placeholders.is_empty() {
+                self.history.push(&query, vec![])?;
+                self.execute(&query, &[], execution, pool.as_ref()).await?;
+            } else {
+                let recent = self.history.params(&query);
+                self.pending = execution;
+                self.bind_params.open(query, placeholders, recent);
+            }
+            self.save_tabs()?;
             return Ok(EventState::Consumed);
         }
 
         Ok(EventState::NotConsumed)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        Component as _, Dialect, Duration, EditorTab, Instant, Key, KeyConfig, QueryError,
+        QueryResult, QueryStatus, SqlEditorComponent,
+    };
+    use crate::database::ErrorPosition;
+
+    #[test]
+    fn test_show_error() {
+        let text = "select 1;\n  select * from t where a = :a and b = 1 form";
+        let mut tab = EditorTab::new("Query 1".to_string(), text, KeyConfig::default());
+        tab.highlight = Some((10..text.len(), Instant::now()));
+        let err = QueryError {
+            message: "syntax error at or near \"form\"".to_string(),
+            code: Some("42601".to_string()),
+            detail: None,
+            hint: None,
+            position: Some(ErrorPosition::Character(40)),
+        };
+        tab.show_error(
+            &err,
+            "  select * from t where a = $1 and b = 1 form",
+            Dialect::Postgres,
+        );
+        assert_eq!(tab.error, Some(text.len() - 4..text.len()));
+        assert_eq!(tab.input.cursor(), text.len() - 4);
+    }
+
+    #[test]
+    fn test_result_str() {
+        let result = |status| QueryResult {
+            status,
+            elapsed: Duration::from_millis(1234),
+            messages: vec![],
+        };
+        assert_eq!(
+            result(QueryStatus::Read { rows: 3 }).result_str(),
+            "3 rows in set (1.234 sec)"
+        );
+        assert_eq!(
+            result(QueryStatus::Write { updated_rows: 1 }).result_str(),
+            "Query OK, 1 row affected (1.234 sec)"
+        );
+    }
+
+    fn names(editor: &SqlEditorComponent) -> Vec<&str> {
+        editor.tabs.iter().map(|tab| tab.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_editor_tabs() {
+        let key_config = KeyConfig::default();
+        let mut editor = SqlEditorComponent::new(key_config.clone());
+        editor.event(Key::Char('a')).unwrap();
+        editor.event(key_config.new_editor_tab).unwrap();
+        editor.event(Key::Char('b')).unwrap();
+        assert_eq!(names(&editor), vec!["Query 1", "Query 2"]);
+        assert_eq!(editor.tab().input.value_str(), "b");
+
+        editor.event(key_config.rename_editor_tab).unwrap();
+        editor.event(Key::Ctrl('u')).unwrap();
+        editor.event(Key::Char('x')).unwrap();
+        editor.event(key_config.enter).unwrap();
+        assert_eq!(names(&editor), vec!["Query 1", "x"]);
+
+        editor.event(key_config.next_editor_tab).unwrap();
+        assert_eq!(editor.tab().input.value_str(), "a");
+        editor.event(key_config.close_editor_tab).unwrap();
+        assert_eq!(names(&editor), vec!["x"]);
+        editor.event(key_config.close_editor_tab).unwrap();
+        assert_eq!(names(&editor), vec!["x"]);
+        assert_eq!(editor.tab().input.value_str(), "");
+    }
+}
@@ -1,21 +1,90 @@
 use super::{
-    utils::scroll_vertical::VerticalScroll, Component, DrawableComponent, EventState,
-    StatefulDrawableComponent, TableStatusComponent, TableValueComponent,
+    utils::{input::Input, scroll_vertical::VerticalScroll},
+    Component, DrawableComponent, EventState, StatefulDrawableComponent, TableStatusComponent,
+    TableValueComponent,
 };
+use crate::column_layouts::{ColumnLayout, ColumnLayouts};
 use crate::components::command::{self, CommandInfo};
-use crate::config::KeyConfig;
+use crate::config::{KeyConfig, DEFAULT_MAX_COLUMN_WIDTH};
 use crate::event::Key;
 use anyhow::Result;
 use database_tree::{Database, Table as DTable};
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
 use std::convert::From;
+use std::ops::Range;
 use tui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Row, Table, TableState},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table, TableState},
     Frame,
 };
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const MIN_COLUMN_WIDTH: usize = 3;
+/// How much a column is widened or narrowed by at a time
+const COLUMN_WIDTH_STEP: isize = 2;
+
+/// Cuts `value` down to `width` terminal columns, ending it with an ellipsis
+/// if anything is left out.
+pub fn truncate(value: &str, width: usize) -> String {
+    if value.width() <= width {
+        return value.to_string();
+    }
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+    for c in value.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if truncated_width + char_width + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        truncated_width += char_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+/// How a row or cell differs from the database, for changes not yet
+/// committed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mark {
+    Inserted,
+    Updated,
+    Deleted,
+}
+
+/// A search through the loaded cells
+struct Search {
+    input: Input,
+    /// Whether the pattern is still being typed
+    editing: bool,
+    case_sensitive: bool,
+    regex: bool,
+    /// The selected row and column when the search started, returned to if
+    /// it is cancelled
+    origin: (Option<usize>, usize),
+}
+
+impl Search {
+    /// None if there is no pattern yet or it is not a valid regex
+    fn matcher(&self) -> Option<Regex> {
+        if self.input.is_empty() {
+            return None;
+        }
+        let pattern = if self.regex {
+            self.input.value_str().to_string()
+        } else {
+            regex::escape(self.input.value_str())
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .ok()
+    }
+}
 
 pub struct TableComponent {
     pub headers: Vec<String>,
@@ -27,6 +96,19 @@ pub struct TableComponent {
     selection_area_corner: Option<(usize, usize)>,
     column_page_start: std::cell::Cell<usize>,
     scroll: VerticalScroll,
+    /// The type of each column, if known
+    column_types: Vec<String>,
+    /// Whether the selected row is shown as a list of its fields
+    record_view: bool,
+    layout: ColumnLayout,
+    /// Identifies the table whose layout is remembered, if any
+    layout_key: Option<String>,
+    layouts: ColumnLayouts,
+    max_column_width: usize,
+    search: Option<Search>,
+    /// Highlighted rows and cells, keyed by row and, for cells, column
+    marks: HashMap<(usize, Option<usize>), Mark>,
+    pending_changes: usize,
     key_config: KeyConfig,
 }
 
@@ -42,14 +124,41 @@ impl TableComponent {
             column_page_start: std::cell::Cell::new(0),
             scroll: VerticalScroll::new(false, false),
             eod: false,
+            column_types: vec![],
+            record_view: false,
+            layout: ColumnLayout::default(),
+            layout_key: None,
+            layouts: ColumnLayouts::new(),
+            max_column_width: DEFAULT_MAX_COLUMN_WIDTH,
+            search: None,
+            marks: HashMap::new(),
+            pending_changes: 0,
             key_config,
         }
     }
 
     fn title(&self) -> String {
-        self.table.as_ref().map_or(" - ".to_string(), |table| {
+        let title = self.table.as_ref().map_or(" - ".to_string(), |table| {
             format!("{}.{}", table.0.name, table.1.name)
-        })
+        });
+        match self.pending_changes {
+            0 => title,
+            1 => format!("{} (1 pending change)", title),
+            changes => format!("{} ({} pending changes)", title, changes),
+        }
+    }
+
+    /// Highlights the rows and cells changed by `changes` pending changes.
+    pub fn set_marks(&mut self, marks: HashMap<(usize, Option<usize>), Mark>, changes: usize) {
+        self.marks = marks;
+        self.pending_changes = changes;
+    }
+
+    fn mark(&self, row: usize, column: usize) -> Option<Mark> {
+        self.marks
+            .get(&(row, None))
+            .or_else(|| self.marks.get(&(row, Some(column))))
+            .copied()
     }
 
     pub fn update(
@@ -70,9 +179,114 @@ impl TableComponent {
         self.column_page_start = std::cell::Cell::new(0);
         self.scroll = VerticalScroll::new(false, false);
         self.eod = false;
+        self.column_types = vec![];
+        self.layout = ColumnLayout::default();
+        self.layout_key = None;
+        self.search = None;
+        self.marks = HashMap::new();
+        self.pending_changes = 0;
         self.table = Some((database, table));
     }
 
+    /// Restores the column layout remembered under `key`, and remembers
+    /// changes to it from now on.
+    pub fn set_layout_key(&mut self, key: String) {
+        self.layout = self.layouts.get(&key);
+        self.layout_key = Some(key);
+        self.selected_column = 0;
+    }
+
+    pub fn set_max_column_width(&mut self, width: usize) {
+        self.max_column_width = width;
+    }
+
+    fn save_layout(&mut self) -> Result<()> {
+        if let Some(key) = &self.layout_key {
+            self.layouts.set(key, self.layout.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Indices into `headers` and each row of the columns shown, in order
+    fn columns(&self) -> Vec<usize> {
+        if self.headers.is_empty() {
+            return (0..self.rows.first().map_or(0, Vec::len)).collect();
+        }
+        self.layout.columns(&self.headers)
+    }
+
+    /// The number of columns kept in place while scrolling horizontally,
+    /// leaving at least one to scroll
+    fn frozen(&self, columns: &[usize]) -> usize {
+        self.layout.frozen.min(columns.len().saturating_sub(1))
+    }
+
+    fn hide_column(&mut self) -> Result<()> {
+        let columns = self.columns();
+        if columns.len() <= 1 {
+            return Ok(());
+        }
+        if let Some(header) = columns.get(self.selected_column).map(|i| &self.headers[*i]) {
+            self.layout.hidden.push(header.clone());
+            if self.selected_column < self.layout.frozen {
+                self.layout.frozen -= 1;
+            }
+            self.selected_column = self.selected_column.min(columns.len() - 2);
+            self.reset_selection();
+        }
+        self.save_layout()
+    }
+
+    fn show_all_columns(&mut self) -> Result<()> {
+        self.layout.hidden.clear();
+        self.selected_column = 0;
+        self.reset_selection();
+        self.save_layout()
+    }
+
+    /// Swaps the selected column with the one next to it.
+    fn move_column(&mut self, right: bool) -> Result<()> {
+        let columns = self.columns();
+        let other = if right {
+            self.selected_column + 1
+        } else {
+            match self.selected_column.checked_sub(1) {
+                Some(other) => other,
+                None => return Ok(()),
+            }
+        };
+        if other >= columns.len() || self.headers.is_empty() {
+            return Ok(());
+        }
+        let mut order = self.layout.order(&self.headers);
+        let a = order
+            .iter()
+            .position(|i| *i == columns[self.selected_column]);
+        let b = order.iter().position(|i| *i == columns[other]);
+        if let (Some(a), Some(b)) = (a, b) {
+            order.swap(a, b);
+        }
+        self.layout.order = order.iter().map(|i| self.headers[*i].clone()).collect();
+        self.selected_column = other;
+        self.reset_selection();
+        self.save_layout()
+    }
+
+    /// Keeps the columns up to the selected one in place, or none if they
+    /// already are.
+    fn freeze_columns(&mut self) -> Result<()> {
+        self.layout.frozen = if self.layout.frozen == self.selected_column + 1 {
+            0
+        } else {
+            self.selected_column + 1
+        };
+        self.save_layout()
+    }
+
+    pub fn set_column_types(&mut self, types: Vec<String>) {
+        self.column_types = types;
+    }
+
     pub fn reset(&mut self) {
         self.selected_row.select(None);
         self.headers = Vec::new();
@@ -82,9 +296,169 @@ impl TableComponent {
         self.column_page_start = std::cell::Cell::new(0);
         self.scroll = VerticalScroll::new(false, false);
         self.eod = false;
+        self.column_types = vec![];
+        self.search = None;
+        self.marks = HashMap::new();
+        self.pending_changes = 0;
         self.table = None;
     }
 
+    /// Whether a search pattern is being typed, so keys are text.
+    pub fn searching(&self) -> bool {
+        matches!(&self.search, Some(search) if search.editing)
+    }
+
+    fn start_search(&mut self) {
+        let (case_sensitive, regex) = self.search.as_ref().map_or((false, false), |search| {
+            (search.case_sensitive, search.regex)
+        });
+        self.search = Some(Search {
+            input: Input::new(),
+            editing: true,
+            case_sensitive,
+            regex,
+            origin: (self.selected_row.selected(), self.selected_column),
+        });
+    }
+
+    /// The rows and shown columns of the cells matching the search, row by row
+    fn search_matches(&self) -> Vec<(usize, usize)> {
+        let matcher = match self.search.as_ref().and_then(Search::matcher) {
+            Some(matcher) => matcher,
+            None => return vec![],
+        };
+        let columns = self.columns();
+        let mut matches = vec![];
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, column) in columns.iter().enumerate() {
+                if matches!(row.get(*column), Some(cell) if matcher.is_match(cell)) {
+                    matches.push((y, x));
+                }
+            }
+        }
+        matches
+    }
+
+    /// Selects the next match after the selected cell, or the previous one
+    /// before it, wrapping around. With `inclusive` the selected cell itself
+    /// counts.
+    fn jump_to_match(&mut self, forward: bool, inclusive: bool) {
+        let matches = self.search_matches();
+        let current = (
+            self.selected_row.selected().unwrap_or(0),
+            self.selected_column,
+        );
+        let found = if forward {
+            matches
+                .iter()
+                .find(|m| **m > current || inclusive && **m == current)
+                .or_else(|| matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|m| **m < current || inclusive && **m == current)
+                .or_else(|| matches.last())
+        };
+        if let Some((y, x)) = found {
+            self.selected_row.select(Some(*y));
+            self.selected_column = *x;
+            self.reset_selection();
+        }
+    }
+
+    /// Jumps to the first match from where the search started as the
+    /// pattern changes.
+    fn update_search(&mut self) {
+        if let Some((row, column)) = self.search.as_ref().map(|search| search.origin) {
+            self.selected_row.select(row);
+            self.selected_column = column;
+            self.jump_to_match(true, true);
+        }
+    }
+
+    fn search_event(&mut self, key: Key) -> Result<EventState> {
+        let search = match self.search.as_mut() {
+            Some(search) => search,
+            None => return Ok(EventState::NotConsumed),
+        };
+        if key == self.key_config.exit_popup {
+            let (row, column) = search.origin;
+            self.search = None;
+            self.selected_row.select(row);
+            self.selected_column = column;
+        } else if key == self.key_config.enter {
+            search.editing = false;
+            if search.input.is_empty() {
+                self.search = None;
+            }
+        } else if key == self.key_config.toggle_search_case {
+            search.case_sensitive = !search.case_sensitive;
+            self.update_search();
+        } else if key == self.key_config.toggle_search_regex {
+            search.regex = !search.regex;
+            self.update_search();
+        } else if search.input.event(key)?.is_consumed() {
+            self.update_search();
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn draw_search<B: Backend>(&self, f: &mut Frame<B>, area: Rect, focused: bool) {
+        let search = match &self.search {
+            Some(search) => search,
+            None => return,
+        };
+        let option = |label, enabled| {
+            Span::styled(
+                format!(" {} ", label),
+                if enabled {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                },
+            )
+        };
+        let matches = self.search_matches();
+        let status = if search.input.is_empty() {
+            String::new()
+        } else if search.matcher().is_none() {
+            "invalid regex".to_string()
+        } else if matches.is_empty() {
+            "no matches".to_string()
+        } else {
+            let current = (
+                self.selected_row.selected().unwrap_or(0),
+                self.selected_column,
+            );
+            match matches.iter().position(|m| *m == current) {
+                Some(i) => format!("{} of {}", i + 1, matches.len()),
+                None => format!("{} matches", matches.len()),
+            }
+        };
+
+        let mut spans = vec![Span::raw("/")];
+        spans.extend(search.input.spans());
+        spans.push(Span::raw("  "));
+        spans.push(option("Aa", search.case_sensitive));
+        spans.push(option(".*", search.regex));
+        spans.push(Span::styled(
+            format!("  {}", status),
+            Style::default().fg(Color::DarkGray),
+        ));
+        let paragraph = Paragraph::new(Spans::from(spans)).block(
+            Block::default().borders(Borders::TOP).style(if focused {
+                Style::default()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            }),
+        );
+        f.render_widget(paragraph, area);
+        if focused && search.editing {
+            f.set_cursor(area.x + 1 + search.input.cursor_position(), area.y + 1);
+        }
+    }
+
     fn reset_selection(&mut self) {
         self.selection_area_corner = None;
     }
@@ -145,7 +519,7 @@ impl TableComponent {
             return;
         }
         self.reset_selection();
-        if self.selected_column >= self.headers.len().saturating_sub(1) {
+        if self.selected_column >= self.columns().len().saturating_sub(1) {
             return;
         }
         self.selected_column += 1;
@@ -172,7 +546,7 @@ impl TableComponent {
         if let Some((x, y)) = self.selection_area_corner {
             self.selection_area_corner = Some((
                 if positive {
-                    (x + 1).min(self.headers.len().saturating_sub(1))
+                    (x + 1).min(self.columns().len().saturating_sub(1))
                 } else {
                     x.saturating_sub(1)
                 },
@@ -201,13 +575,20 @@ impl TableComponent {
     }
 
     pub fn selected_cells(&self) -> Option<String> {
+        let columns = self.columns();
         if let Some((x, y)) = self.selection_area_corner {
             let selected_row_index = self.selected_row.selected()?;
+            let selected_columns =
+                &columns[x.min(self.selected_column)..x.max(self.selected_column) + 1];
             return Some(
                 self.rows[y.min(selected_row_index)..y.max(selected_row_index) + 1]
                     .iter()
                     .map(|row| {
-                        row[x.min(self.selected_column)..x.max(self.selected_column) + 1].join(",")
+                        selected_columns
+                            .iter()
+                            .map(|i| row[*i].as_str())
+                            .collect::<Vec<&str>>()
+                            .join(",")
                     })
                     .collect::<Vec<String>>()
                     .join("\n"),
@@ -215,10 +596,68 @@ impl TableComponent {
         }
         self.rows
             .get(self.selected_row.selected()?)?
-            .get(self.selected_column)
+            .get(*columns.get(self.selected_column)?)
             .map(|cell| cell.to_string())
     }
 
+    /// The header, value and type of the selected cell
+    pub fn selected_field(&self) -> Option<(&str, &str, &str)> {
+        let column = *self.columns().get(self.selected_column)?;
+        Some((
+            self.headers.get(column)?,
+            self.rows.get(self.selected_row.selected()?)?.get(column)?,
+            self.column_types.get(column).map_or("", String::as_str),
+        ))
+    }
+
+    /// The rows covered by the selection, or the selected row.
+    pub fn selected_rows(&self) -> Option<Range<usize>> {
+        let row = self.selected_row.selected()?;
+        Some(match self.selection_area_corner {
+            Some((_, y)) => y.min(row)..y.max(row) + 1,
+            None => row..row + 1,
+        })
+    }
+
+    /// The row and the index into `headers` of the selected cell.
+    pub fn selected_position(&self) -> Option<(usize, usize)> {
+        let row = self.selected_row.selected()?;
+        let column = *self.columns().get(self.selected_column)?;
+        Some((row, column))
+    }
+
+    /// The table the rows are from
+    pub fn table(&self) -> Option<&(Database, DTable)> {
+        self.table.as_ref()
+    }
+
+    /// The selected row and shown column, to come back to with
+    /// `restore_selection`.
+    pub fn selection(&self) -> (Option<usize>, usize) {
+        (self.selected_row.selected(), self.selected_column)
+    }
+
+    /// Selects a cell again, or the nearest one if fewer rows are loaded.
+    pub fn restore_selection(&mut self, row: Option<usize>, column: usize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        self.selected_row
+            .select(row.map(|row| row.min(self.rows.len() - 1)));
+        self.selected_column = column.min(self.columns().len().saturating_sub(1));
+        self.reset_selection();
+    }
+
+    /// Shows `row`, e.g. one just inserted, at the top and selects it.
+    pub fn prepend_row(&mut self, row: Vec<String>) {
+        self.rows.insert(0, row);
+        self.selected_row.select(Some(0));
+    }
+
+    pub fn column_type(&self, column: usize) -> &str {
+        self.column_types.get(column).map_or("", String::as_str)
+    }
+
     fn selected_column_index(&self) -> usize {
         if let Some((x, _)) = self.selection_area_corner {
             return x;
@@ -233,9 +672,7 @@ impl TableComponent {
         selected_column_index: usize,
     ) -> bool {
         if let Some((x, y)) = self.selection_area_corner {
-            let x_in_page = x
-                .saturating_add(1)
-                .saturating_sub(self.column_page_start.get());
+            let x_in_page = self.position_in_page(x, self.frozen(&self.columns()));
             return matches!(
                 self.selected_row.selected(),
                 Some(selected_row_index)
@@ -258,26 +695,94 @@ impl TableComponent {
         )
     }
 
+    /// Where `column` is drawn, counting the row number column, or 0 if it
+    /// is scrolled out of view to the left
+    fn position_in_page(&self, column: usize, frozen: usize) -> usize {
+        if column < frozen {
+            column + 1
+        } else {
+            (frozen + column + 1).saturating_sub(self.column_page_start.get())
+        }
+    }
+
+    /// The frozen columns followed by those from `left` to `right`
+    fn page_columns(&self, left: usize, right: usize) -> Vec<usize> {
+        let columns = self.columns();
+        let frozen = self.frozen(&columns);
+        columns[..frozen]
+            .iter()
+            .chain(columns[left.max(frozen)..right].iter())
+            .copied()
+            .collect()
+    }
+
     fn headers(&self, left: usize, right: usize) -> Vec<String> {
-        let mut headers = self.headers.clone()[left..right].to_vec();
+        let mut headers = self
+            .page_columns(left, right)
+            .iter()
+            .map(|i| self.headers[*i].clone())
+            .collect::<Vec<String>>();
         headers.insert(0, "".to_string());
         headers
     }
 
     fn rows(&self, left: usize, right: usize) -> Vec<Vec<String>> {
-        let rows = self
+        let columns = self.page_columns(left, right);
+        let mut new_rows: Vec<Vec<String>> = self
             .rows
             .iter()
-            .map(|row| row.to_vec())
-            .collect::<Vec<Vec<String>>>();
-        let mut new_rows: Vec<Vec<String>> =
-            rows.iter().map(|row| row[left..right].to_vec()).collect();
+            .map(|row| columns.iter().map(|i| row[*i].clone()).collect())
+            .collect();
         for (index, row) in new_rows.iter_mut().enumerate() {
             row.insert(0, (index + 1).to_string())
         }
         new_rows
     }
 
+    /// The width of the widest value of a column, header included
+    fn content_width(&self, column: usize) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row.get(column).map_or(0, |cell| cell.width()))
+            .chain(self.headers.get(column).map(|header| header.width()))
+            .max()
+            .unwrap_or(0)
+            .max(MIN_COLUMN_WIDTH)
+    }
+
+    /// The width set by hand, or else that of the content up to the maximum
+    fn column_width(&self, column: usize) -> usize {
+        self.headers
+            .get(column)
+            .and_then(|header| self.layout.widths.get(header))
+            .copied()
+            .unwrap_or_else(|| {
+                self.content_width(column)
+                    .min(self.max_column_width.max(MIN_COLUMN_WIDTH))
+            })
+    }
+
+    /// Widens or narrows the selected column by `delta`, or fits it to its
+    /// content if there is none.
+    fn resize_column(&mut self, delta: Option<isize>) -> Result<()> {
+        let column = match self.columns().get(self.selected_column) {
+            Some(column) => *column,
+            None => return Ok(()),
+        };
+        let header = match self.headers.get(column) {
+            Some(header) => header.clone(),
+            None => return Ok(()),
+        };
+        let width = match delta {
+            Some(delta) => (self.column_width(column) as isize + delta).max(0) as usize,
+            None => self.content_width(column),
+        };
+        self.layout
+            .widths
+            .insert(header, width.max(MIN_COLUMN_WIDTH));
+        self.save_layout()
+    }
+
     fn calculate_cell_widths(
         &self,
         area_width: u16,
@@ -285,42 +790,39 @@ impl TableComponent {
         if self.rows.is_empty() {
             return (0, Vec::new(), Vec::new(), Vec::new());
         }
-        if self.selected_column_index() < self.column_page_start.get() {
-            self.column_page_start.set(self.selected_column_index());
+        let columns = self.columns();
+        let frozen = self.frozen(&columns);
+        let frozen_widths = columns[..frozen]
+            .iter()
+            .map(|i| self.column_width(*i))
+            .collect::<Vec<usize>>();
+        let area_width = area_width
+            .saturating_sub((frozen_widths.iter().sum::<usize>() + frozen_widths.len()) as u16);
+
+        // Frozen columns are always shown, so only the others scroll.
+        let scrolled_column_index = self.selected_column_index().max(frozen);
+        if scrolled_column_index < self.column_page_start.get()
+            || self.column_page_start.get() < frozen
+        {
+            self.column_page_start.set(scrolled_column_index);
         }
 
-        let far_right_column_index = self.selected_column_index();
-        let mut column_index = self.selected_column_index();
+        let far_right_column_index = scrolled_column_index;
+        let mut column_index = scrolled_column_index;
         let number_column_width = (self.rows.len() + 1).to_string().width() as u16;
         let mut widths = Vec::new();
         loop {
+            // A column wider than the table is cut so that it still shows.
             let length = self
-                .rows
-                .iter()
-                .map(|row| {
-                    row.get(column_index)
-                        .map_or(String::new(), |cell| cell.to_string())
-                        .width()
-                })
-                .collect::<Vec<usize>>()
-                .iter()
-                .max()
-                .map_or(3, |v| {
-                    *v.max(
-                        &self
-                            .headers
-                            .get(column_index)
-                            .map_or(3, |header| header.to_string().width()),
-                    )
-                    .clamp(&3, &20)
-                });
-            if widths.iter().map(|(_, width)| width).sum::<usize>() + length + widths.len() + 1
+                .column_width(columns[column_index])
+                .min((area_width.saturating_sub(number_column_width) as usize).saturating_sub(2));
+            if widths.iter().sum::<usize>() + length + widths.len() + 1
                 >= area_width.saturating_sub(number_column_width) as usize
             {
                 column_index += 1;
                 break;
             }
-            widths.push((self.headers[column_index].clone(), length));
+            widths.push(length);
             if column_index == self.column_page_start.get() {
                 break;
             }
@@ -329,75 +831,147 @@ impl TableComponent {
         widths.reverse();
 
         let far_left_column_index = column_index;
-        let selected_column_index = widths.len().saturating_sub(1);
         let mut column_index = far_right_column_index + 1;
-        while widths.iter().map(|(_, width)| width).sum::<usize>() + widths.len()
+        while widths.iter().sum::<usize>() + widths.len()
             < area_width.saturating_sub(number_column_width) as usize
         {
-            let length = self
-                .rows
-                .iter()
-                .map(|row| {
-                    row.get(column_index)
-                        .map_or(String::new(), |cell| cell.to_string())
-                        .width()
-                })
-                .collect::<Vec<usize>>()
-                .iter()
-                .max()
-                .map_or(3, |v| {
-                    *v.max(
-                        self.headers
-                            .iter()
-                            .map(|header| header.to_string().width())
-                            .collect::<Vec<usize>>()
-                            .get(column_index)
-                            .unwrap_or(&3),
-                    )
-                    .clamp(&3, &20)
-                });
-            match self.headers.get(column_index) {
-                Some(header) => {
-                    widths.push((header.to_string(), length));
-                }
+            match columns.get(column_index) {
+                Some(i) => widths.push(self.column_width(*i).min(
+                    (area_width.saturating_sub(number_column_width) as usize).saturating_sub(2),
+                )),
                 None => break,
             }
             column_index += 1
         }
-        if self.selected_column_index() != self.headers.len().saturating_sub(1)
-            && column_index.saturating_sub(1) != self.headers.len().saturating_sub(1)
+        let last_column_index = columns.len().saturating_sub(1);
+        if scrolled_column_index != last_column_index
+            && column_index.saturating_sub(1) != last_column_index
         {
             widths.pop();
         }
         let far_right_column_index = column_index;
-        let mut constraints = widths
+        let mut constraints = frozen_widths
             .iter()
-            .map(|(_, width)| Constraint::Length(*width as u16))
+            .chain(widths.iter())
+            .map(|width| Constraint::Length(*width as u16))
             .collect::<Vec<Constraint>>();
-        if self.selected_column_index() != self.headers.len().saturating_sub(1)
-            && column_index.saturating_sub(1) != self.headers.len().saturating_sub(1)
+        if scrolled_column_index != last_column_index
+            && column_index.saturating_sub(1) != last_column_index
         {
             constraints.push(Constraint::Min(10));
         }
         constraints.insert(0, Constraint::Length(number_column_width));
         self.column_page_start.set(far_left_column_index);
 
+        let widths = frozen_widths
+            .iter()
+            .chain(widths.iter())
+            .copied()
+            .collect::<Vec<usize>>();
+        let fit = |row: Vec<String>| {
+            row.into_iter()
+                .enumerate()
+                .map(
+                    |(i, cell)| match i.checked_sub(1).and_then(|i| widths.get(i)) {
+                        Some(width) => truncate(&cell, *width),
+                        None => cell,
+                    },
+                )
+                .collect::<Vec<String>>()
+        };
         (
-            self.selection_area_corner
-                .map_or(selected_column_index + 1, |(x, _)| {
-                    if x > self.selected_column {
-                        (selected_column_index + 1)
-                            .saturating_sub(x.saturating_sub(self.selected_column))
-                    } else {
-                        (selected_column_index + 1)
-                            .saturating_add(self.selected_column.saturating_sub(x))
-                    }
-                }),
-            self.headers(far_left_column_index, far_right_column_index),
-            self.rows(far_left_column_index, far_right_column_index),
+            self.position_in_page(self.selected_column, frozen),
+            fit(self.headers(far_left_column_index, far_right_column_index)),
+            self.rows(far_left_column_index, far_right_column_index)
+                .into_iter()
+                .map(fit)
+                .collect(),
             constraints,
         )
     }
+
+    /// Draws the selected row as `column | type | value` lines, like `\x` in
+    /// psql.
+    fn draw_record<B: Backend>(&mut self, f: &mut Frame<B>, area: Rect, focused: bool) {
+        let row = match self.selected_row.selected().and_then(|i| self.rows.get(i)) {
+            Some(row) => row,
+            None => return,
+        };
+        let columns = self.columns();
+        self.scroll
+            .update(self.selected_column, columns.len(), area.height as usize);
+
+        let name_width = self.headers.iter().map(|h| h.width()).max().unwrap_or(0);
+        let type_width = self
+            .column_types
+            .iter()
+            .map(|t| t.width())
+            .max()
+            .unwrap_or(0);
+        let matcher = self.search.as_ref().and_then(Search::matcher);
+        let rows = columns.iter().enumerate().map(|(i, column)| {
+            let value = row.get(*column).map_or("", String::as_str);
+            Row::new(vec![
+                Cell::from(self.headers.get(*column).map_or("", String::as_str))
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+                Cell::from(self.column_types.get(*column).map_or("", String::as_str))
+                    .style(Style::default().fg(Color::DarkGray)),
+                Cell::from(value).style(if i == self.selected_column {
+                    Style::default().bg(Color::Blue)
+                } else if matches!(&matcher, Some(matcher) if matcher.is_match(value)) {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                } else {
+                    Style::default()
+                }),
+            ])
+            .height(value.lines().count().max(1) as u16)
+        });
+        let constraints = [
+            Constraint::Length(name_width.min(area.width as usize / 3) as u16),
+            Constraint::Length(type_width.min(area.width as usize / 5) as u16),
+            Constraint::Min(1),
+        ];
+        let table = Table::new(rows)
+            .column_spacing(3)
+            .style(if focused {
+                Style::default()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            })
+            .widths(&constraints);
+        let mut state = TableState::default();
+        state.select(Some(self.selected_column));
+        f.render_stateful_widget(table, area, &mut state);
+        self.scroll.draw(f, area);
+    }
+
+    /// In the record view fields are listed vertically, so the keys moving
+    /// between rows and columns swap roles.
+    fn record_event(&mut self, key: Key) -> Result<EventState> {
+        let last_field = self.headers.len().saturating_sub(1);
+        if key == self.key_config.scroll_down {
+            self.selected_column = (self.selected_column + 1).min(last_field);
+        } else if key == self.key_config.scroll_up {
+            self.selected_column = self.selected_column.saturating_sub(1);
+        } else if key == self.key_config.scroll_down_multiple_lines {
+            self.selected_column = (self.selected_column + 10).min(last_field);
+        } else if key == self.key_config.scroll_up_multiple_lines {
+            self.selected_column = self.selected_column.saturating_sub(10);
+        } else if key == self.key_config.scroll_to_top {
+            self.selected_column = 0;
+        } else if key == self.key_config.scroll_to_bottom {
+            self.selected_column = last_field;
+        } else if key == self.key_config.scroll_right {
+            self.next_row(1);
+            // Not consumed, so that the next page of records gets loaded.
+            return Ok(EventState::NotConsumed);
+        } else if key == self.key_config.scroll_left {
+            self.previous_row(1);
+        } else {
+            return Ok(EventState::NotConsumed);
+        }
+        Ok(EventState::Consumed)
+    }
 }
 
 impl StatefulDrawableComponent for TableComponent {
@@ -428,6 +1002,27 @@ impl StatefulDrawableComponent for TableComponent {
             area,
         );
 
+        if self.record_view && self.selected_row.selected().is_some() {
+            self.draw_record(f, chunks[1], focused);
+            TableValueComponent::draw_preview(
+                &self.selected_cells().unwrap_or_default(),
+                f,
+                chunks[0],
+                focused,
+            );
+            if self.search.is_some() {
+                self.draw_search(f, chunks[2], focused);
+            } else {
+                TableStatusComponent::new(
+                    Some(self.rows.len()),
+                    Some(self.headers.len()),
+                    self.table.as_ref().map(|t| t.1.clone()),
+                )
+                .draw(f, chunks[2], focused)?;
+            }
+            return Ok(());
+        }
+
         self.selected_row.selected().map_or_else(
             || {
                 self.scroll.reset();
@@ -452,6 +1047,25 @@ impl StatefulDrawableComponent for TableComponent {
             })
         });
         let header = Row::new(header_cells).height(1).bottom_margin(1);
+        let matcher = self.search.as_ref().and_then(Search::matcher);
+        let frozen = self.frozen(&self.columns());
+        let page_columns = self.page_columns(
+            self.column_page_start.get(),
+            self.column_page_start.get() + headers.len().saturating_sub(frozen + 1),
+        );
+        let is_match = |row_index: usize, column_index: usize| {
+            let cell = column_index
+                .checked_sub(1)
+                .and_then(|i| page_columns.get(i))
+                .and_then(|column| self.rows[row_index].get(*column));
+            matches!((&matcher, cell), (Some(matcher), Some(cell)) if matcher.is_match(cell))
+        };
+        let mark = |row_index: usize, column_index: usize| {
+            column_index
+                .checked_sub(1)
+                .and_then(|i| page_columns.get(i))
+                .and_then(|column| self.mark(row_index, *column))
+        };
         let rows = rows.iter().enumerate().map(|(row_index, item)| {
             let height = item
                 .iter()
@@ -463,6 +1077,16 @@ impl StatefulDrawableComponent for TableComponent {
                 Cell::from(c.to_string()).style(
                     if self.is_selected_cell(row_index, column_index, selected_column_index) {
                         Style::default().bg(Color::Blue)
+                    } else if is_match(row_index, column_index) {
+                        Style::default().fg(Color::Black).bg(Color::Yellow)
+                    } else if let Some(mark) = mark(row_index, column_index) {
+                        match mark {
+                            Mark::Inserted => Style::default().fg(Color::Green),
+                            Mark::Updated => Style::default().fg(Color::Yellow),
+                            Mark::Deleted => Style::default()
+                                .fg(Color::Red)
+                                .add_modifier(Modifier::CROSSED_OUT),
+                        }
                     } else if self.is_number_column(row_index, column_index) {
                         Style::default().add_modifier(Modifier::BOLD)
                     } else {
@@ -494,23 +1118,31 @@ impl StatefulDrawableComponent for TableComponent {
             },
         );
 
-        TableValueComponent::new(self.selected_cells().unwrap_or_default())
-            .draw(f, chunks[0], focused)?;
+        TableValueComponent::draw_preview(
+            &self.selected_cells().unwrap_or_default(),
+            f,
+            chunks[0],
+            focused,
+        );
 
-        TableStatusComponent::new(
-            if self.rows.is_empty() {
-                None
-            } else {
-                Some(self.rows.len())
-            },
-            if self.headers.is_empty() {
-                None
-            } else {
-                Some(self.headers.len())
-            },
-            self.table.as_ref().map(|t| t.1.clone()),
-        )
-        .draw(f, chunks[2], focused)?;
+        if self.search.is_some() {
+            self.draw_search(f, chunks[2], focused);
+        } else {
+            TableStatusComponent::new(
+                if self.rows.is_empty() {
+                    None
+                } else {
+                    Some(self.rows.len())
+                },
+                if self.headers.is_empty() {
+                    None
+                } else {
+                    Some(self.headers.len())
+                },
+                self.table.as_ref().map(|t| t.1.clone()),
+            )
+            .draw(f, chunks[2], focused)?;
+        }
 
         self.scroll.draw(f, chunks[1]);
         Ok(())
@@ -522,9 +1154,69 @@ impl Component for TableComponent {
         out.push(CommandInfo::new(command::extend_selection_by_one_cell(
             &self.key_config,
         )));
+        out.push(CommandInfo::new(command::toggle_record_view(
+            &self.key_config,
+        )));
+        out.push(CommandInfo::new(command::hide_column(&self.key_config)));
+        out.push(CommandInfo::new(command::move_column(&self.key_config)));
+        out.push(CommandInfo::new(command::freeze_columns(&self.key_config)));
+        out.push(CommandInfo::new(command::resize_column(&self.key_config)));
+        out.push(CommandInfo::new(command::search(&self.key_config)));
     }
 
     fn event(&mut self, key: Key) -> Result<EventState> {
+        if self.searching() {
+            return self.search_event(key);
+        }
+        if key == self.key_config.search {
+            self.start_search();
+            return Ok(EventState::Consumed);
+        } else if self.search.is_some() {
+            if key == self.key_config.search_next {
+                self.jump_to_match(true, false);
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.search_previous {
+                self.jump_to_match(false, false);
+                return Ok(EventState::Consumed);
+            } else if key == self.key_config.exit_popup {
+                self.search = None;
+                return Ok(EventState::Consumed);
+            }
+        }
+        if key == self.key_config.toggle_record_view {
+            self.record_view = !self.record_view;
+            self.reset_selection();
+            return Ok(EventState::Consumed);
+        }
+        if key == self.key_config.hide_column {
+            self.hide_column()?;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.show_all_columns {
+            self.show_all_columns()?;
+            return Ok(EventState::Consumed);
+        }
+        if self.record_view {
+            return self.record_event(key);
+        }
+        if key == self.key_config.move_column_left {
+            self.move_column(false)?;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.move_column_right {
+            self.move_column(true)?;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.freeze_columns {
+            self.freeze_columns()?;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.widen_column {
+            self.resize_column(Some(COLUMN_WIDTH_STEP))?;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.narrow_column {
+            self.resize_column(Some(-COLUMN_WIDTH_STEP))?;
+            return Ok(EventState::Consumed);
+        } else if key == self.key_config.fit_column {
+            self.resize_column(None)?;
+            return Ok(EventState::Consumed);
+        }
         if key == self.key_config.scroll_left {
             self.previous_column();
             return Ok(EventState::Consumed);
@@ -568,9 +1260,39 @@ impl Component for TableComponent {
 
 #[cfg(test)]
 mod test {
-    use super::{KeyConfig, TableComponent};
+    use super::{truncate, Component as _, Key, KeyConfig, TableComponent};
     use tui::layout::Constraint;
 
+    #[test]
+    fn test_record_view() {
+        let key_config = KeyConfig::default();
+        let mut component = TableComponent::new(key_config.clone());
+        component.headers = vec!["a", "b", "c"].iter().map(|h| h.to_string()).collect();
+        component.rows = vec![
+            vec!["a1", "b1", "c1"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            vec!["a2", "b2", "c2"]
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+        ];
+        component.selected_row.select(Some(0));
+
+        component.event(key_config.toggle_record_view).unwrap();
+        component.event(key_config.scroll_down).unwrap();
+        component.event(key_config.scroll_right).unwrap();
+        assert_eq!(component.selected_cells(), Some("b2".to_string()));
+        component.event(key_config.scroll_to_bottom).unwrap();
+        component.event(key_config.scroll_left).unwrap();
+        assert_eq!(component.selected_cells(), Some("c1".to_string()));
+
+        component.event(key_config.toggle_record_view).unwrap();
+        component.event(key_config.scroll_down).unwrap();
+        assert_eq!(component.selected_cells(), Some("c2".to_string()));
+    }
+
     #[test]
     fn test_headers() {
         let mut component = TableComponent::new(KeyConfig::default());
@@ -732,6 +1454,22 @@ mod test {
         assert_eq!(component.selected_cells(), Some("a,b\nd,e".to_string()));
     }
 
+    #[test]
+    fn test_selected_rows() {
+        let mut component = TableComponent::new(KeyConfig::default());
+        component.headers = ["1", "2"].iter().map(|h| h.to_string()).collect();
+        component.rows = ["a", "b", "c"]
+            .iter()
+            .map(|v| vec![v.to_string(), v.to_string()])
+            .collect();
+        assert_eq!(component.selected_rows(), None);
+        component.selected_row.select(Some(2));
+        assert_eq!(component.selected_rows(), Some(2..3));
+        component.expand_selected_area_y(false);
+        component.expand_selected_area_y(false);
+        assert_eq!(component.selected_rows(), Some(0..3));
+    }
+
     #[test]
     fn test_is_selected_cell_when_one_cell_selected() {
         //    1  2 3
@@ -875,4 +1613,149 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_hide_and_move_columns() {
+        let key_config = KeyConfig::default();
+        let mut component = TableComponent::new(key_config.clone());
+        component.headers = ["a", "b", "c"].iter().map(|h| h.to_string()).collect();
+        component.rows = vec![["1", "2", "3"].iter().map(|h| h.to_string()).collect()];
+        component.selected_row.select(Some(0));
+
+        component.event(key_config.move_column_right).unwrap();
+        assert_eq!(component.selected_column, 1);
+        assert_eq!(component.headers(0, 3), vec!["", "b", "a", "c"]);
+        assert_eq!(component.selected_cells(), Some("1".to_string()));
+
+        component.event(key_config.hide_column).unwrap();
+        assert_eq!(component.headers(0, 2), vec!["", "b", "c"]);
+        assert_eq!(component.rows(0, 2), vec![vec!["1", "2", "3"]]);
+        assert_eq!(component.selected_cells(), Some("3".to_string()));
+
+        component.event(key_config.hide_column).unwrap();
+        component.event(key_config.hide_column).unwrap();
+        assert_eq!(component.headers(0, 1), vec!["", "b"]);
+
+        component.event(key_config.show_all_columns).unwrap();
+        assert_eq!(component.headers(0, 3), vec!["", "b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_calculate_cell_widths_with_frozen_columns() {
+        let key_config = KeyConfig::default();
+        let mut component = TableComponent::new(key_config.clone());
+        component.headers = ["1", "2", "3", "4"].iter().map(|h| h.to_string()).collect();
+        component.rows = vec![["aaaaa", "bbbbb", "ccccc", "ddddd"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect()];
+        component.selected_row.select(Some(0));
+        component.event(key_config.freeze_columns).unwrap();
+        component.selected_column = 3;
+
+        let (selected_column_index, headers, rows, constraints) =
+            component.calculate_cell_widths(20);
+        assert_eq!(selected_column_index, 2);
+        assert_eq!(headers, vec!["", "1", "4"]);
+        assert_eq!(rows, vec![vec!["1", "aaaaa", "ddddd"]]);
+        assert_eq!(
+            constraints,
+            vec![
+                Constraint::Length(1),
+                Constraint::Length(5),
+                Constraint::Length(5),
+            ]
+        );
+        assert!(component.is_selected_cell(0, 2, selected_column_index));
+
+        component.selected_column = 0;
+        let (selected_column_index, headers, _, _) = component.calculate_cell_widths(20);
+        assert_eq!(selected_column_index, 1);
+        assert_eq!(headers, vec!["", "1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("abc", 3), "abc");
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("日本語テキスト", 6), "日本…");
+        assert_eq!(truncate("日本語テキスト", 5), "日本…");
+    }
+
+    #[test]
+    fn test_resize_column() {
+        let key_config = KeyConfig::default();
+        let mut component = TableComponent::new(key_config.clone());
+        component.set_max_column_width(5);
+        component.headers = ["id", "name"].iter().map(|h| h.to_string()).collect();
+        component.rows = vec![["1", "abcdefghij"].iter().map(|h| h.to_string()).collect()];
+        component.selected_row.select(Some(0));
+
+        let (_, _, rows, constraints) = component.calculate_cell_widths(40);
+        assert_eq!(rows, vec![vec!["1", "1", "abcd…"]]);
+        assert_eq!(constraints[2], Constraint::Length(5));
+
+        component.next_column();
+        component.event(key_config.widen_column).unwrap();
+        let (_, _, rows, _) = component.calculate_cell_widths(40);
+        assert_eq!(rows, vec![vec!["1", "1", "abcdef…"]]);
+
+        component.event(key_config.fit_column).unwrap();
+        let (_, _, rows, constraints) = component.calculate_cell_widths(40);
+        assert_eq!(rows, vec![vec!["1", "1", "abcdefghij"]]);
+        assert_eq!(constraints[2], Constraint::Length(10));
+
+        for _ in 0..10 {
+            component.event(key_config.narrow_column).unwrap();
+        }
+        let (_, _, rows, _) = component.calculate_cell_widths(40);
+        assert_eq!(rows, vec![vec!["1", "1", "ab…"]]);
+    }
+
+    #[test]
+    fn test_search() {
+        let key_config = KeyConfig::default();
+        let mut component = TableComponent::new(key_config.clone());
+        component.headers = ["a", "b"].iter().map(|h| h.to_string()).collect();
+        component.rows = vec![
+            ["Foo", "bar"].iter().map(|h| h.to_string()).collect(),
+            ["baz", "foo"].iter().map(|h| h.to_string()).collect(),
+            ["f00", "qux"].iter().map(|h| h.to_string()).collect(),
+        ];
+        component.selected_row.select(Some(0));
+
+        component.event(key_config.search).unwrap();
+        assert!(component.searching());
+        for c in "foo".chars() {
+            component.event(Key::Char(c)).unwrap();
+        }
+        assert_eq!(component.search_matches(), [(0, 0), (1, 1)]);
+        assert_eq!(component.selected_cells(), Some("Foo".to_string()));
+
+        component.event(key_config.toggle_search_case).unwrap();
+        assert_eq!(component.search_matches(), [(1, 1)]);
+        assert_eq!(component.selected_row.selected(), Some(1));
+        assert_eq!(component.selected_column, 1);
+
+        component.event(key_config.toggle_search_case).unwrap();
+        component.event(key_config.enter).unwrap();
+        assert!(!component.searching());
+        component.event(key_config.search_next).unwrap();
+        assert_eq!(component.selected_row.selected(), Some(1));
+        component.event(key_config.search_next).unwrap();
+        assert_eq!(component.selected_row.selected(), Some(0));
+        component.event(key_config.search_previous).unwrap();
+        assert_eq!(component.selected_row.selected(), Some(1));
+
+        component.event(key_config.search).unwrap();
+        component.event(key_config.toggle_search_regex).unwrap();
+        for c in "^f[o0]+$".chars() {
+            component.event(Key::Char(c)).unwrap();
+        }
+        assert_eq!(component.search_matches(), [(0, 0), (1, 1), (2, 0)]);
+        component.event(key_config.exit_popup).unwrap();
+        assert!(component.search.is_none());
+        assert_eq!(component.selected_row.selected(), Some(1));
+        assert_eq!(component.selected_column, 1);
+    }
 }
@@ -1,28 +1,83 @@
 use super::{Component, DrawableComponent, EventState};
-use crate::components::command::CommandInfo;
+use crate::clipboard::copy_to_clipboard;
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::database::string_to_binary;
 use crate::event::Key;
 use anyhow::Result;
+use std::cell::Cell;
+use std::collections::HashSet;
 use tui::{
     backend::Backend,
     layout::Rect,
     style::{Color, Style},
-    widgets::{Block, Borders, Paragraph},
+    text::Spans,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
+const JSON_INDENT: &str = "  ";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum View {
+    Text,
+    Json,
+    Hex,
+}
+
+/// Shows the value of a cell in full, in a popup covering the screen.
 pub struct TableValueComponent {
     value: String,
+    /// The pretty printed lines of the value, if it is JSON
+    json: Option<Vec<String>>,
+    /// The raw bytes of the value, if it is binary data
+    binary: Option<Vec<u8>>,
+    view: View,
+    /// The first line shown in the text and hex views, the selected line in
+    /// the JSON view
+    position: usize,
+    /// How far `position` can go, known once the value has been laid out
+    max_position: Cell<usize>,
+    /// JSON lines opening an object or array that is folded
+    folded: HashSet<usize>,
+    visible: bool,
+    key_config: KeyConfig,
 }
 
 impl TableValueComponent {
-    pub fn new(value: String) -> Self {
-        Self { value }
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            value: String::new(),
+            json: None,
+            binary: None,
+            view: View::Text,
+            position: 0,
+            max_position: Cell::new(0),
+            folded: HashSet::new(),
+            visible: false,
+            key_config,
+        }
     }
-}
 
-impl DrawableComponent for TableValueComponent {
-    fn draw<B: Backend>(&self, f: &mut Frame<B>, area: Rect, focused: bool) -> Result<()> {
-        let paragraph = Paragraph::new(self.value.clone())
+    pub fn open(&mut self, value: String) {
+        self.json = pretty_json(&value);
+        self.binary = string_to_binary(&value);
+        self.view = if self.json.is_some() {
+            View::Json
+        } else {
+            View::Text
+        };
+        self.value = value;
+        self.position = 0;
+        self.max_position.set(0);
+        self.folded.clear();
+        self.visible = true;
+    }
+
+    /// The value of the selected cell, shown in a single line above a table.
+    pub fn draw_preview<B: Backend>(value: &str, f: &mut Frame<B>, area: Rect, focused: bool) {
+        let paragraph = Paragraph::new(value.to_string())
             .block(Block::default().borders(Borders::BOTTOM))
             .style(if focused {
                 Style::default()
@@ -30,14 +85,385 @@ impl DrawableComponent for TableValueComponent {
                 Style::default().fg(Color::DarkGray)
             });
         f.render_widget(paragraph, area);
+    }
+
+    fn next_view(&mut self) {
+        self.view = match self.view {
+            View::Text if self.json.is_some() => View::Json,
+            View::Text | View::Json => View::Hex,
+            View::Hex => View::Text,
+        };
+        self.position = 0;
+        self.max_position.set(0);
+    }
+
+    /// The bytes shown in the hex view
+    fn bytes(&self) -> &[u8] {
+        self.binary.as_deref().unwrap_or(self.value.as_bytes())
+    }
+
+    /// The JSON lines left after folding, with their index in `json`
+    fn json_lines(&self) -> Vec<(usize, String)> {
+        let lines = match &self.json {
+            Some(lines) => lines,
+            None => return vec![],
+        };
+        let mut visible = vec![];
+        let mut i = 0;
+        while i < lines.len() {
+            match block_end(lines, i).filter(|_| self.folded.contains(&i)) {
+                Some(end) => {
+                    visible.push((i, format!("{} … {}", lines[i], lines[end].trim_start())));
+                    i = end + 1;
+                }
+                None => {
+                    visible.push((i, lines[i].clone()));
+                    i += 1;
+                }
+            }
+        }
+        visible
+    }
+
+    /// Folds the object or array opened on the selected line, or the one the
+    /// selected line is in.
+    fn fold(&mut self) {
+        let (lines, visible) = match &self.json {
+            Some(lines) => (lines, self.json_lines()),
+            None => return,
+        };
+        let (index, _) = match visible.get(self.position) {
+            Some(line) => line.clone(),
+            None => return,
+        };
+        if block_end(lines, index).is_some() && !self.folded.contains(&index) {
+            self.folded.insert(index);
+            return;
+        }
+        let indent = indent_of(&lines[index]);
+        if let Some(position) = visible[..self.position]
+            .iter()
+            .rposition(|(i, _)| indent_of(&lines[*i]) < indent && block_end(lines, *i).is_some())
+        {
+            self.folded.insert(visible[position].0);
+            self.position = position;
+        }
+    }
+
+    fn unfold(&mut self) {
+        if let Some((index, _)) = self.json_lines().get(self.position) {
+            self.folded.remove(index);
+        }
+    }
+
+    fn scroll(&mut self, lines: isize) {
+        self.position = if lines < 0 {
+            self.position.saturating_sub(lines.unsigned_abs())
+        } else {
+            (self.position + lines as usize).min(self.max_position.get())
+        };
+    }
+}
+
+/// The lines of `value` formatted as JSON, if it is a JSON object or array.
+/// Unlike `serde_json`'s pretty printer, this keeps the keys in their order.
+fn pretty_json(value: &str) -> Option<Vec<String>> {
+    let value = value.trim();
+    if !(value.starts_with('{') || value.starts_with('['))
+        || serde_json::from_str::<serde_json::Value>(value).is_err()
+    {
+        return None;
+    }
+
+    let mut lines = vec![];
+    let mut line = String::new();
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_string {
+            line.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                line.push(c);
+            }
+            '{' | '[' => {
+                while matches!(chars.peek(), Some(c) if c.is_whitespace()) {
+                    chars.next();
+                }
+                line.push(c);
+                // Empty objects and arrays stay on one line.
+                if let Some(close @ ('}' | ']')) = chars.peek().copied() {
+                    chars.next();
+                    line.push(close);
+                    continue;
+                }
+                depth += 1;
+                lines.push(std::mem::replace(&mut line, JSON_INDENT.repeat(depth)));
+            }
+            '}' | ']' => {
+                depth -= 1;
+                if !line.trim().is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                line = JSON_INDENT.repeat(depth);
+                line.push(c);
+            }
+            ',' => {
+                line.push(c);
+                lines.push(std::mem::replace(&mut line, JSON_INDENT.repeat(depth)));
+            }
+            ':' => line.push_str(": "),
+            c if c.is_whitespace() => (),
+            c => line.push(c),
+        }
+    }
+    if !line.trim().is_empty() {
+        lines.push(line);
+    }
+    Some(lines)
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// The line closing the object or array opened on line `start`
+fn block_end(lines: &[String], start: usize) -> Option<usize> {
+    if !(lines[start].ends_with('{') || lines[start].ends_with('[')) {
+        return None;
+    }
+    let indent = indent_of(&lines[start]);
+    lines[start + 1..]
+        .iter()
+        .position(|line| indent_of(line) == indent)
+        .map(|i| start + 1 + i)
+}
+
+/// `bytes` as offset, hex and printable characters, 16 a line.
+fn hex_dump(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .chunks(16)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let hex = chunk
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let printable = chunk
+                .iter()
+                .map(|b| {
+                    if b.is_ascii_graphic() || *b == b' ' {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>();
+            format!("{:08x}  {:<47}  |{}|", i * 16, hex, printable)
+        })
+        .collect()
+}
+
+impl DrawableComponent for TableValueComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let size = f.size();
+        let area = Rect::new(
+            2.min(size.width),
+            1.min(size.height),
+            size.width.saturating_sub(4),
+            size.height.saturating_sub(2),
+        );
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            "Value ({}) [{} view, {} copy, {} close]",
+            match self.view {
+                View::Text => "text",
+                View::Json => "json",
+                View::Hex => "hex",
+            },
+            Key::Tab,
+            self.key_config.copy,
+            self.key_config.exit_popup
+        ));
+        let inner = block.inner(area);
+        f.render_widget(Clear, area);
+
+        match self.view {
+            View::Text => {
+                let width = inner.width.max(1) as usize;
+                let height = self
+                    .value
+                    .lines()
+                    .map(|line| (line.width().max(1) - 1) / width + 1)
+                    .sum::<usize>();
+                self.max_position
+                    .set(height.saturating_sub(inner.height as usize));
+                let paragraph = Paragraph::new(self.value.as_str())
+                    .block(block)
+                    .wrap(Wrap { trim: false })
+                    .scroll((self.position as u16, 0));
+                f.render_widget(paragraph, area);
+            }
+            View::Json => {
+                let lines = self.json_lines();
+                self.max_position.set(lines.len().saturating_sub(1));
+                let items = lines
+                    .into_iter()
+                    .map(|(_, line)| ListItem::new(line))
+                    .collect::<Vec<_>>();
+                let mut state = ListState::default();
+                state.select(Some(self.position));
+                let list = List::new(items)
+                    .block(block)
+                    .highlight_style(Style::default().bg(Color::Blue));
+                f.render_stateful_widget(list, area, &mut state);
+            }
+            View::Hex => {
+                let lines = hex_dump(self.bytes());
+                self.max_position
+                    .set(lines.len().saturating_sub(inner.height as usize));
+                let paragraph = Paragraph::new(
+                    lines
+                        .iter()
+                        .map(|line| Spans::from(line.as_str()))
+                        .collect::<Vec<_>>(),
+                )
+                .block(block)
+                .scroll((self.position as u16, 0));
+                f.render_widget(paragraph, area);
+            }
+        }
         Ok(())
     }
 }
 
 impl Component for TableValueComponent {
-    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::inspect_cell(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        if key == self.key_config.exit_popup || key == self.key_config.inspect_cell {
+            self.hide();
+        } else if key == self.key_config.copy {
+            copy_to_clipboard(&self.value)?;
+        } else if key == Key::Tab {
+            self.next_view();
+        } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            self.scroll(1);
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.scroll(-1);
+        } else if key == self.key_config.scroll_down_multiple_lines {
+            self.scroll(10);
+        } else if key == self.key_config.scroll_up_multiple_lines {
+            self.scroll(-10);
+        } else if key == self.key_config.scroll_to_top {
+            self.position = 0;
+        } else if key == self.key_config.scroll_to_bottom {
+            self.position = self.max_position.get();
+        } else if self.view == View::Json && key == self.key_config.enter {
+            match self.json_lines().get(self.position) {
+                Some((index, _)) if self.folded.contains(index) => self.unfold(),
+                _ => self.fold(),
+            }
+        } else if self.view == View::Json && key == self.key_config.scroll_left {
+            self.fold();
+        } else if self.view == View::Json && key == self.key_config.scroll_right {
+            self.unfold();
+        }
+        // The inspector covers the screen, so it takes every key.
+        Ok(EventState::Consumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{hex_dump, pretty_json, Component as _, KeyConfig, TableValueComponent, View};
+
+    #[test]
+    fn test_pretty_json() {
+        assert_eq!(
+            pretty_json(r#"{"b": [1, {"c": "x,{"}], "a": {}, "d": []}"#).unwrap(),
+            vec![
+                "{",
+                r#"  "b": ["#,
+                "    1,",
+                "    {",
+                r#"      "c": "x,{""#,
+                "    }",
+                "  ],",
+                r#"  "a": {},"#,
+                r#"  "d": []"#,
+                "}",
+            ]
+        );
+        assert_eq!(pretty_json("42"), None);
+        assert_eq!(pretty_json("{not json}"), None);
+    }
+
+    #[test]
+    fn test_fold_json() {
+        let key_config = KeyConfig::default();
+        let mut value = TableValueComponent::new(key_config.clone());
+        value.open(r#"{"a": {"b": 1, "c": 2}, "d": 3}"#.to_string());
+        assert_eq!(value.view, View::Json);
+        value.max_position.set(usize::MAX);
+
+        value.event(key_config.scroll_down).unwrap();
+        value.event(key_config.scroll_down).unwrap();
+        value.event(key_config.scroll_left).unwrap();
+        assert_eq!(value.position, 1);
+        assert_eq!(
+            value
+                .json_lines()
+                .into_iter()
+                .map(|(_, line)| line)
+                .collect::<Vec<_>>(),
+            vec!["{", r#"  "a": { … },"#, r#"  "d": 3"#, "}"]
+        );
+
+        value.event(key_config.scroll_right).unwrap();
+        assert_eq!(value.json_lines().len(), 7);
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        assert_eq!(
+            hex_dump(b"gobang\n"),
+            vec!["00000000  67 6f 62 61 6e 67 0a                             |gobang.|"]
+        );
 
-    fn event(&mut self, _key: Key) -> Result<EventState> {
-        todo!("scroll");
+        let mut value = TableValueComponent::new(KeyConfig::default());
+        value.open("\\x67ff00".to_string());
+        assert_eq!(
+            hex_dump(value.bytes()),
+            vec!["00000000  67 ff 00                                         |g..|"]
+        );
     }
 }
@@ -0,0 +1,322 @@
+use super::{utils::input::Input, Component, DrawableComponent, EventState};
+use crate::components::command::{self, CommandInfo};
+use crate::config::KeyConfig;
+use crate::event::Key;
+use crate::sql::Dialect;
+use anyhow::Result;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+const WIDTH: u16 = 50;
+
+/// What can be done to an item of the database tree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreeAction {
+    RenameTable,
+    DuplicateTable { with_data: bool },
+    EmptyTable,
+    TruncateTable,
+    DropTable,
+    CreateSchema,
+    DropSchema,
+    CreateDatabase,
+    DropDatabase,
+}
+
+impl TreeAction {
+    /// The actions for a table, a schema or a database item, whichever is
+    /// selected
+    pub fn for_item(table: bool, schema: bool, dialect: Dialect) -> Vec<Self> {
+        if table {
+            vec![
+                Self::RenameTable,
+                Self::DuplicateTable { with_data: false },
+                Self::DuplicateTable { with_data: true },
+                Self::EmptyTable,
+                Self::TruncateTable,
+                Self::DropTable,
+            ]
+        } else if schema {
+            vec![Self::CreateSchema, Self::DropSchema]
+        } else {
+            match dialect {
+                Dialect::MySql => vec![Self::CreateDatabase, Self::DropDatabase],
+                Dialect::Postgres => {
+                    vec![Self::CreateSchema, Self::CreateDatabase, Self::DropDatabase]
+                }
+                Dialect::Sqlite => vec![],
+            }
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::RenameTable => "Rename table",
+            Self::DuplicateTable { with_data: false } => "Duplicate structure",
+            Self::DuplicateTable { with_data: true } => "Duplicate with data",
+            Self::EmptyTable => "Empty (delete all rows)",
+            Self::TruncateTable => "Truncate",
+            Self::DropTable => "Drop table",
+            Self::CreateSchema => "Create schema",
+            Self::DropSchema => "Drop schema",
+            Self::CreateDatabase => "Create database",
+            Self::DropDatabase => "Drop database",
+        }
+    }
+
+    /// The name the action asks for, given the name of the selected item,
+    /// if it asks for one
+    fn name_prompt(&self, item: &str) -> Option<(&'static str, String)> {
+        match self {
+            Self::RenameTable => Some(("New name", item.to_string())),
+            Self::DuplicateTable { .. } => Some(("Copy name", format!("{}_copy", item))),
+            Self::CreateSchema => Some(("Schema name", String::new())),
+            Self::CreateDatabase => Some(("Database name", String::new())),
+            _ => None,
+        }
+    }
+}
+
+/// Lists what can be done to the selected tree item, asking for a name
+/// when the action needs one.
+pub struct TreeMenuComponent {
+    visible: bool,
+    item: String,
+    actions: Vec<TreeAction>,
+    state: ListState,
+    /// The action whose name is being entered, and what it is asked as
+    naming: Option<(TreeAction, &'static str)>,
+    name: Input,
+    message: Option<String>,
+    chosen: Option<(TreeAction, String)>,
+    key_config: KeyConfig,
+}
+
+impl TreeMenuComponent {
+    pub fn new(key_config: KeyConfig) -> Self {
+        Self {
+            visible: false,
+            item: String::new(),
+            actions: vec![],
+            state: ListState::default(),
+            naming: None,
+            name: Input::new(),
+            message: None,
+            chosen: None,
+            key_config,
+        }
+    }
+
+    /// Opens the menu of `actions` for the tree item called `item`.
+    pub fn open(&mut self, item: String, actions: Vec<TreeAction>) {
+        self.item = item;
+        self.state
+            .select(if actions.is_empty() { None } else { Some(0) });
+        self.actions = actions;
+        self.naming = None;
+        self.message = None;
+        self.chosen = None;
+        self.visible = true;
+    }
+
+    /// The chosen action and the name entered for it, empty if it asks for
+    /// none. The menu is closed then.
+    pub fn take_chosen(&mut self) -> Option<(TreeAction, String)> {
+        let chosen = self.chosen.take()?;
+        self.hide();
+        Some(chosen)
+    }
+
+    fn choose(&mut self) {
+        if let Some((action, _)) = self.naming {
+            let name = self.name.value_str().trim();
+            if name.is_empty() {
+                self.message = Some("enter a name".to_string());
+            } else {
+                self.chosen = Some((action, name.to_string()));
+            }
+            return;
+        }
+        let action = match self.state.selected().and_then(|i| self.actions.get(i)) {
+            Some(action) => *action,
+            None => return,
+        };
+        match action.name_prompt(&self.item) {
+            Some((prompt, name)) => {
+                self.name.set_value(name);
+                self.naming = Some((action, prompt));
+            }
+            None => self.chosen = Some((action, String::new())),
+        }
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        if let Some(selected) = self.state.selected() {
+            self.state.select(Some(if down {
+                (selected + 1).min(self.actions.len() - 1)
+            } else {
+                selected.saturating_sub(1)
+            }));
+        }
+    }
+}
+
+impl DrawableComponent for TreeMenuComponent {
+    fn draw<B: Backend>(&self, f: &mut Frame<B>, _area: Rect, _focused: bool) -> Result<()> {
+        if !self.visible {
+            return Ok(());
+        }
+        let width = WIDTH.min(f.size().width);
+        let height = match self.naming {
+            Some(_) => 4,
+            None => self.actions.len().max(1) as u16 + 2,
+        }
+        .min(f.size().height);
+        let area = Rect::new(
+            (f.size().width.saturating_sub(width)) / 2,
+            (f.size().height.saturating_sub(height)) / 2,
+            width,
+            height,
+        );
+        let title = format!(
+            "{} [{} choose, {} close]",
+            self.item, self.key_config.enter, self.key_config.exit_popup
+        );
+        f.render_widget(Clear, area);
+        if let Some((_, prompt)) = self.naming {
+            let mut spans = vec![Span::styled(
+                format!("{}: ", prompt),
+                Style::default().fg(Color::Blue),
+            )];
+            spans.extend(self.name.spans());
+            f.render_widget(
+                Paragraph::new(vec![
+                    Spans::from(spans),
+                    Spans::from(Span::styled(
+                        self.message.clone().unwrap_or_default(),
+                        Style::default().fg(Color::Red),
+                    )),
+                ])
+                .block(Block::default().borders(Borders::ALL).title(title)),
+                area,
+            );
+            f.set_cursor(
+                (area.x + 1 + prompt.len() as u16 + 2)
+                    .saturating_add(self.name.cursor_position())
+                    .min(area.right().saturating_sub(2)),
+                area.y + 1,
+            );
+            return Ok(());
+        }
+        let items = if self.actions.is_empty() {
+            vec![ListItem::new(Span::styled(
+                "Nothing can be done here",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            self.actions
+                .iter()
+                .map(|action| ListItem::new(action.label()))
+                .collect()
+        };
+        let mut state = self.state.clone();
+        f.render_stateful_widget(
+            List::new(items)
+                .block(Block::default().borders(Borders::ALL).title(title))
+                .highlight_style(
+                    Style::default()
+                        .bg(Color::Blue)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            area,
+            &mut state,
+        );
+        Ok(())
+    }
+}
+
+impl Component for TreeMenuComponent {
+    fn commands(&self, out: &mut Vec<CommandInfo>) {
+        out.push(CommandInfo::new(command::tree_actions(&self.key_config)));
+    }
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        if !self.visible {
+            return Ok(EventState::NotConsumed);
+        }
+        self.message = None;
+        if key == self.key_config.exit_popup {
+            self.hide();
+        } else if key == self.key_config.enter {
+            self.choose();
+        } else if self.naming.is_some() {
+            self.name.event(key)?;
+        } else if key == self.key_config.scroll_down || key == self.key_config.move_down {
+            self.move_selection(true);
+        } else if key == self.key_config.scroll_up || key == self.key_config.move_up {
+            self.move_selection(false);
+        }
+        Ok(EventState::Consumed)
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Component as _, Dialect, KeyConfig, TreeAction, TreeMenuComponent};
+    use crate::event::Key;
+
+    #[test]
+    fn test_take_chosen() {
+        let key_config = KeyConfig::default();
+        let mut menu = TreeMenuComponent::new(key_config.clone());
+        menu.open(
+            "users".to_string(),
+            TreeAction::for_item(true, false, Dialect::Sqlite),
+        );
+        menu.event(key_config.move_down).unwrap();
+        menu.event(key_config.enter).unwrap();
+        assert_eq!(menu.take_chosen(), None);
+        for key in [Key::Backspace, Key::Backspace, Key::Char('2')] {
+            menu.event(key).unwrap();
+        }
+        menu.event(key_config.enter).unwrap();
+        assert_eq!(
+            menu.take_chosen(),
+            Some((
+                TreeAction::DuplicateTable { with_data: false },
+                "users_co2".to_string()
+            ))
+        );
+        assert!(!menu.visible);
+
+        menu.open(
+            "users".to_string(),
+            TreeAction::for_item(true, false, Dialect::Sqlite),
+        );
+        for _ in 0..10 {
+            menu.event(key_config.move_down).unwrap();
+        }
+        menu.event(key_config.enter).unwrap();
+        assert_eq!(
+            menu.take_chosen(),
+            Some((TreeAction::DropTable, String::new()))
+        );
+
+        menu.open(
+            "main".to_string(),
+            TreeAction::for_item(false, false, Dialect::Sqlite),
+        );
+        menu.event(key_config.enter).unwrap();
+        assert_eq!(menu.take_chosen(), None);
+    }
+}
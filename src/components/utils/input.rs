@@ -0,0 +1,363 @@
+use crate::clipboard::paste_from_clipboard;
+use crate::components::command::CommandInfo;
+use crate::components::{Component, EventState};
+use crate::event::Key;
+use anyhow::Result;
+use std::ops::Range;
+use tui::{
+    style::{Color, Style},
+    text::Span,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// A text buffer with a grapheme-aware cursor, shared by the SQL editor and
+/// the filters. It handles readline-style editing keys but leaves drawing to
+/// the component that owns it.
+#[derive(Default)]
+pub struct Input {
+    value: String,
+    /// Byte offset of the cursor, always on a grapheme boundary
+    cursor: usize,
+    /// Byte offset where the selection started, if any
+    selection_anchor: Option<usize>,
+}
+
+impl Input {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn value_str(&self) -> &str {
+        self.value.as_str()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.len();
+        self.selection_anchor = None;
+    }
+
+    pub fn reset(&mut self) {
+        self.set_value(String::new());
+    }
+
+    /// Byte offset of the cursor in `value_str()`.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the cursor to the byte offset `index`, clearing the selection.
+    pub fn set_cursor(&mut self, index: usize) {
+        let mut index = index.min(self.value.len());
+        while !self.value.is_char_boundary(index) {
+            index -= 1;
+        }
+        self.move_cursor(index, false);
+    }
+
+    /// Display width of the text between the start of the cursor line and
+    /// the cursor.
+    pub fn cursor_position(&self) -> u16 {
+        self.value[self.line_start()..self.cursor].width() as u16
+    }
+
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.selection_anchor?;
+        if anchor == self.cursor {
+            return None;
+        }
+        Some(anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    /// Splits the value into spans so that the selection is highlighted.
+    pub fn spans(&self) -> Vec<Span<'_>> {
+        self.highlighted_spans(&[])
+    }
+
+    /// Like `spans`, additionally styling the given byte ranges. The
+    /// selection is drawn on top of them.
+    pub fn highlighted_spans(&self, highlights: &[(Range<usize>, Style)]) -> Vec<Span<'_>> {
+        let mut ranges = highlights.to_vec();
+        if let Some(range) = self.selection() {
+            ranges.push((range, Style::default().bg(Color::Blue)));
+        }
+
+        let mut bounds = vec![0, self.value.len()];
+        for (range, _) in &ranges {
+            bounds.push(range.start.min(self.value.len()));
+            bounds.push(range.end.min(self.value.len()));
+        }
+        bounds.sort_unstable();
+        bounds.dedup();
+
+        let mut spans = vec![];
+        for pair in bounds.windows(2) {
+            let text = &self.value[pair[0]..pair[1]];
+            match ranges
+                .iter()
+                .rev()
+                .find(|(range, _)| range.contains(&pair[0]))
+            {
+                Some((_, style)) => spans.push(Span::styled(text, *style)),
+                None => spans.push(Span::raw(text)),
+            }
+        }
+        spans
+    }
+
+    /// Inserts `text` at the cursor, replacing the selection if there is one.
+    pub fn insert_str(&mut self, text: &str) {
+        self.delete_selection();
+        self.value.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    /// The word the cursor is at the end of, used to look up completion
+    /// candidates.
+    pub fn word_before_cursor(&self) -> &str {
+        let before = &self.value[..self.cursor];
+        before
+            .rfind(char::is_whitespace)
+            .map_or(before, |i| &before[i + 1..])
+    }
+
+    /// Replaces `word` before the cursor with `candidate`, followed by a space.
+    pub fn complete(&mut self, word: &str, candidate: &str) {
+        let start = self.cursor.saturating_sub(word.len());
+        if !self.value.is_char_boundary(start) {
+            return;
+        }
+        let followed_by_space = self.value[self.cursor..].starts_with(' ');
+        self.value.replace_range(start..self.cursor, candidate);
+        self.cursor = start + candidate.len();
+        if !followed_by_space {
+            self.value.insert(self.cursor, ' ');
+        }
+        self.cursor += 1;
+        self.selection_anchor = None;
+    }
+
+    fn delete_selection(&mut self) -> bool {
+        if let Some(range) = self.selection() {
+            self.value.replace_range(range.clone(), "");
+            self.cursor = range.start;
+            self.selection_anchor = None;
+            return true;
+        }
+        self.selection_anchor = None;
+        false
+    }
+
+    fn previous_boundary(&self) -> usize {
+        self.value[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.value[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |g| self.cursor + g.len())
+    }
+
+    fn previous_word_boundary(&self) -> usize {
+        let mut index = self.cursor;
+        let mut seen_word = false;
+        for (i, g) in self.value[..self.cursor].grapheme_indices(true).rev() {
+            let is_word = g.chars().any(char::is_alphanumeric) || g == "_";
+            if seen_word && !is_word {
+                break;
+            }
+            seen_word |= is_word;
+            index = i;
+        }
+        index
+    }
+
+    fn next_word_boundary(&self) -> usize {
+        let mut index = self.cursor;
+        let mut seen_word = false;
+        for (i, g) in self.value[self.cursor..].grapheme_indices(true) {
+            let is_word = g.chars().any(char::is_alphanumeric) || g == "_";
+            if seen_word && !is_word {
+                break;
+            }
+            seen_word |= is_word;
+            index = self.cursor + i + g.len();
+        }
+        index
+    }
+
+    fn line_start(&self) -> usize {
+        self.value[..self.cursor].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self) -> usize {
+        self.value[self.cursor..]
+            .find('\n')
+            .map_or(self.value.len(), |i| self.cursor + i)
+    }
+
+    fn move_cursor(&mut self, index: usize, select: bool) {
+        if select {
+            if self.selection_anchor.is_none() {
+                self.selection_anchor = Some(self.cursor);
+            }
+        } else {
+            self.selection_anchor = None;
+        }
+        self.cursor = index;
+    }
+
+    fn delete_range(&mut self, range: Range<usize>) {
+        if !self.delete_selection() {
+            self.cursor = range.start;
+            self.value.replace_range(range, "");
+        }
+    }
+}
+
+impl From<&str> for Input {
+    fn from(value: &str) -> Self {
+        let mut input = Self::new();
+        input.set_value(value);
+        input
+    }
+}
+
+impl Component for Input {
+    fn commands(&self, _out: &mut Vec<CommandInfo>) {}
+
+    fn event(&mut self, key: Key) -> Result<EventState> {
+        match key {
+            Key::Char(c) => {
+                let mut buf = [0; 4];
+                self.insert_str(c.encode_utf8(&mut buf));
+            }
+            Key::Ctrl('v') => {
+                let text = paste_from_clipboard()?;
+                self.insert_str(text.as_str());
+            }
+            Key::Backspace => self.delete_range(self.previous_boundary()..self.cursor),
+            Key::Delete | Key::Ctrl('d') => self.delete_range(self.cursor..self.next_boundary()),
+            Key::Ctrl('w') => self.delete_range(self.previous_word_boundary()..self.cursor),
+            Key::Ctrl('u') => self.delete_range(self.line_start()..self.cursor),
+            Key::Ctrl('k') => self.delete_range(self.cursor..self.line_end()),
+            Key::Left => self.move_cursor(self.previous_boundary(), false),
+            Key::Right => self.move_cursor(self.next_boundary(), false),
+            Key::ShiftLeft => self.move_cursor(self.previous_boundary(), true),
+            Key::ShiftRight => self.move_cursor(self.next_boundary(), true),
+            Key::Alt('b') => self.move_cursor(self.previous_word_boundary(), false),
+            Key::Alt('f') => self.move_cursor(self.next_word_boundary(), false),
+            Key::Home | Key::Ctrl('a') => self.move_cursor(self.line_start(), false),
+            Key::End | Key::Ctrl('e') => self.move_cursor(self.line_end(), false),
+            _ => return Ok(EventState::NotConsumed),
+        }
+        Ok(EventState::Consumed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Color, Component as _, Input, Key, Span, Style};
+
+    #[test]
+    fn test_insert_and_move_by_grapheme() {
+        let mut input = Input::new();
+        for c in "ae\u{301}b".chars() {
+            input.event(Key::Char(c)).unwrap();
+        }
+        assert_eq!(input.value_str(), "ae\u{301}b");
+        input.event(Key::Left).unwrap();
+        input.event(Key::Left).unwrap();
+        assert_eq!(input.cursor(), 1);
+        input.event(Key::Delete).unwrap();
+        assert_eq!(input.value_str(), "ab");
+    }
+
+    #[test]
+    fn test_cursor_position_wide_characters() {
+        let mut input = Input::from("日本語");
+        assert_eq!(input.cursor_position(), 6);
+        input.event(Key::Left).unwrap();
+        assert_eq!(input.cursor_position(), 4);
+        input.event(Key::Backspace).unwrap();
+        assert_eq!(input.value_str(), "日語");
+        assert_eq!(input.cursor_position(), 2);
+    }
+
+    #[test]
+    fn test_readline_keys() {
+        let mut input = Input::from("select id from users");
+        input.event(Key::Ctrl('w')).unwrap();
+        assert_eq!(input.value_str(), "select id from ");
+        input.event(Key::Alt('b')).unwrap();
+        assert_eq!(input.cursor(), 10);
+        input.event(Key::Ctrl('k')).unwrap();
+        assert_eq!(input.value_str(), "select id ");
+        input.event(Key::Ctrl('a')).unwrap();
+        assert_eq!(input.cursor(), 0);
+        input.event(Key::Alt('f')).unwrap();
+        assert_eq!(input.cursor(), 6);
+        input.event(Key::Ctrl('u')).unwrap();
+        assert_eq!(input.value_str(), " id ");
+        input.event(Key::End).unwrap();
+        assert_eq!(input.cursor(), 4);
+    }
+
+    #[test]
+    fn test_line_keys_in_multiline_value() {
+        let mut input = Input::from("select 1;\nselect 2;");
+        input.event(Key::Home).unwrap();
+        assert_eq!(input.cursor(), 10);
+        assert_eq!(input.cursor_position(), 0);
+        input.event(Key::Ctrl('k')).unwrap();
+        assert_eq!(input.value_str(), "select 1;\n");
+    }
+
+    #[test]
+    fn test_selection_is_replaced() {
+        let mut input = Input::from("abcd");
+        input.event(Key::ShiftLeft).unwrap();
+        input.event(Key::ShiftLeft).unwrap();
+        assert_eq!(input.selection(), Some(2..4));
+        input.event(Key::Char('x')).unwrap();
+        assert_eq!(input.value_str(), "abx");
+        assert_eq!(input.selection(), None);
+
+        input.event(Key::ShiftLeft).unwrap();
+        input.event(Key::Backspace).unwrap();
+        assert_eq!(input.value_str(), "ab");
+    }
+
+    #[test]
+    fn test_highlighted_spans() {
+        let mut input = Input::from("select 1; select 2");
+        input.event(Key::ShiftLeft).unwrap();
+        let highlight = Style::default().fg(Color::Red);
+        assert_eq!(
+            input.highlighted_spans(&[(10..18, highlight)]),
+            vec![
+                Span::raw("select 1; "),
+                Span::styled("select ", highlight),
+                Span::styled("2", Style::default().bg(Color::Blue)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_word_before_cursor() {
+        let mut input = Input::from("id = 1 an");
+        assert_eq!(input.word_before_cursor(), "an");
+        input.complete("an", "AND");
+        assert_eq!(input.value_str(), "id = 1 AND ");
+        assert_eq!(input.word_before_cursor(), "");
+    }
+}
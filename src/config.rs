@@ -24,6 +24,15 @@ pub struct Config {
     pub key_config: KeyConfig,
     #[serde(default)]
     pub log_level: LogLevel,
+    /// The widest a table column gets unless its width is set by hand
+    #[serde(default = "default_max_column_width")]
+    pub max_column_width: usize,
+}
+
+pub const DEFAULT_MAX_COLUMN_WIDTH: usize = 20;
+
+fn default_max_column_width() -> usize {
+    DEFAULT_MAX_COLUMN_WIDTH
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -61,6 +70,7 @@ impl Default for Config {
             }],
             key_config: KeyConfig::default(),
             log_level: LogLevel::default(),
+            max_column_width: DEFAULT_MAX_COLUMN_WIDTH,
         }
     }
 }
@@ -114,6 +124,53 @@ pub struct KeyConfig {
     pub tab_properties: Key,
     pub extend_or_shorten_widget_width_to_right: Key,
     pub extend_or_shorten_widget_width_to_left: Key,
+    pub execute_statement: Key,
+    pub format_query: Key,
+    pub toggle_param_type: Key,
+    pub recent_params: Key,
+    pub new_editor_tab: Key,
+    pub close_editor_tab: Key,
+    pub next_editor_tab: Key,
+    pub previous_editor_tab: Key,
+    pub rename_editor_tab: Key,
+    pub toggle_messages: Key,
+    pub explain_query: Key,
+    pub explain_analyze_query: Key,
+    pub inspect_cell: Key,
+    pub toggle_record_view: Key,
+    pub hide_column: Key,
+    pub show_all_columns: Key,
+    pub move_column_left: Key,
+    pub move_column_right: Key,
+    pub freeze_columns: Key,
+    pub widen_column: Key,
+    pub narrow_column: Key,
+    pub fit_column: Key,
+    pub search: Key,
+    pub search_next: Key,
+    pub search_previous: Key,
+    pub toggle_search_case: Key,
+    pub toggle_search_regex: Key,
+    pub quick_filter: Key,
+    pub quick_filter_not: Key,
+    pub column_stats: Key,
+    pub edit_cell: Key,
+    pub set_null: Key,
+    pub insert_row: Key,
+    pub delete_rows: Key,
+    pub review_changes: Key,
+    pub discard_changes: Key,
+    pub follow_foreign_key: Key,
+    pub go_back: Key,
+    pub show_references: Key,
+    pub add_column: Key,
+    pub rename_column: Key,
+    pub change_column_type: Key,
+    pub drop_column: Key,
+    pub create_index: Key,
+    pub drop_index: Key,
+    pub create_table: Key,
+    pub tree_actions: Key,
 }
 
 impl Default for KeyConfig {
@@ -135,7 +192,7 @@ impl Default for KeyConfig {
             focus_above: Key::Up,
             focus_connections: Key::Char('c'),
             open_help: Key::Char('?'),
-            filter: Key::Char('/'),
+            filter: Key::Ctrl('f'),
             scroll_down_multiple_lines: Key::Ctrl('d'),
             scroll_up_multiple_lines: Key::Ctrl('u'),
             scroll_to_top: Key::Char('g'),
@@ -153,6 +210,53 @@ impl Default for KeyConfig {
             tab_indexes: Key::Char('7'),
             extend_or_shorten_widget_width_to_right: Key::Char('>'),
             extend_or_shorten_widget_width_to_left: Key::Char('<'),
+            execute_statement: Key::Ctrl('r'),
+            format_query: Key::Alt('q'),
+            toggle_param_type: Key::Ctrl('t'),
+            recent_params: Key::Ctrl('p'),
+            new_editor_tab: Key::Alt('t'),
+            close_editor_tab: Key::Alt('w'),
+            next_editor_tab: Key::Alt('n'),
+            previous_editor_tab: Key::Alt('p'),
+            rename_editor_tab: Key::F2,
+            toggle_messages: Key::Alt('m'),
+            explain_query: Key::Alt('e'),
+            explain_analyze_query: Key::Alt('a'),
+            inspect_cell: Key::Char('v'),
+            toggle_record_view: Key::Char('x'),
+            hide_column: Key::Char('z'),
+            show_all_columns: Key::Char('Z'),
+            move_column_left: Key::Alt('h'),
+            move_column_right: Key::Alt('l'),
+            freeze_columns: Key::Char('p'),
+            widen_column: Key::Char('+'),
+            narrow_column: Key::Char('-'),
+            fit_column: Key::Char('='),
+            search: Key::Char('/'),
+            search_next: Key::Char('n'),
+            search_previous: Key::Char('N'),
+            toggle_search_case: Key::Alt('c'),
+            toggle_search_regex: Key::Alt('r'),
+            quick_filter: Key::Char('f'),
+            quick_filter_not: Key::Char('F'),
+            column_stats: Key::Char('s'),
+            edit_cell: Key::Char('e'),
+            set_null: Key::Ctrl('n'),
+            insert_row: Key::Char('a'),
+            delete_rows: Key::Char('d'),
+            review_changes: Key::Char('w'),
+            discard_changes: Key::Char('u'),
+            follow_foreign_key: Key::Char(']'),
+            go_back: Key::Char('['),
+            show_references: Key::Char('r'),
+            add_column: Key::Char('A'),
+            rename_column: Key::Char('R'),
+            change_column_type: Key::Char('T'),
+            drop_column: Key::Char('D'),
+            create_index: Key::Char('I'),
+            drop_index: Key::Char('X'),
+            create_table: Key::Char('C'),
+            tree_actions: Key::Char('m'),
         }
     }
 }
@@ -280,6 +384,21 @@ impl Connection {
         })
     }
 
+    /// The connection without its password, to key data saved for it
+    pub fn identifier(&self) -> anyhow::Result<String> {
+        let database_url = self.database_url()?;
+        let database_url = database_url.replacen(
+            &format!(":{}@", self.password.as_deref().unwrap_or_default()),
+            "@",
+            1,
+        );
+
+        Ok(match &self.name {
+            Some(name) => format!("[{}] {}", name, database_url),
+            None => database_url,
+        })
+    }
+
     pub fn is_mysql(&self) -> bool {
         matches!(self.r#type, DatabaseType::MySql)
     }
@@ -325,7 +444,7 @@ fn expand_path(path: &Path) -> Option<PathBuf> {
 
 #[cfg(test)]
 mod test {
-    use super::{expand_path, KeyConfig, Path, PathBuf};
+    use super::{expand_path, Connection, DatabaseType, KeyConfig, Path, PathBuf};
     use serde_json::Value;
     use std::env;
 
@@ -349,6 +468,31 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_connection_identifier() {
+        let mut conn = Connection {
+            r#type: DatabaseType::Postgres,
+            name: None,
+            user: Some("root".to_string()),
+            host: Some("localhost".to_string()),
+            port: Some(5432),
+            path: None,
+            password: Some("secret".to_string()),
+            database: Some("foo".to_string()),
+        };
+        assert_eq!(
+            conn.identifier().unwrap(),
+            "postgres://root@localhost:5432/foo"
+        );
+
+        conn.password = None;
+        conn.name = Some("Foo".to_string());
+        assert_eq!(
+            conn.identifier().unwrap(),
+            "[Foo] postgres://root@localhost:5432/foo"
+        );
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_expand_path() {
@@ -1,19 +1,31 @@
 pub mod mysql;
+pub mod plan;
 pub mod postgres;
 pub mod sqlite;
 
 pub use mysql::MySqlPool;
+pub use plan::PlanNode;
 pub use postgres::PostgresPool;
 pub use sqlite::SqlitePool;
 
+use crate::sql::{ddl::ColumnChange, quote, Dialect};
 use async_trait::async_trait;
 use database_tree::{Child, Database, Table};
+use serde::{Deserialize, Serialize};
 
 pub const RECORDS_LIMIT_PER_PAGE: u8 = 200;
 
 #[async_trait]
 pub trait Pool: Send + Sync {
-    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult>;
+    async fn execute(&self, query: &str, params: &[BindValue]) -> anyhow::Result<ExecuteResult>;
+    /// Runs `query` under `EXPLAIN`, actually executing it if `analyze` is set
+    /// and the database supports that.
+    async fn explain(
+        &self,
+        query: &str,
+        params: &[BindValue],
+        analyze: bool,
+    ) -> anyhow::Result<PlanNode>;
     async fn get_databases(&self) -> anyhow::Result<Vec<Database>>;
     async fn get_tables(&self, database: String) -> anyhow::Result<Vec<Child>>;
     async fn get_records(
@@ -38,29 +50,426 @@ pub trait Pool: Send + Sync {
         database: &Database,
         table: &Table,
     ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// The foreign keys of `table`, each with the table it references.
+    async fn get_foreign_key_references(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Reference>>;
+    /// The foreign keys of the tables in `database`, `table` included, that
+    /// reference `table`.
+    async fn get_references(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Reference>>;
     async fn get_indexes(
         &self,
         database: &Database,
         table: &Table,
     ) -> anyhow::Result<Vec<Box<dyn TableRow>>>;
+    /// Aggregates `column` over the rows matching `filter`. Averages are only
+    /// taken of `numeric` columns.
+    async fn get_column_stats(
+        &self,
+        database: &Database,
+        table: &Table,
+        column: &str,
+        numeric: bool,
+        filter: Option<String>,
+    ) -> anyhow::Result<ColumnStats>;
+    async fn count_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<i64>;
+    /// Runs `statements` in one transaction. If one fails or affects another
+    /// number of rows than expected, none of them take effect. Gives the
+    /// rows read back for each statement, in order.
+    async fn execute_batch(
+        &self,
+        statements: &[BatchStatement],
+    ) -> anyhow::Result<Vec<Option<InsertedRow>>>;
+    /// The statements that make `change` to the columns of `table`.
+    async fn alter_column_statements(
+        &self,
+        database: &Database,
+        table: &Table,
+        change: &ColumnChange,
+    ) -> anyhow::Result<Vec<String>>;
+    /// The statements that copy the structure of `table` to a new table
+    /// called `new_name`, and its rows too if `with_data`.
+    async fn duplicate_table_statements(
+        &self,
+        database: &Database,
+        table: &Table,
+        new_name: &str,
+        with_data: bool,
+    ) -> anyhow::Result<Vec<String>>;
+    /// Runs statements changing the schema, in one transaction where the
+    /// database rolls schema changes back.
+    async fn execute_ddl(&self, statements: &[String]) -> anyhow::Result<()>;
+    fn dialect(&self) -> Dialect;
     async fn close(&self);
 }
 
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteResult {
     Read {
         headers: Vec<String>,
+        /// The database's name for the type of each column
+        types: Vec<String>,
         rows: Vec<Vec<String>>,
         database: Database,
         table: Table,
+        /// Notices or warnings the server sent while running the query
+        messages: Vec<String>,
     },
     Write {
         updated_rows: u64,
+        messages: Vec<String>,
     },
 }
 
+/// A foreign key between a table and another, or the same, table
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    /// The schema of the table on the other side of the key
+    pub schema: Option<String>,
+    pub table: String,
+    /// Each column of the key with the column it references, which SQLite
+    /// leaves out when it is the primary key
+    pub columns: Vec<(String, Option<String>)>,
+}
+
+/// Adds `column` of the foreign key identified by `key`, read one column a
+/// row in key order, to the reference it belongs to in `references`.
+fn add_reference_column(
+    references: &mut Vec<(String, Reference)>,
+    key: String,
+    schema: Option<String>,
+    table: String,
+    column: (String, Option<String>),
+) {
+    match references.iter_mut().find(|(k, _)| *k == key) {
+        Some((_, reference)) => reference.columns.push(column),
+        None => references.push((
+            key,
+            Reference {
+                schema,
+                table,
+                columns: vec![column],
+            },
+        )),
+    }
+}
+
+/// A statement run as part of a batch, with the number of rows it must
+/// affect
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchStatement {
+    pub query: String,
+    pub params: Vec<BindValue>,
+    pub rows: u64,
+    /// For an `INSERT`, how the row it adds is read back
+    pub read_back: Option<ReadBack>,
+}
+
+/// How a batch reads back the row an `INSERT` added
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReadBack {
+    /// The statement returns it, with `RETURNING *`
+    Returned,
+    /// This query selects it right after the statement, for databases that
+    /// can't return it
+    Selected(String),
+}
+
+/// A row as stored by the database, defaults filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertedRow {
+    pub headers: Vec<String>,
+    pub row: Vec<String>,
+}
+
+/// Checks that a statement of a batch affected as many rows as it should.
+fn check_batch_statement(statement: &BatchStatement, rows: u64) -> anyhow::Result<()> {
+    if rows != statement.rows {
+        anyhow::bail!(
+            "`{}` affected {} rows instead of {}, so nothing was committed",
+            statement.query,
+            rows,
+            statement.rows
+        );
+    }
+    Ok(())
+}
+
+/// How the values of a column are distributed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ColumnStats {
+    pub rows: i64,
+    pub distinct: i64,
+    pub nulls: i64,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub average: Option<String>,
+    /// The most frequent values, null included, with how often they occur
+    pub top_values: Vec<(Option<String>, i64)>,
+}
+
+/// How many of the most frequent values `ColumnStats` lists
+pub const COLUMN_STATS_TOP_VALUES: u8 = 10;
+
+/// The quoted name of `table` to use in generated statements
+pub fn qualified_table_name(database: &Database, table: &Table, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::MySql => format!(
+            "{}.{}",
+            quote::identifier(&database.name, dialect),
+            quote::identifier(&table.name, dialect)
+        ),
+        Dialect::Postgres => format!(
+            "{}.{}",
+            quote::identifier(table.schema.as_deref().unwrap_or("public"), dialect),
+            quote::identifier(&table.name, dialect)
+        ),
+        Dialect::Sqlite => quote::identifier(&table.name, dialect),
+    }
+}
+
+/// The statement `Pool::count_records` runs
+fn count_query(table_name: &str, filter: Option<&str>) -> String {
+    format!(
+        "SELECT COUNT(*) FROM {}{}",
+        table_name,
+        filter.map_or(String::new(), |filter| format!(" WHERE {}", filter))
+    )
+}
+
+/// The statements `Pool::get_column_stats` runs: one for the aggregates and
+/// one for the most frequent values. Every value is read back as text.
+/// Postgres lacks `MIN` and `MAX` for some types such as `boolean` and
+/// `json`, so there the values of other than numeric columns are compared as
+/// text.
+fn column_stats_queries(
+    table_name: &str,
+    column: &str,
+    numeric: bool,
+    filter: Option<&str>,
+    dialect: Dialect,
+) -> (String, String) {
+    let as_text = |expression: &str| match dialect {
+        Dialect::MySql => format!("CAST({} AS CHAR)", expression),
+        Dialect::Postgres | Dialect::Sqlite => format!("CAST({} AS TEXT)", expression),
+    };
+    let column = quote::identifier(column, dialect);
+    let value = if numeric || dialect != Dialect::Postgres {
+        column.clone()
+    } else {
+        as_text(&column)
+    };
+    let filter = filter.map_or(String::new(), |filter| format!(" WHERE {}", filter));
+    (
+        format!(
+            "SELECT COUNT(*), COUNT(DISTINCT {value}), COUNT(*) - COUNT({value}), {min}, {max}, {average} FROM {table}{filter}",
+            value = value,
+            min = as_text(&format!("MIN({})", value)),
+            max = as_text(&format!("MAX({})", value)),
+            average = if numeric {
+                as_text(&format!("AVG({})", value))
+            } else {
+                "NULL".to_string()
+            },
+            table = table_name,
+            filter = filter,
+        ),
+        format!(
+            "SELECT {value}, COUNT(*) FROM {table}{filter} GROUP BY 1 ORDER BY 2 DESC, 1 LIMIT {limit}",
+            value = as_text(&column),
+            table = table_name,
+            filter = filter,
+            limit = COLUMN_STATS_TOP_VALUES,
+        ),
+    )
+}
+
+/// An error the database returned for a query, with the fields servers send
+/// besides the message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    pub message: String,
+    /// The SQLSTATE code
+    pub code: Option<String>,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+    pub position: Option<ErrorPosition>,
+}
+
+/// Where in the query an error was found
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorPosition {
+    /// 1-based character index, as reported by Postgres
+    Character(usize),
+    /// The text starting at the error and its 1-based line, as in MySQL's
+    /// "near '...' at line N"
+    Near { text: String, line: usize },
+}
+
+impl QueryError {
+    /// Byte offset in `query` the error points at.
+    pub fn offset(&self, query: &str) -> Option<usize> {
+        match self.position.as_ref()? {
+            ErrorPosition::Character(position) => query
+                .char_indices()
+                .nth(position.checked_sub(1)?)
+                .map(|(i, _)| i),
+            ErrorPosition::Near { text, line } => {
+                let line_start = match line.checked_sub(2) {
+                    Some(n) => query.match_indices('\n').nth(n)?.0 + 1,
+                    None => 0,
+                };
+                let near = text.lines().next().unwrap_or_default();
+                if near.is_empty() {
+                    return Some(query.len());
+                }
+                query[line_start..].find(near).map(|i| line_start + i)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(code) = &self.code {
+            write!(f, " (SQLSTATE {})", code)?;
+        }
+        if let Some(detail) = &self.detail {
+            write!(f, "\nDETAIL: {}", detail)?;
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, "\nHINT: {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// A value sent to the database as a bind parameter
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindValue {
+    Null,
+    Text(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+}
+
+/// The type a bind parameter typed in by the user is sent as
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ParamType {
+    Text,
+    Integer,
+    Float,
+    Boolean,
+    Null,
+}
+
+impl ParamType {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Text => Self::Integer,
+            Self::Integer => Self::Float,
+            Self::Float => Self::Boolean,
+            Self::Boolean => Self::Null,
+            Self::Null => Self::Text,
+        }
+    }
+
+    pub fn parse(self, value: &str) -> anyhow::Result<BindValue> {
+        let invalid = || anyhow::anyhow!("`{}` is not a valid {}", value, self);
+        Ok(match self {
+            Self::Text => BindValue::Text(value.to_string()),
+            Self::Integer => BindValue::Integer(value.trim().parse().map_err(|_| invalid())?),
+            Self::Float => BindValue::Float(value.trim().parse().map_err(|_| invalid())?),
+            Self::Boolean => match value.trim().to_lowercase().as_str() {
+                "true" | "t" | "1" => BindValue::Boolean(true),
+                "false" | "f" | "0" => BindValue::Boolean(false),
+                _ => return Err(invalid()),
+            },
+            Self::Null => BindValue::Null,
+        })
+    }
+}
+
+impl std::fmt::Display for ParamType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
 pub trait TableRow: std::marker::Send {
     fn fields(&self) -> Vec<String>;
     fn columns(&self) -> Vec<String>;
+
+    /// The value of the field called `name`, if there is one
+    fn field(&self, name: &str) -> Option<String> {
+        let index = self.fields().iter().position(|field| field == name)?;
+        self.columns().get(index).cloned()
+    }
+}
+
+/// The columns that identify a row, given the constraints `get_constraints`
+/// returns: those of the primary key, or else of the first unique key.
+/// Empty if the table has neither.
+pub fn key_columns(constraints: &[Box<dyn TableRow>]) -> Vec<String> {
+    let mut keys: Vec<(String, Vec<String>)> = vec![];
+    for constraint in constraints {
+        let (name, column) = match (constraint.field("name"), constraint.field("column_name")) {
+            (Some(name), Some(column)) => (name, column),
+            _ => continue,
+        };
+        match keys.iter_mut().find(|(key, _)| *key == name) {
+            Some((_, columns)) if !columns.contains(&column) => columns.push(column),
+            Some(_) => (),
+            None => keys.push((name, vec![column])),
+        }
+    }
+    let is_primary = |name: &str| {
+        name == "PRIMARY"
+            || name.ends_with("_pkey")
+            || constraints.iter().any(|constraint| {
+                constraint.field("name").as_deref() == Some(name)
+                    && constraint.field("origin").as_deref() == Some("pk")
+            })
+    };
+    keys.iter()
+        .find(|(name, _)| is_primary(name))
+        .or_else(|| keys.first())
+        .map_or(vec![], |(_, columns)| columns.clone())
+}
+
+/// Shows binary data as Postgres does, as `\\x` followed by the bytes in hex.
+pub fn binary_to_string(bytes: &[u8]) -> String {
+    bytes.iter().fold("\\x".to_string(), |mut value, b| {
+        value.push_str(&format!("{:02x}", b));
+        value
+    })
+}
+
+/// The bytes of a value shown by `binary_to_string`, if it is one.
+pub fn string_to_binary(value: &str) -> Option<Vec<u8>> {
+    let hex = value.strip_prefix("\\x")?;
+    if hex.len() % 2 != 0 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
 }
 
 #[macro_export]
@@ -69,3 +478,151 @@ macro_rules! get_or_null {
         $value.map_or("NULL".to_string(), |v| v.to_string())
     };
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        add_reference_column, binary_to_string, column_stats_queries, key_columns,
+        string_to_binary, Dialect, ErrorPosition, QueryError, Reference, TableRow,
+    };
+
+    fn error(position: ErrorPosition) -> QueryError {
+        QueryError {
+            message: "syntax error".to_string(),
+            code: Some("42601".to_string()),
+            detail: None,
+            hint: Some("check it".to_string()),
+            position: Some(position),
+        }
+    }
+
+    #[test]
+    fn test_error_offset() {
+        let query = "select 'é', a\nform users";
+        assert_eq!(error(ErrorPosition::Character(15)).offset(query), Some(15));
+        assert_eq!(
+            error(ErrorPosition::Near {
+                text: "form users".to_string(),
+                line: 2
+            })
+            .offset(query),
+            Some(15)
+        );
+        assert_eq!(
+            error(ErrorPosition::Near {
+                text: String::new(),
+                line: 2
+            })
+            .offset(query),
+            Some(query.len())
+        );
+        assert_eq!(error(ErrorPosition::Character(99)).offset(query), None);
+    }
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            error(ErrorPosition::Character(1)).to_string(),
+            "syntax error (SQLSTATE 42601)\nHINT: check it"
+        );
+    }
+
+    #[test]
+    fn test_column_stats_queries() {
+        let (stats, top_values) =
+            column_stats_queries("`db`.`users`", "age", true, Some("id > 1"), Dialect::MySql);
+        assert_eq!(
+            stats,
+            "SELECT COUNT(*), COUNT(DISTINCT `age`), COUNT(*) - COUNT(`age`), CAST(MIN(`age`) AS CHAR), CAST(MAX(`age`) AS CHAR), CAST(AVG(`age`) AS CHAR) FROM `db`.`users` WHERE id > 1"
+        );
+        assert_eq!(
+            top_values,
+            "SELECT CAST(`age` AS CHAR), COUNT(*) FROM `db`.`users` WHERE id > 1 GROUP BY 1 ORDER BY 2 DESC, 1 LIMIT 10"
+        );
+
+        let (stats, _) = column_stats_queries(
+            "\"public\".\"users\"",
+            "active",
+            false,
+            None,
+            Dialect::Postgres,
+        );
+        assert_eq!(
+            stats,
+            "SELECT COUNT(*), COUNT(DISTINCT CAST(\"active\" AS TEXT)), COUNT(*) - COUNT(CAST(\"active\" AS TEXT)), CAST(MIN(CAST(\"active\" AS TEXT)) AS TEXT), CAST(MAX(CAST(\"active\" AS TEXT)) AS TEXT), NULL FROM \"public\".\"users\""
+        );
+    }
+
+    struct Constraint(&'static str, &'static str);
+
+    impl TableRow for Constraint {
+        fn fields(&self) -> Vec<String> {
+            vec!["name".to_string(), "column_name".to_string()]
+        }
+
+        fn columns(&self) -> Vec<String> {
+            vec![self.0.to_string(), self.1.to_string()]
+        }
+    }
+
+    #[test]
+    fn test_key_columns() {
+        let constraints: Vec<Box<dyn TableRow>> = vec![
+            Box::new(Constraint("users_email_key", "email")),
+            Box::new(Constraint("users_pkey", "org_id")),
+            Box::new(Constraint("users_pkey", "id")),
+            Box::new(Constraint("users_pkey", "id")),
+        ];
+        assert_eq!(key_columns(&constraints), vec!["org_id", "id"]);
+        assert_eq!(key_columns(&constraints[..1]), vec!["email"]);
+        assert!(key_columns(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_add_reference_column() {
+        let mut references = vec![];
+        for (key, table, column, ref_column) in [
+            ("lines_order_fkey", "lines", "order_id", "id"),
+            ("lines_product_fkey", "lines", "product_id", "id"),
+            ("lines_order_fkey", "lines", "order_version", "version"),
+        ] {
+            add_reference_column(
+                &mut references,
+                key.to_string(),
+                None,
+                table.to_string(),
+                (column.to_string(), Some(ref_column.to_string())),
+            );
+        }
+        let pair =
+            |column: &str, ref_column: &str| (column.to_string(), Some(ref_column.to_string()));
+        assert_eq!(
+            references
+                .into_iter()
+                .map(|(_, reference)| reference)
+                .collect::<Vec<_>>(),
+            vec![
+                Reference {
+                    schema: None,
+                    table: "lines".to_string(),
+                    columns: vec![pair("order_id", "id"), pair("order_version", "version")],
+                },
+                Reference {
+                    schema: None,
+                    table: "lines".to_string(),
+                    columns: vec![pair("product_id", "id")],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_binary_to_string() {
+        assert_eq!(binary_to_string(&[0x67, 0x00, 0xff]), "\\x6700ff");
+        assert_eq!(string_to_binary("\\x6700ff"), Some(vec![0x67, 0x00, 0xff]));
+        assert_eq!(string_to_binary("\\x"), Some(vec![]));
+        assert_eq!(string_to_binary("\\x670"), None);
+        assert_eq!(string_to_binary("\\xzz"), None);
+        assert_eq!(string_to_binary("gobang"), None);
+    }
+}
@@ -1,11 +1,22 @@
 use crate::get_or_null;
+use crate::sql::ddl::{self, ColumnChange};
+use crate::sql::{quote, Dialect};
 
-use super::{ExecuteResult, Pool, TableRow, RECORDS_LIMIT_PER_PAGE};
+use super::{
+    add_reference_column, binary_to_string, check_batch_statement, column_stats_queries,
+    count_query, plan, qualified_table_name, BatchStatement, BindValue, ColumnStats, ErrorPosition,
+    ExecuteResult, InsertedRow, PlanNode, Pool, QueryError, ReadBack, Reference, TableRow,
+    RECORDS_LIMIT_PER_PAGE,
+};
 use async_trait::async_trait;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use database_tree::{Child, Database, Table};
 use futures::TryStreamExt;
-use sqlx::mysql::{MySqlColumn, MySqlPoolOptions, MySqlRow};
+use sqlx::mysql::{
+    MySql, MySqlArguments, MySqlColumn, MySqlConnection, MySqlDatabaseError, MySqlPoolOptions,
+    MySqlRow,
+};
+use sqlx::query::Query;
 use sqlx::{Column as _, Row as _, TypeInfo as _};
 use std::time::Duration;
 
@@ -44,6 +55,7 @@ pub struct Column {
     r#type: Option<String>,
     null: Option<String>,
     default: Option<String>,
+    extra: Option<String>,
     comment: Option<String>,
 }
 
@@ -54,6 +66,7 @@ impl TableRow for Column {
             "type".to_string(),
             "null".to_string(),
             "default".to_string(),
+            "extra".to_string(),
             "comment".to_string(),
         ]
     }
@@ -72,6 +85,9 @@ impl TableRow for Column {
             self.default
                 .as_ref()
                 .map_or(String::new(), |default| default.to_string()),
+            self.extra
+                .as_ref()
+                .map_or(String::new(), |extra| extra.to_string()),
             self.comment
                 .as_ref()
                 .map_or(String::new(), |comment| comment.to_string()),
@@ -146,29 +162,41 @@ impl TableRow for Index {
 
 #[async_trait]
 impl Pool for MySqlPool {
-    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+    async fn execute(&self, query: &str, params: &[BindValue]) -> anyhow::Result<ExecuteResult> {
         let query = query.trim();
+        // Warnings are per session, so they have to be read on the same
+        // connection the query ran on.
+        let mut conn = self.pool.acquire().await?;
 
         if query.to_uppercase().starts_with("SELECT") {
-            let mut rows = sqlx::query(query).fetch(&self.pool);
             let mut headers = vec![];
+            let mut types = vec![];
             let mut records = vec![];
-            while let Some(row) = rows.try_next().await? {
+            let mut rows = bind_values(sqlx::query(query), params).fetch(&mut *conn);
+            while let Some(row) = rows.try_next().await.map_err(query_error)? {
                 headers = row
                     .columns()
                     .iter()
                     .map(|column| column.name().to_string())
                     .collect();
+                types = row
+                    .columns()
+                    .iter()
+                    .map(|column| column.type_info().name().to_string())
+                    .collect();
                 let mut new_row = vec![];
                 for column in row.columns() {
                     new_row.push(convert_column_value_to_string(&row, column)?)
                 }
                 records.push(new_row)
             }
+            drop(rows);
 
             return Ok(ExecuteResult::Read {
                 headers,
+                types,
                 rows: records,
+                messages: warnings(&mut conn).await?,
                 database: Database {
                     name: "-".to_string(),
                     children: Vec::new(),
@@ -183,12 +211,32 @@ impl Pool for MySqlPool {
             });
         }
 
-        let result = sqlx::query(query).execute(&self.pool).await?;
+        let result = bind_values(sqlx::query(query), params)
+            .execute(&mut *conn)
+            .await
+            .map_err(query_error)?;
         Ok(ExecuteResult::Write {
             updated_rows: result.rows_affected(),
+            messages: warnings(&mut conn).await?,
         })
     }
 
+    /// `EXPLAIN ANALYZE` only has a text format in MySQL, so `analyze` is
+    /// ignored.
+    async fn explain(
+        &self,
+        query: &str,
+        params: &[BindValue],
+        _analyze: bool,
+    ) -> anyhow::Result<PlanNode> {
+        let query = format!("EXPLAIN FORMAT=JSON {}", query.trim());
+        let row = bind_values(sqlx::query(&query), params)
+            .fetch_one(&self.pool)
+            .await?;
+        let json: String = row.try_get_unchecked(0)?;
+        plan::from_mysql_json(&serde_json::from_str(&json)?)
+    }
+
     async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
         let databases = sqlx::query("SHOW DATABASES")
             .fetch_all(&self.pool)
@@ -282,6 +330,7 @@ impl Pool for MySqlPool {
                 r#type: row.try_get("Type")?,
                 null: row.try_get("Null")?,
                 default: row.try_get("Default")?,
+                extra: row.try_get("Extra")?,
                 comment: row.try_get("Comment")?,
             }))
         }
@@ -358,6 +407,100 @@ impl Pool for MySqlPool {
         Ok(foreign_keys)
     }
 
+    async fn get_foreign_key_references(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Reference>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            CONSTRAINT_NAME,
+            COLUMN_NAME,
+            REFERENCED_TABLE_NAME,
+            REFERENCED_COLUMN_NAME
+        FROM
+            INFORMATION_SCHEMA.KEY_COLUMN_USAGE
+        WHERE
+            TABLE_SCHEMA = ?
+            AND TABLE_NAME = ?
+            AND REFERENCED_TABLE_NAME IS NOT NULL
+        ORDER BY
+            CONSTRAINT_NAME,
+            ORDINAL_POSITION
+        ",
+        )
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut references = vec![];
+        while let Some(row) = rows.try_next().await? {
+            add_reference_column(
+                &mut references,
+                row.try_get("CONSTRAINT_NAME")?,
+                None,
+                row.try_get("REFERENCED_TABLE_NAME")?,
+                (
+                    row.try_get("COLUMN_NAME")?,
+                    row.try_get("REFERENCED_COLUMN_NAME")?,
+                ),
+            );
+        }
+        Ok(references
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect())
+    }
+
+    async fn get_references(
+        &self,
+        database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Reference>> {
+        let mut rows = sqlx::query(
+            "
+        SELECT
+            TABLE_NAME,
+            CONSTRAINT_NAME,
+            COLUMN_NAME,
+            REFERENCED_COLUMN_NAME
+        FROM
+            INFORMATION_SCHEMA.KEY_COLUMN_USAGE
+        WHERE
+            TABLE_SCHEMA = ?
+            AND REFERENCED_TABLE_SCHEMA = ?
+            AND REFERENCED_TABLE_NAME = ?
+        ORDER BY
+            TABLE_NAME,
+            CONSTRAINT_NAME,
+            ORDINAL_POSITION
+        ",
+        )
+        .bind(&database.name)
+        .bind(&database.name)
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut references = vec![];
+        while let Some(row) = rows.try_next().await? {
+            let table_name: String = row.try_get("TABLE_NAME")?;
+            let constraint_name: String = row.try_get("CONSTRAINT_NAME")?;
+            add_reference_column(
+                &mut references,
+                format!("{}.{}", table_name, constraint_name),
+                None,
+                table_name,
+                (
+                    row.try_get("COLUMN_NAME")?,
+                    row.try_get("REFERENCED_COLUMN_NAME")?,
+                ),
+            );
+        }
+        Ok(references
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect())
+    }
+
     async fn get_indexes(
         &self,
         database: &Database,
@@ -391,11 +534,230 @@ impl Pool for MySqlPool {
         Ok(foreign_keys)
     }
 
+    async fn get_column_stats(
+        &self,
+        database: &Database,
+        table: &Table,
+        column: &str,
+        numeric: bool,
+        filter: Option<String>,
+    ) -> anyhow::Result<ColumnStats> {
+        let (stats_query, top_values_query) = column_stats_queries(
+            &qualified_table_name(database, table, self.dialect()),
+            column,
+            numeric,
+            filter.as_deref(),
+            self.dialect(),
+        );
+        let row = sqlx::query(&stats_query).fetch_one(&self.pool).await?;
+        let mut top_values = vec![];
+        for row in sqlx::query(&top_values_query).fetch_all(&self.pool).await? {
+            top_values.push((row.try_get_unchecked(0)?, row.try_get_unchecked(1)?));
+        }
+        Ok(ColumnStats {
+            rows: row.try_get_unchecked(0)?,
+            distinct: row.try_get_unchecked(1)?,
+            nulls: row.try_get_unchecked(2)?,
+            min: row.try_get_unchecked(3)?,
+            max: row.try_get_unchecked(4)?,
+            average: row.try_get_unchecked(5)?,
+            top_values,
+        })
+    }
+
+    async fn count_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<i64> {
+        let query = count_query(
+            &qualified_table_name(database, table, self.dialect()),
+            filter.as_deref(),
+        );
+        Ok(sqlx::query(&query)
+            .fetch_one(&self.pool)
+            .await?
+            .try_get_unchecked(0)?)
+    }
+
+    async fn execute_batch(
+        &self,
+        statements: &[BatchStatement],
+    ) -> anyhow::Result<Vec<Option<InsertedRow>>> {
+        let mut transaction = self.pool.begin().await?;
+        let mut inserted = vec![];
+        for statement in statements {
+            let query = bind_values(sqlx::query(&statement.query), &statement.params);
+            // Dropping the transaction rolls it back.
+            let row = match &statement.read_back {
+                Some(ReadBack::Returned) => {
+                    let row = query
+                        .fetch_optional(&mut transaction)
+                        .await
+                        .map_err(query_error)?;
+                    check_batch_statement(statement, row.is_some() as u64)?;
+                    row
+                }
+                Some(ReadBack::Selected(select)) => {
+                    let result = query.execute(&mut transaction).await.map_err(query_error)?;
+                    check_batch_statement(statement, result.rows_affected())?;
+                    sqlx::query(select).fetch_optional(&mut transaction).await?
+                }
+                None => {
+                    let result = query.execute(&mut transaction).await.map_err(query_error)?;
+                    check_batch_statement(statement, result.rows_affected())?;
+                    None
+                }
+            };
+            inserted.push(row.as_ref().map(inserted_row).transpose()?);
+        }
+        transaction.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn alter_column_statements(
+        &self,
+        database: &Database,
+        table: &Table,
+        change: &ColumnChange,
+    ) -> anyhow::Result<Vec<String>> {
+        let table_name = qualified_table_name(database, table, self.dialect());
+        if let ColumnChange::ChangeType { column, type_name } = change {
+            let query = format!("SHOW FULL COLUMNS FROM {} WHERE Field = ?", table_name);
+            let row = sqlx::query(&query)
+                .bind(column)
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("{} has no column {}", table.name, column))?;
+            let column = Column {
+                name: row.try_get("Field")?,
+                r#type: row.try_get("Type")?,
+                null: row.try_get("Null")?,
+                default: row.try_get("Default")?,
+                extra: row.try_get("Extra")?,
+                comment: row.try_get("Comment")?,
+            };
+            return Ok(vec![modify_column(&table_name, &column, type_name)?]);
+        }
+        Ok(ddl::alter_column(&table_name, change, self.dialect())
+            .into_iter()
+            .collect())
+    }
+
+    async fn duplicate_table_statements(
+        &self,
+        database: &Database,
+        table: &Table,
+        new_name: &str,
+        with_data: bool,
+    ) -> anyhow::Result<Vec<String>> {
+        let new_table = Table {
+            name: new_name.to_string(),
+            ..table.clone()
+        };
+        ddl::duplicate_table(
+            &qualified_table_name(database, table, self.dialect()),
+            &qualified_table_name(database, &new_table, self.dialect()),
+            None,
+            with_data,
+            self.dialect(),
+        )
+    }
+
+    async fn execute_ddl(&self, statements: &[String]) -> anyhow::Result<()> {
+        // MySQL commits each statement changing the schema on its own.
+        for statement in statements {
+            sqlx::query(statement)
+                .execute(&self.pool)
+                .await
+                .map_err(query_error)?;
+        }
+        Ok(())
+    }
+
+    fn dialect(&self) -> Dialect {
+        Dialect::MySql
+    }
+
     async fn close(&self) {
         self.pool.close().await;
     }
 }
 
+/// Keeps the SQLSTATE of an error the server returned and where in the query
+/// it was found, so that the editor can point at it.
+fn query_error(err: sqlx::Error) -> anyhow::Error {
+    let db_err = match err
+        .as_database_error()
+        .and_then(|err| err.try_downcast_ref::<MySqlDatabaseError>())
+    {
+        Some(db_err) => db_err,
+        None => return err.into(),
+    };
+    QueryError {
+        message: format!("ERROR {}: {}", db_err.number(), db_err.message()),
+        code: db_err.code().map(str::to_string),
+        detail: None,
+        hint: None,
+        position: error_position(db_err.message()),
+    }
+    .into()
+}
+
+/// Parses "... near 'text' at line N" out of a syntax error message.
+fn error_position(message: &str) -> Option<ErrorPosition> {
+    let start = message.find(" near '")? + " near '".len();
+    let end = message.rfind("' at line ")?;
+    Some(ErrorPosition::Near {
+        text: message.get(start..end)?.to_string(),
+        line: message[end + "' at line ".len()..].trim().parse().ok()?,
+    })
+}
+
+async fn warnings(conn: &mut MySqlConnection) -> anyhow::Result<Vec<String>> {
+    let mut warnings = vec![];
+    for row in sqlx::query("SHOW WARNINGS").fetch_all(conn).await? {
+        let level: String = row.try_get("Level")?;
+        let code: u32 = row.try_get("Code")?;
+        let message: String = row.try_get("Message")?;
+        warnings.push(format!("{} {}: {}", level, code, message));
+    }
+    Ok(warnings)
+}
+
+fn bind_values<'q>(
+    mut query: Query<'q, MySql, MySqlArguments>,
+    params: &'q [BindValue],
+) -> Query<'q, MySql, MySqlArguments> {
+    for param in params {
+        query = match param {
+            BindValue::Null => query.bind(None::<String>),
+            BindValue::Text(value) => query.bind(value.as_str()),
+            BindValue::Integer(value) => query.bind(*value),
+            BindValue::Float(value) => query.bind(*value),
+            BindValue::Boolean(value) => query.bind(*value),
+        }
+    }
+    query
+}
+
+/// Reads a row an `INSERT` of a batch added.
+fn inserted_row(row: &MySqlRow) -> anyhow::Result<InsertedRow> {
+    let mut values = vec![];
+    for column in row.columns() {
+        values.push(convert_column_value_to_string(row, column)?)
+    }
+    Ok(InsertedRow {
+        headers: row
+            .columns()
+            .iter()
+            .map(|column| column.name().to_string())
+            .collect(),
+        row: values,
+    })
+}
+
 fn convert_column_value_to_string(row: &MySqlRow, column: &MySqlColumn) -> anyhow::Result<String> {
     let column_name = column.name();
 
@@ -456,6 +818,9 @@ fn convert_column_value_to_string(row: &MySqlRow, column: &MySqlColumn) -> anyho
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<bool> = value;
         Ok(get_or_null!(value))
+    } else if let Ok(value) = row.try_get(column_name) {
+        let value: Option<Vec<u8>> = value;
+        Ok(value.map_or("NULL".to_string(), |v| binary_to_string(&v)))
     } else {
         anyhow::bail!(
             "column type not implemented: `{}` {}",
@@ -464,3 +829,109 @@ fn convert_column_value_to_string(row: &MySqlRow, column: &MySqlColumn) -> anyho
         )
     }
 }
+
+/// Changes the type of `column`, restating the rest of its definition as
+/// `MODIFY COLUMN` drops whatever isn't stated.
+fn modify_column(table: &str, column: &Column, type_name: &str) -> anyhow::Result<String> {
+    let name = column.name.as_deref().unwrap_or_default();
+    let extra = column.extra.as_deref().unwrap_or_default().to_lowercase();
+    if extra.contains("virtual generated") || extra.contains("stored generated") {
+        anyhow::bail!("{} is a generated column", name);
+    }
+    let mut sql = format!(
+        "ALTER TABLE {} MODIFY COLUMN {} {}",
+        table,
+        quote::identifier(name, Dialect::MySql),
+        type_name
+    );
+    if column.null.as_deref() == Some("NO") {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = &column.default {
+        sql.push_str(" DEFAULT ");
+        if default.to_uppercase().starts_with("CURRENT_TIMESTAMP") {
+            sql.push_str(default);
+        } else if extra.contains("default_generated") {
+            sql.push_str(&format!("({})", default));
+        } else {
+            sql.push_str(&quote::string(default, Dialect::MySql));
+        }
+    }
+    if extra.contains("auto_increment") {
+        sql.push_str(" AUTO_INCREMENT");
+    }
+    if let Some(on_update) = extra.find("on update ") {
+        sql.push(' ');
+        sql.push_str(&extra[on_update..].to_uppercase());
+    }
+    if let Some(comment) = column
+        .comment
+        .as_deref()
+        .filter(|comment| !comment.is_empty())
+    {
+        sql.push_str(" COMMENT ");
+        sql.push_str(&quote::string(comment, Dialect::MySql));
+    }
+    Ok(sql)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{error_position, modify_column, Column, ErrorPosition};
+
+    #[test]
+    fn test_error_position() {
+        assert_eq!(
+            error_position(
+                "You have an error in your SQL syntax; check the manual that corresponds to \
+                 your MySQL server version for the right syntax to use near 'form users' at line 2"
+            ),
+            Some(ErrorPosition::Near {
+                text: "form users".to_string(),
+                line: 2
+            })
+        );
+        assert_eq!(error_position("Table 'db.t' doesn't exist"), None);
+    }
+
+    #[test]
+    fn test_modify_column() {
+        let column = |null: &str, default: Option<&str>, extra: &str, comment: &str| Column {
+            name: Some("updated_at".to_string()),
+            r#type: Some("datetime".to_string()),
+            null: Some(null.to_string()),
+            default: default.map(str::to_string),
+            extra: Some(extra.to_string()),
+            comment: Some(comment.to_string()),
+        };
+        assert_eq!(
+            modify_column(
+                "`db`.`users`",
+                &column(
+                    "NO",
+                    Some("CURRENT_TIMESTAMP"),
+                    "DEFAULT_GENERATED on update CURRENT_TIMESTAMP",
+                    "it's set"
+                ),
+                "timestamp"
+            )
+            .unwrap(),
+            "ALTER TABLE `db`.`users` MODIFY COLUMN `updated_at` timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP COMMENT 'it''s set'"
+        );
+        assert_eq!(
+            modify_column(
+                "`users`",
+                &column("YES", Some("n/a"), "", ""),
+                "varchar(20)"
+            )
+            .unwrap(),
+            "ALTER TABLE `users` MODIFY COLUMN `updated_at` varchar(20) DEFAULT 'n/a'"
+        );
+        assert!(modify_column(
+            "`users`",
+            &column("YES", None, "VIRTUAL GENERATED", ""),
+            "int"
+        )
+        .is_err());
+    }
+}
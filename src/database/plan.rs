@@ -0,0 +1,351 @@
+use serde_json::{Map, Value};
+
+/// Nodes whose own share of the plan is at least this are highlighted
+const EXPENSIVE_SHARE: f64 = 0.3;
+
+/// A step of a query plan as reported by `EXPLAIN`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanNode {
+    pub label: String,
+    /// Estimated cost in the database's own units
+    pub cost: Option<f64>,
+    /// Actual rows with `ANALYZE`, estimated rows otherwise
+    pub rows: Option<f64>,
+    /// Actual time in milliseconds, only known with `ANALYZE`
+    pub time: Option<f64>,
+    pub expensive: bool,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            cost: None,
+            rows: None,
+            time: None,
+            expensive: false,
+            children: vec![],
+        }
+    }
+
+    /// Statistics shown next to the label
+    pub fn stats(&self) -> String {
+        let mut stats = vec![];
+        if let Some(cost) = self.cost {
+            stats.push(format!("cost={:.2}", cost));
+        }
+        if let Some(rows) = self.rows {
+            stats.push(format!("rows={}", rows));
+        }
+        if let Some(time) = self.time {
+            stats.push(format!("time={:.3}ms", time));
+        }
+        stats.join(" ")
+    }
+
+    /// Highlights the nodes whose own `weight`, i.e. excluding their
+    /// children, makes up a large part of the total.
+    fn mark_expensive(&mut self, weight: &dyn Fn(&PlanNode) -> Option<f64>) {
+        fn own_weights(
+            node: &PlanNode,
+            weight: &dyn Fn(&PlanNode) -> Option<f64>,
+            out: &mut Vec<f64>,
+        ) {
+            out.push(own_weight(node, weight));
+            for child in &node.children {
+                own_weights(child, weight, out);
+            }
+        }
+        fn mark(node: &mut PlanNode, weight: &dyn Fn(&PlanNode) -> Option<f64>, threshold: f64) {
+            node.expensive = own_weight(node, weight) >= threshold;
+            for child in &mut node.children {
+                mark(child, weight, threshold);
+            }
+        }
+        fn own_weight(node: &PlanNode, weight: &dyn Fn(&PlanNode) -> Option<f64>) -> f64 {
+            let children = node.children.iter().filter_map(weight).sum::<f64>();
+            weight(node).map_or(0.0, |w| (w - children).max(0.0))
+        }
+
+        let mut weights = vec![];
+        own_weights(self, weight, &mut weights);
+        let total = weights.iter().sum::<f64>();
+        if total > 0.0 {
+            mark(self, weight, total * EXPENSIVE_SHARE);
+        }
+    }
+}
+
+/// Parses the output of Postgres' `EXPLAIN (FORMAT JSON)`.
+pub fn from_postgres_json(plan: &Value) -> anyhow::Result<PlanNode> {
+    let plan = plan
+        .get(0)
+        .and_then(|plan| plan.get("Plan"))
+        .ok_or_else(|| anyhow::anyhow!("unexpected EXPLAIN output"))?;
+    let mut root = postgres_node(plan);
+    let analyzed = root.time.is_some();
+    root.mark_expensive(&|node| if analyzed { node.time } else { node.cost });
+    Ok(root)
+}
+
+fn postgres_node(plan: &Value) -> PlanNode {
+    let str_field = |key| plan.get(key).and_then(Value::as_str);
+    let f64_field = |key| plan.get(key).and_then(Value::as_f64);
+
+    let mut label = str_field("Node Type").unwrap_or("?").to_string();
+    if let Some(relation) = str_field("Relation Name") {
+        label.push_str(&format!(" on {}", relation));
+        if let Some(alias) = str_field("Alias").filter(|alias| *alias != relation) {
+            label.push_str(&format!(" {}", alias));
+        }
+    }
+    if let Some(index) = str_field("Index Name") {
+        label.push_str(&format!(" using {}", index));
+    }
+
+    let loops = f64_field("Actual Loops").unwrap_or(1.0);
+    PlanNode {
+        label,
+        cost: f64_field("Total Cost"),
+        rows: f64_field("Actual Rows")
+            .map(|rows| rows * loops)
+            .or_else(|| f64_field("Plan Rows")),
+        time: f64_field("Actual Total Time").map(|time| time * loops),
+        expensive: false,
+        children: plan
+            .get("Plans")
+            .and_then(Value::as_array)
+            .map_or(vec![], |plans| plans.iter().map(postgres_node).collect()),
+    }
+}
+
+/// Parses the output of MySQL's `EXPLAIN FORMAT=JSON`.
+pub fn from_mysql_json(plan: &Value) -> anyhow::Result<PlanNode> {
+    let query_block = plan
+        .get("query_block")
+        .and_then(Value::as_object)
+        .ok_or_else(|| anyhow::anyhow!("unexpected EXPLAIN output"))?;
+    let mut root = mysql_query_block(query_block);
+    // Table costs are their own, so only they are weighed.
+    root.mark_expensive(&|node| {
+        if node.children.is_empty() {
+            node.cost
+        } else {
+            Some(node.children.iter().filter_map(|c| c.cost).sum())
+        }
+    });
+    Ok(root)
+}
+
+fn mysql_cost(object: &Map<String, Value>, key: &str) -> Option<f64> {
+    let value = object.get("cost_info")?.get(key)?;
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|cost| cost.parse().ok()))
+}
+
+fn mysql_query_block(block: &Map<String, Value>) -> PlanNode {
+    let mut node = PlanNode::new(match block.get("select_id") {
+        Some(id) => format!("query block #{}", id),
+        None => "query block".to_string(),
+    });
+    node.cost = mysql_cost(block, "query_cost");
+    node.children = mysql_children(block);
+    node
+}
+
+fn mysql_table(table: &Map<String, Value>) -> PlanNode {
+    let str_field = |key| table.get(key).and_then(Value::as_str);
+    let mut label = format!(
+        "{} on {}",
+        str_field("access_type").unwrap_or("?"),
+        str_field("table_name").unwrap_or("?")
+    );
+    if let Some(key) = str_field("key") {
+        label.push_str(&format!(" using {}", key));
+    }
+    let mut node = PlanNode::new(label);
+    node.cost = match (
+        mysql_cost(table, "read_cost"),
+        mysql_cost(table, "eval_cost"),
+    ) {
+        (None, None) => None,
+        (read, eval) => Some(read.unwrap_or(0.0) + eval.unwrap_or(0.0)),
+    };
+    node.rows = table.get("rows_examined_per_scan").and_then(Value::as_f64);
+    node.children = mysql_children(table);
+    node
+}
+
+/// Operations such as `ordering_operation` or `nested_loop` become nodes of
+/// their own, holding the tables and query blocks found inside them.
+fn mysql_children(object: &Map<String, Value>) -> Vec<PlanNode> {
+    let mut children = vec![];
+    for (key, value) in object {
+        let node = match (key.as_str(), value) {
+            ("cost_info", _) => continue,
+            ("table", Value::Object(table)) => mysql_table(table),
+            ("query_block", Value::Object(block)) => mysql_query_block(block),
+            (_, Value::Object(operation)) => {
+                let mut node = PlanNode::new(key.replace('_', " "));
+                node.cost = mysql_cost(operation, "sort_cost");
+                node.children = mysql_children(operation);
+                node
+            }
+            (_, Value::Array(items)) => {
+                let mut node = PlanNode::new(key.replace('_', " "));
+                node.children = items
+                    .iter()
+                    .filter_map(Value::as_object)
+                    .flat_map(mysql_children)
+                    .collect();
+                node
+            }
+            _ => continue,
+        };
+        if key == "table" || key == "query_block" || !node.children.is_empty() {
+            children.push(node);
+        }
+    }
+    children
+}
+
+/// Builds the tree of SQLite's `EXPLAIN QUERY PLAN`, given its `(id, parent,
+/// detail)` rows. SQLite reports no costs, so full table scans are the ones
+/// highlighted.
+pub fn from_sqlite_rows(rows: &[(i64, i64, String)]) -> PlanNode {
+    fn children(rows: &[(i64, i64, String)], parent: i64) -> Vec<PlanNode> {
+        rows.iter()
+            .filter(|(_, p, _)| *p == parent)
+            .map(|(id, _, detail)| {
+                let mut node = PlanNode::new(detail.as_str());
+                node.expensive = detail.starts_with("SCAN") && !detail.contains(" USING ");
+                node.children = children(rows, *id);
+                node
+            })
+            .collect()
+    }
+
+    let mut root = PlanNode::new("QUERY PLAN");
+    root.children = children(rows, 0);
+    root
+}
+
+#[cfg(test)]
+mod test {
+    use super::{from_mysql_json, from_postgres_json, from_sqlite_rows};
+    use serde_json::json;
+
+    #[test]
+    fn test_postgres_plan() {
+        let plan = json!([{
+            "Plan": {
+                "Node Type": "Hash Join",
+                "Total Cost": 100.0,
+                "Plan Rows": 10,
+                "Plans": [
+                    {
+                        "Node Type": "Seq Scan",
+                        "Relation Name": "users",
+                        "Alias": "u",
+                        "Total Cost": 80.0,
+                        "Plan Rows": 1000
+                    },
+                    {
+                        "Node Type": "Index Scan",
+                        "Relation Name": "posts",
+                        "Alias": "posts",
+                        "Index Name": "posts_pkey",
+                        "Total Cost": 5.0,
+                        "Plan Rows": 10
+                    }
+                ]
+            }
+        }]);
+        let root = from_postgres_json(&plan).unwrap();
+        assert_eq!(root.label, "Hash Join");
+        assert_eq!(root.stats(), "cost=100.00 rows=10");
+        assert_eq!(root.children[0].label, "Seq Scan on users u");
+        assert_eq!(
+            root.children[1].label,
+            "Index Scan on posts using posts_pkey"
+        );
+        assert!(!root.expensive);
+        assert!(root.children[0].expensive);
+        assert!(!root.children[1].expensive);
+    }
+
+    #[test]
+    fn test_postgres_analyzed_plan() {
+        let plan = json!([{
+            "Plan": {
+                "Node Type": "Seq Scan",
+                "Relation Name": "users",
+                "Total Cost": 10.0,
+                "Plan Rows": 100,
+                "Actual Total Time": 0.5,
+                "Actual Rows": 3,
+                "Actual Loops": 2
+            }
+        }]);
+        let root = from_postgres_json(&plan).unwrap();
+        assert_eq!(root.stats(), "cost=10.00 rows=6 time=1.000ms");
+        assert!(root.expensive);
+    }
+
+    #[test]
+    fn test_mysql_plan() {
+        let plan = json!({
+            "query_block": {
+                "select_id": 1,
+                "cost_info": { "query_cost": "12.50" },
+                "nested_loop": [
+                    {
+                        "table": {
+                            "table_name": "users",
+                            "access_type": "ALL",
+                            "rows_examined_per_scan": 100,
+                            "cost_info": { "read_cost": "9.00", "eval_cost": "1.00" }
+                        }
+                    },
+                    {
+                        "table": {
+                            "table_name": "posts",
+                            "access_type": "ref",
+                            "key": "user_id",
+                            "rows_examined_per_scan": 1,
+                            "cost_info": { "read_cost": "2.00", "eval_cost": "0.50" }
+                        }
+                    }
+                ]
+            }
+        });
+        let root = from_mysql_json(&plan).unwrap();
+        assert_eq!(root.label, "query block #1");
+        assert_eq!(root.cost, Some(12.5));
+        let nested_loop = &root.children[0];
+        assert_eq!(nested_loop.label, "nested loop");
+        assert_eq!(nested_loop.children[0].label, "ALL on users");
+        assert_eq!(nested_loop.children[0].stats(), "cost=10.00 rows=100");
+        assert_eq!(nested_loop.children[1].label, "ref on posts using user_id");
+        assert!(nested_loop.children[0].expensive);
+        assert!(!nested_loop.children[1].expensive);
+    }
+
+    #[test]
+    fn test_sqlite_plan() {
+        let root = from_sqlite_rows(&[
+            (2, 0, "SCAN users".to_string()),
+            (3, 0, "SEARCH posts USING INDEX idx (user_id=?)".to_string()),
+            (5, 3, "CORRELATED SCALAR SUBQUERY 1".to_string()),
+        ]);
+        assert_eq!(root.children.len(), 2);
+        assert!(root.children[0].expensive);
+        assert!(!root.children[1].expensive);
+        assert_eq!(
+            root.children[1].children[0].label,
+            "CORRELATED SCALAR SUBQUERY 1"
+        );
+    }
+}
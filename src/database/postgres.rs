@@ -1,21 +1,135 @@
 use crate::get_or_null;
+use crate::sql::ddl::{self, ColumnChange};
+use crate::sql::Dialect;
 
-use super::{ExecuteResult, Pool, TableRow, RECORDS_LIMIT_PER_PAGE};
+use super::{
+    add_reference_column, binary_to_string, check_batch_statement, column_stats_queries,
+    count_query, plan, qualified_table_name, BatchStatement, BindValue, ColumnStats, ErrorPosition,
+    ExecuteResult, InsertedRow, PlanNode, Pool, QueryError, ReadBack, Reference, TableRow,
+    RECORDS_LIMIT_PER_PAGE,
+};
 use async_trait::async_trait;
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use database_tree::{Child, Database, Schema, Table};
 use futures::TryStreamExt;
 use itertools::Itertools;
-use sqlx::postgres::{PgColumn, PgPool, PgPoolOptions, PgRow};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use sqlx::postgres::{
+    PgArguments, PgColumn, PgDatabaseError, PgErrorPosition, PgPool, PgPoolOptions, PgRow, Postgres,
+};
+use sqlx::query::Query;
 use sqlx::{Column as _, Row as _, TypeInfo as _};
+use std::sync::Mutex;
 use std::time::Duration;
 
 pub struct PostgresPool {
     pool: PgPool,
 }
 
+/// The constraints other than foreign keys of a table, given its name and
+/// schema. Constraint names are only unique within a schema, and the rows
+/// are grouped by them into keys.
+const CONSTRAINTS_QUERY: &str = "
+        SELECT
+            tc.table_schema,
+            tc.constraint_name,
+            tc.table_name,
+            kcu.column_name,
+            ccu.table_schema AS foreign_table_schema,
+            ccu.table_name AS foreign_table_name,
+            ccu.column_name AS foreign_column_name
+        FROM
+            information_schema.table_constraints AS tc
+            JOIN information_schema.key_column_usage AS kcu ON tc.constraint_name = kcu.constraint_name
+            AND tc.table_schema = kcu.table_schema
+            JOIN information_schema.constraint_column_usage AS ccu ON ccu.constraint_name = tc.constraint_name
+            AND ccu.table_schema = tc.table_schema
+        WHERE
+            NOT tc.constraint_type = 'FOREIGN KEY'
+            AND tc.table_name = $1
+            AND tc.table_schema = $2
+        ";
+
+/// The foreign keys matching `condition`, one row per column in key order,
+/// with the referencing table, aliased `cl` in schema `ns`, and the
+/// referenced one, `ref` in `ref_ns`
+fn foreign_keys_query(condition: &str) -> String {
+    format!(
+        "
+        SELECT
+            con.oid::text AS key,
+            ns.nspname AS table_schema,
+            cl.relname AS table_name,
+            ref_ns.nspname AS ref_schema,
+            ref.relname AS ref_table,
+            a.attname AS column_name,
+            ref_a.attname AS ref_column
+        FROM
+            pg_constraint AS con
+            JOIN pg_class AS cl ON cl.oid = con.conrelid
+            JOIN pg_namespace AS ns ON ns.oid = cl.relnamespace
+            JOIN pg_class AS ref ON ref.oid = con.confrelid
+            JOIN pg_namespace AS ref_ns ON ref_ns.oid = ref.relnamespace
+            CROSS JOIN LATERAL unnest(con.conkey, con.confkey) WITH ORDINALITY AS k(attnum, ref_attnum, position)
+            JOIN pg_attribute AS a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+            JOIN pg_attribute AS ref_a ON ref_a.attrelid = con.confrelid AND ref_a.attnum = k.ref_attnum
+        WHERE
+            con.contype = 'f'
+            AND {}
+        ORDER BY
+            con.oid,
+            k.position
+        ",
+        condition
+    )
+}
+
+/// Messages like `RAISE NOTICE` sent by the server. sqlx only hands them to
+/// the `log` crate, so they are collected by a logger and picked up after each
+/// query. The logger can't tell which connection a message came from, so the
+/// buffer is shared by all pools.
+static NOTICES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Held while a query runs through `Pool::execute`, so that the notices
+/// picked up after it are its own. Other queries, such as those loading
+/// records, aren't held back and could still slip a notice in.
+static EXECUTE: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+struct NoticeLogger;
+
+impl Log for NoticeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }
+
+    fn log(&self, record: &Record) {
+        if record.module_path() == Some("sqlx::postgres::notice") {
+            let severity = match record.level() {
+                Level::Error => "ERROR",
+                Level::Warn => "WARNING",
+                _ => "NOTICE",
+            };
+            if let Ok(mut notices) = NOTICES.lock() {
+                notices.push(format!("{}: {}", severity, record.args()));
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn take_notices() -> Vec<String> {
+    NOTICES
+        .lock()
+        .map_or(vec![], |mut notices| std::mem::take(&mut *notices))
+}
+
 impl PostgresPool {
     pub async fn new(database_url: &str) -> anyhow::Result<Self> {
+        // Fails if the logger is already installed by an earlier connection
+        if log::set_logger(&NoticeLogger).is_ok() {
+            log::set_max_level(LevelFilter::Info);
+        }
         Ok(Self {
             pool: PgPoolOptions::new()
                 .connect_timeout(Duration::from_secs(5))
@@ -147,18 +261,26 @@ impl TableRow for Index {
 
 #[async_trait]
 impl Pool for PostgresPool {
-    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+    async fn execute(&self, query: &str, params: &[BindValue]) -> anyhow::Result<ExecuteResult> {
         let query = query.trim();
+        let _execute = EXECUTE.lock().await;
+        take_notices();
         if query.to_uppercase().starts_with("SELECT") {
-            let mut rows = sqlx::query(query).fetch(&self.pool);
+            let mut rows = bind_values(sqlx::query(query), params).fetch(&self.pool);
             let mut headers = vec![];
+            let mut types = vec![];
             let mut records = vec![];
-            while let Some(row) = rows.try_next().await? {
+            while let Some(row) = rows.try_next().await.map_err(query_error)? {
                 headers = row
                     .columns()
                     .iter()
                     .map(|column| column.name().to_string())
                     .collect();
+                types = row
+                    .columns()
+                    .iter()
+                    .map(|column| column.type_info().name().to_string())
+                    .collect();
                 let mut new_row = vec![];
                 for column in row.columns() {
                     new_row.push(convert_column_value_to_string(&row, column)?)
@@ -167,7 +289,9 @@ impl Pool for PostgresPool {
             }
             return Ok(ExecuteResult::Read {
                 headers,
+                types,
                 rows: records,
+                messages: take_notices(),
                 database: Database {
                     name: "-".to_string(),
                     children: Vec::new(),
@@ -182,12 +306,33 @@ impl Pool for PostgresPool {
             });
         }
 
-        let result = sqlx::query(query).execute(&self.pool).await?;
+        let result = bind_values(sqlx::query(query), params)
+            .execute(&self.pool)
+            .await
+            .map_err(query_error)?;
         Ok(ExecuteResult::Write {
             updated_rows: result.rows_affected(),
+            messages: take_notices(),
         })
     }
 
+    async fn explain(
+        &self,
+        query: &str,
+        params: &[BindValue],
+        analyze: bool,
+    ) -> anyhow::Result<PlanNode> {
+        let query = format!(
+            "EXPLAIN (FORMAT JSON{}) {}",
+            if analyze { ", ANALYZE" } else { "" },
+            query.trim()
+        );
+        let row = bind_values(sqlx::query(&query), params)
+            .fetch_one(&self.pool)
+            .await?;
+        plan::from_postgres_json(&row.try_get::<serde_json::Value, _>(0)?)
+    }
+
     async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
         let databases = sqlx::query("SELECT datname FROM pg_database")
             .fetch_all(&self.pool)
@@ -336,7 +481,13 @@ impl Pool for PostgresPool {
         while let Some(row) = rows.try_next().await? {
             columns.push(Box::new(Column {
                 name: row.try_get("column_name")?,
-                r#type: row.try_get("data_type")?,
+                r#type: match row.try_get::<Option<String>, _>("data_type")? {
+                    // Enums and arrays are named by their own type.
+                    Some(data_type) if data_type == "USER-DEFINED" || data_type == "ARRAY" => {
+                        row.try_get("udt_name")?
+                    }
+                    data_type => data_type,
+                },
                 null: row.try_get("is_nullable")?,
                 default: row.try_get("column_default")?,
                 comment: None,
@@ -350,29 +501,10 @@ impl Pool for PostgresPool {
         _database: &Database,
         table: &Table,
     ) -> anyhow::Result<Vec<Box<dyn TableRow>>> {
-        let mut rows = sqlx::query(
-            "
-        SELECT
-            tc.table_schema,
-            tc.constraint_name,
-            tc.table_name,
-            kcu.column_name,
-            ccu.table_schema AS foreign_table_schema,
-            ccu.table_name AS foreign_table_name,
-            ccu.column_name AS foreign_column_name
-        FROM
-            information_schema.table_constraints AS tc
-            JOIN information_schema.key_column_usage AS kcu ON tc.constraint_name = kcu.constraint_name
-            AND tc.table_schema = kcu.table_schema
-            JOIN information_schema.constraint_column_usage AS ccu ON ccu.constraint_name = tc.constraint_name
-            AND ccu.table_schema = tc.table_schema
-        WHERE
-            NOT tc.constraint_type = 'FOREIGN KEY'
-            AND tc.table_name = $1
-        ",
-        )
-        .bind(&table.name)
-        .fetch(&self.pool);
+        let mut rows = sqlx::query(CONSTRAINTS_QUERY)
+            .bind(&table.name)
+            .bind(table.schema.as_deref().unwrap_or("public"))
+            .fetch(&self.pool);
         let mut constraints: Vec<Box<dyn TableRow>> = vec![];
         while let Some(row) = rows.try_next().await? {
             constraints.push(Box::new(Constraint {
@@ -423,6 +555,58 @@ impl Pool for PostgresPool {
         Ok(constraints)
     }
 
+    async fn get_foreign_key_references(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Reference>> {
+        let query = foreign_keys_query("ns.nspname = $1 AND cl.relname = $2");
+        let mut rows = sqlx::query(&query)
+            .bind(table.schema.as_deref().unwrap_or("public"))
+            .bind(&table.name)
+            .fetch(&self.pool);
+        let mut references = vec![];
+        while let Some(row) = rows.try_next().await? {
+            add_reference_column(
+                &mut references,
+                row.try_get("key")?,
+                row.try_get("ref_schema")?,
+                row.try_get("ref_table")?,
+                (row.try_get("column_name")?, row.try_get("ref_column")?),
+            );
+        }
+        Ok(references
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect())
+    }
+
+    async fn get_references(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Reference>> {
+        let query = foreign_keys_query("ref_ns.nspname = $1 AND ref.relname = $2");
+        let mut rows = sqlx::query(&query)
+            .bind(table.schema.as_deref().unwrap_or("public"))
+            .bind(&table.name)
+            .fetch(&self.pool);
+        let mut references = vec![];
+        while let Some(row) = rows.try_next().await? {
+            add_reference_column(
+                &mut references,
+                row.try_get("key")?,
+                row.try_get("table_schema")?,
+                row.try_get("table_name")?,
+                (row.try_get("column_name")?, row.try_get("ref_column")?),
+            );
+        }
+        Ok(references
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect())
+    }
+
     async fn get_indexes(
         &self,
         _database: &Database,
@@ -467,6 +651,147 @@ impl Pool for PostgresPool {
         Ok(foreign_keys)
     }
 
+    async fn get_column_stats(
+        &self,
+        database: &Database,
+        table: &Table,
+        column: &str,
+        numeric: bool,
+        filter: Option<String>,
+    ) -> anyhow::Result<ColumnStats> {
+        let (stats_query, top_values_query) = column_stats_queries(
+            &qualified_table_name(database, table, self.dialect()),
+            column,
+            numeric,
+            filter.as_deref(),
+            self.dialect(),
+        );
+        let row = sqlx::query(&stats_query).fetch_one(&self.pool).await?;
+        let mut top_values = vec![];
+        for row in sqlx::query(&top_values_query).fetch_all(&self.pool).await? {
+            top_values.push((row.try_get_unchecked(0)?, row.try_get_unchecked(1)?));
+        }
+        Ok(ColumnStats {
+            rows: row.try_get_unchecked(0)?,
+            distinct: row.try_get_unchecked(1)?,
+            nulls: row.try_get_unchecked(2)?,
+            min: row.try_get_unchecked(3)?,
+            max: row.try_get_unchecked(4)?,
+            average: row.try_get_unchecked(5)?,
+            top_values,
+        })
+    }
+
+    async fn count_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<i64> {
+        let query = count_query(
+            &qualified_table_name(database, table, self.dialect()),
+            filter.as_deref(),
+        );
+        Ok(sqlx::query(&query)
+            .fetch_one(&self.pool)
+            .await?
+            .try_get_unchecked(0)?)
+    }
+
+    async fn execute_batch(
+        &self,
+        statements: &[BatchStatement],
+    ) -> anyhow::Result<Vec<Option<InsertedRow>>> {
+        let mut transaction = self.pool.begin().await?;
+        let mut inserted = vec![];
+        for statement in statements {
+            let query = bind_values(sqlx::query(&statement.query), &statement.params);
+            // Dropping the transaction rolls it back.
+            let row = match &statement.read_back {
+                Some(ReadBack::Returned) => {
+                    let row = query
+                        .fetch_optional(&mut transaction)
+                        .await
+                        .map_err(query_error)?;
+                    check_batch_statement(statement, row.is_some() as u64)?;
+                    row
+                }
+                Some(ReadBack::Selected(select)) => {
+                    let result = query.execute(&mut transaction).await.map_err(query_error)?;
+                    check_batch_statement(statement, result.rows_affected())?;
+                    sqlx::query(select).fetch_optional(&mut transaction).await?
+                }
+                None => {
+                    let result = query.execute(&mut transaction).await.map_err(query_error)?;
+                    check_batch_statement(statement, result.rows_affected())?;
+                    None
+                }
+            };
+            inserted.push(row.as_ref().map(inserted_row).transpose()?);
+        }
+        transaction.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn alter_column_statements(
+        &self,
+        database: &Database,
+        table: &Table,
+        change: &ColumnChange,
+    ) -> anyhow::Result<Vec<String>> {
+        Ok(ddl::alter_column(
+            &qualified_table_name(database, table, self.dialect()),
+            change,
+            self.dialect(),
+        )
+        .into_iter()
+        .collect())
+    }
+
+    async fn duplicate_table_statements(
+        &self,
+        database: &Database,
+        table: &Table,
+        new_name: &str,
+        with_data: bool,
+    ) -> anyhow::Result<Vec<String>> {
+        let new_table = Table {
+            name: new_name.to_string(),
+            ..table.clone()
+        };
+        ddl::duplicate_table(
+            &qualified_table_name(database, table, self.dialect()),
+            &qualified_table_name(database, &new_table, self.dialect()),
+            None,
+            with_data,
+            self.dialect(),
+        )
+    }
+
+    async fn execute_ddl(&self, statements: &[String]) -> anyhow::Result<()> {
+        // `CREATE INDEX CONCURRENTLY` can't run in a transaction.
+        if let [statement] = statements {
+            sqlx::query(statement)
+                .execute(&self.pool)
+                .await
+                .map_err(query_error)?;
+            return Ok(());
+        }
+        let mut transaction = self.pool.begin().await?;
+        for statement in statements {
+            sqlx::query(statement)
+                .execute(&mut transaction)
+                .await
+                .map_err(query_error)?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    fn dialect(&self) -> Dialect {
+        Dialect::Postgres
+    }
+
     async fn close(&self) {
         self.pool.close().await;
     }
@@ -506,6 +831,61 @@ impl PostgresPool {
     }
 }
 
+/// Keeps the SQLSTATE, detail, hint and position of an error the server
+/// returned, so that the editor can point at it.
+fn query_error(err: sqlx::Error) -> anyhow::Error {
+    let db_err = match err
+        .as_database_error()
+        .and_then(|err| err.try_downcast_ref::<PgDatabaseError>())
+    {
+        Some(db_err) => db_err,
+        None => return err.into(),
+    };
+    QueryError {
+        message: db_err.message().to_string(),
+        code: Some(db_err.code().to_string()),
+        detail: db_err.detail().map(str::to_string),
+        hint: db_err.hint().map(str::to_string),
+        position: match db_err.position() {
+            Some(PgErrorPosition::Original(position)) => Some(ErrorPosition::Character(position)),
+            _ => None,
+        },
+    }
+    .into()
+}
+
+fn bind_values<'q>(
+    mut query: Query<'q, Postgres, PgArguments>,
+    params: &'q [BindValue],
+) -> Query<'q, Postgres, PgArguments> {
+    for param in params {
+        query = match param {
+            BindValue::Null => query.bind(None::<String>),
+            BindValue::Text(value) => query.bind(value.as_str()),
+            BindValue::Integer(value) => query.bind(*value),
+            BindValue::Float(value) => query.bind(*value),
+            BindValue::Boolean(value) => query.bind(*value),
+        }
+    }
+    query
+}
+
+/// Reads a row an `INSERT` of a batch added.
+fn inserted_row(row: &PgRow) -> anyhow::Result<InsertedRow> {
+    let mut values = vec![];
+    for column in row.columns() {
+        values.push(convert_column_value_to_string(row, column)?)
+    }
+    Ok(InsertedRow {
+        headers: row
+            .columns()
+            .iter()
+            .map(|column| column.name().to_string())
+            .collect(),
+        row: values,
+    })
+}
+
 fn convert_column_value_to_string(row: &PgRow, column: &PgColumn) -> anyhow::Result<String> {
     let column_name = column.name();
     if let Ok(value) = row.try_get(column_name) {
@@ -561,6 +941,9 @@ fn convert_column_value_to_string(row: &PgRow, column: &PgColumn) -> anyhow::Res
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<Vec<String>> = value;
         Ok(value.map_or("NULL".to_string(), |v| v.join(",")))
+    } else if let Ok(value) = row.try_get(column_name) {
+        let value: Option<Vec<u8>> = value;
+        Ok(value.map_or("NULL".to_string(), |v| binary_to_string(&v)))
     } else {
         anyhow::bail!(
             "column type not implemented: `{}` {}",
@@ -569,3 +952,15 @@ fn convert_column_value_to_string(row: &PgRow, column: &PgColumn) -> anyhow::Res
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::CONSTRAINTS_QUERY;
+
+    #[test]
+    fn test_constraints_query() {
+        // Tables of the same name in other schemas have constraints of the
+        // same names, which would be merged into one key.
+        assert!(CONSTRAINTS_QUERY.contains("AND tc.table_schema = $2"));
+    }
+}
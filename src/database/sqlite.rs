@@ -1,12 +1,19 @@
 use crate::get_or_null;
+use crate::sql::ddl::{self, ColumnChange, SchemaObject};
+use crate::sql::Dialect;
 
-use super::{ExecuteResult, Pool, TableRow, RECORDS_LIMIT_PER_PAGE};
+use super::{
+    add_reference_column, binary_to_string, check_batch_statement, column_stats_queries,
+    count_query, plan, qualified_table_name, BatchStatement, BindValue, ColumnStats, ExecuteResult,
+    InsertedRow, PlanNode, Pool, ReadBack, Reference, TableRow, RECORDS_LIMIT_PER_PAGE,
+};
 use async_trait::async_trait;
 use chrono::NaiveDateTime;
 use database_tree::{Child, Database, Table};
 use futures::TryStreamExt;
-use sqlx::sqlite::{SqliteColumn, SqlitePoolOptions, SqliteRow};
-use sqlx::{Column as _, Row as _, TypeInfo as _};
+use sqlx::query::Query;
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteColumn, SqlitePoolOptions, SqliteRow};
+use sqlx::{Column as _, Connection as _, Row as _, TypeInfo as _};
 use std::time::Duration;
 
 pub struct SqlitePool {
@@ -150,11 +157,12 @@ impl TableRow for Index {
 
 #[async_trait]
 impl Pool for SqlitePool {
-    async fn execute(&self, query: &String) -> anyhow::Result<ExecuteResult> {
+    async fn execute(&self, query: &str, params: &[BindValue]) -> anyhow::Result<ExecuteResult> {
         let query = query.trim();
         if query.to_uppercase().starts_with("SELECT") {
-            let mut rows = sqlx::query(query).fetch(&self.pool);
+            let mut rows = bind_values(sqlx::query(query), params).fetch(&self.pool);
             let mut headers = vec![];
+            let mut types = vec![];
             let mut records = vec![];
             while let Some(row) = rows.try_next().await? {
                 headers = row
@@ -162,6 +170,11 @@ impl Pool for SqlitePool {
                     .iter()
                     .map(|column| column.name().to_string())
                     .collect();
+                types = row
+                    .columns()
+                    .iter()
+                    .map(|column| column.type_info().name().to_string())
+                    .collect();
                 let mut new_row = vec![];
                 for column in row.columns() {
                     new_row.push(convert_column_value_to_string(&row, column)?)
@@ -170,7 +183,9 @@ impl Pool for SqlitePool {
             }
             return Ok(ExecuteResult::Read {
                 headers,
+                types,
                 rows: records,
+                messages: vec![],
                 database: Database {
                     name: "-".to_string(),
                     children: Vec::new(),
@@ -185,12 +200,37 @@ impl Pool for SqlitePool {
             });
         }
 
-        let result = sqlx::query(query).execute(&self.pool).await?;
+        let result = bind_values(sqlx::query(query), params)
+            .execute(&self.pool)
+            .await?;
         Ok(ExecuteResult::Write {
             updated_rows: result.rows_affected(),
+            messages: vec![],
         })
     }
 
+    /// SQLite can't report how a query actually ran, so `analyze` is ignored.
+    async fn explain(
+        &self,
+        query: &str,
+        params: &[BindValue],
+        _analyze: bool,
+    ) -> anyhow::Result<PlanNode> {
+        let query = format!("EXPLAIN QUERY PLAN {}", query.trim());
+        let mut rows = vec![];
+        for row in bind_values(sqlx::query(&query), params)
+            .fetch_all(&self.pool)
+            .await?
+        {
+            rows.push((
+                row.try_get("id")?,
+                row.try_get("parent")?,
+                row.try_get("detail")?,
+            ));
+        }
+        Ok(plan::from_sqlite_rows(&rows))
+    }
+
     async fn get_databases(&self) -> anyhow::Result<Vec<Database>> {
         let databases = sqlx::query("SELECT name FROM pragma_database_list")
             .fetch_all(&self.pool)
@@ -278,7 +318,7 @@ impl Pool for SqlitePool {
             columns.push(Box::new(Column {
                 name: row.try_get("name")?,
                 r#type: row.try_get("type")?,
-                null: if matches!(null, Some(null) if null == 1) {
+                null: if matches!(null, Some(null) if null == 0) {
                     Some("✔︎".to_string())
                 } else {
                     Some("".to_string())
@@ -321,6 +361,24 @@ impl Pool for SqlitePool {
                 origin: row.try_get("origin")?,
             }))
         }
+        // An INTEGER PRIMARY KEY is the rowid itself, which needs no index.
+        if !constraints
+            .iter()
+            .any(|constraint| constraint.field("origin").as_deref() == Some("pk"))
+        {
+            let rows =
+                sqlx::query("SELECT name FROM pragma_table_info(?) WHERE pk > 0 ORDER BY pk")
+                    .bind(&table.name)
+                    .fetch_all(&self.pool)
+                    .await?;
+            for row in rows {
+                constraints.push(Box::new(Constraint {
+                    name: "PRIMARY".to_string(),
+                    column_name: row.try_get("name")?,
+                    origin: "pk".to_string(),
+                }))
+            }
+        }
         Ok(constraints)
     }
 
@@ -347,6 +405,77 @@ impl Pool for SqlitePool {
         Ok(foreign_keys)
     }
 
+    async fn get_foreign_key_references(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Reference>> {
+        let mut rows = sqlx::query(
+            "SELECT p.id, p.`table`, p.`from`, p.`to` FROM pragma_foreign_key_list(?) p ORDER BY p.id, p.seq",
+        )
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut references = vec![];
+        while let Some(row) = rows.try_next().await? {
+            let id: i64 = row.try_get("id")?;
+            add_reference_column(
+                &mut references,
+                id.to_string(),
+                None,
+                row.try_get("table")?,
+                (row.try_get("from")?, row.try_get("to")?),
+            );
+        }
+        Ok(references
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect())
+    }
+
+    async fn get_references(
+        &self,
+        _database: &Database,
+        table: &Table,
+    ) -> anyhow::Result<Vec<Reference>> {
+        let mut rows = sqlx::query(
+            "
+            SELECT
+                m.name AS table_name,
+                p.id,
+                p.`from`,
+                p.`to`
+            FROM
+                sqlite_master m
+                JOIN pragma_foreign_key_list(m.name) p
+            WHERE
+                m.type = 'table'
+                AND p.`table` = ?
+            ORDER BY
+                m.name,
+                p.id,
+                p.seq
+            ",
+        )
+        .bind(&table.name)
+        .fetch(&self.pool);
+        let mut references = vec![];
+        while let Some(row) = rows.try_next().await? {
+            let table_name: String = row.try_get("table_name")?;
+            let id: i64 = row.try_get("id")?;
+            add_reference_column(
+                &mut references,
+                format!("{}.{}", table_name, id),
+                None,
+                table_name,
+                (row.try_get("from")?, row.try_get("to")?),
+            );
+        }
+        Ok(references
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect())
+    }
+
     async fn get_indexes(
         &self,
         _database: &Database,
@@ -378,11 +507,225 @@ impl Pool for SqlitePool {
         Ok(foreign_keys)
     }
 
+    async fn get_column_stats(
+        &self,
+        database: &Database,
+        table: &Table,
+        column: &str,
+        numeric: bool,
+        filter: Option<String>,
+    ) -> anyhow::Result<ColumnStats> {
+        let (stats_query, top_values_query) = column_stats_queries(
+            &qualified_table_name(database, table, self.dialect()),
+            column,
+            numeric,
+            filter.as_deref(),
+            self.dialect(),
+        );
+        let row = sqlx::query(&stats_query).fetch_one(&self.pool).await?;
+        let mut top_values = vec![];
+        for row in sqlx::query(&top_values_query).fetch_all(&self.pool).await? {
+            top_values.push((row.try_get_unchecked(0)?, row.try_get_unchecked(1)?));
+        }
+        Ok(ColumnStats {
+            rows: row.try_get_unchecked(0)?,
+            distinct: row.try_get_unchecked(1)?,
+            nulls: row.try_get_unchecked(2)?,
+            min: row.try_get_unchecked(3)?,
+            max: row.try_get_unchecked(4)?,
+            average: row.try_get_unchecked(5)?,
+            top_values,
+        })
+    }
+
+    async fn count_records(
+        &self,
+        database: &Database,
+        table: &Table,
+        filter: Option<String>,
+    ) -> anyhow::Result<i64> {
+        let query = count_query(
+            &qualified_table_name(database, table, self.dialect()),
+            filter.as_deref(),
+        );
+        Ok(sqlx::query(&query)
+            .fetch_one(&self.pool)
+            .await?
+            .try_get_unchecked(0)?)
+    }
+
+    async fn execute_batch(
+        &self,
+        statements: &[BatchStatement],
+    ) -> anyhow::Result<Vec<Option<InsertedRow>>> {
+        let mut transaction = self.pool.begin().await?;
+        let mut inserted = vec![];
+        for statement in statements {
+            let query = bind_values(sqlx::query(&statement.query), &statement.params);
+            // Dropping the transaction rolls it back.
+            let row = match &statement.read_back {
+                Some(ReadBack::Returned) => {
+                    let row = query.fetch_optional(&mut transaction).await?;
+                    check_batch_statement(statement, row.is_some() as u64)?;
+                    row
+                }
+                Some(ReadBack::Selected(select)) => {
+                    let result = query.execute(&mut transaction).await?;
+                    check_batch_statement(statement, result.rows_affected())?;
+                    sqlx::query(select).fetch_optional(&mut transaction).await?
+                }
+                None => {
+                    let result = query.execute(&mut transaction).await?;
+                    check_batch_statement(statement, result.rows_affected())?;
+                    None
+                }
+            };
+            inserted.push(row.as_ref().map(inserted_row).transpose()?);
+        }
+        transaction.commit().await?;
+        Ok(inserted)
+    }
+
+    async fn alter_column_statements(
+        &self,
+        database: &Database,
+        table: &Table,
+        change: &ColumnChange,
+    ) -> anyhow::Result<Vec<String>> {
+        if let Some(statement) = ddl::alter_column(
+            &qualified_table_name(database, table, self.dialect()),
+            change,
+            self.dialect(),
+        ) {
+            return Ok(vec![statement]);
+        }
+        let create_sql: String =
+            sqlx::query("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+                .bind(&table.name)
+                .fetch_one(&self.pool)
+                .await?
+                .try_get("sql")?;
+        let mut objects = vec![];
+        for row in sqlx::query(
+            "SELECT type, name, tbl_name, sql FROM sqlite_master WHERE type IN ('index', 'trigger', 'view') AND sql IS NOT NULL ORDER BY rowid",
+        )
+        .fetch_all(&self.pool)
+        .await?
+        {
+            objects.push(SchemaObject {
+                kind: row.try_get("type")?,
+                name: row.try_get("name")?,
+                table: row.try_get("tbl_name")?,
+                sql: row.try_get("sql")?,
+            });
+        }
+        ddl::rebuild_table(&table.name, &create_sql, &objects, change)
+    }
+
+    async fn duplicate_table_statements(
+        &self,
+        database: &Database,
+        table: &Table,
+        new_name: &str,
+        with_data: bool,
+    ) -> anyhow::Result<Vec<String>> {
+        let create_sql: String =
+            sqlx::query("SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?")
+                .bind(&table.name)
+                .fetch_one(&self.pool)
+                .await?
+                .try_get("sql")?;
+        let new_table = Table {
+            name: new_name.to_string(),
+            ..table.clone()
+        };
+        ddl::duplicate_table(
+            &qualified_table_name(database, table, self.dialect()),
+            &qualified_table_name(database, &new_table, self.dialect()),
+            Some(&create_sql),
+            with_data,
+            self.dialect(),
+        )
+    }
+
+    /// Foreign keys are turned off meanwhile, as rebuilding a table drops
+    /// it, and checked before committing instead.
+    async fn execute_ddl(&self, statements: &[String]) -> anyhow::Result<()> {
+        let mut connection = self.pool.acquire().await?;
+        let foreign_keys: bool = sqlx::query("PRAGMA foreign_keys")
+            .fetch_one(&mut connection)
+            .await?
+            .try_get(0)?;
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&mut connection)
+            .await?;
+        let result = async {
+            let mut transaction = connection.begin().await?;
+            for statement in statements {
+                sqlx::query(statement).execute(&mut transaction).await?;
+            }
+            let violations = sqlx::query("PRAGMA foreign_key_check")
+                .fetch_all(&mut transaction)
+                .await?;
+            if !violations.is_empty() {
+                anyhow::bail!(
+                    "the change breaks {} foreign key references, so nothing was committed",
+                    violations.len()
+                );
+            }
+            transaction.commit().await?;
+            Ok(())
+        }
+        .await;
+        if foreign_keys {
+            sqlx::query("PRAGMA foreign_keys = ON")
+                .execute(&mut connection)
+                .await?;
+        }
+        result
+    }
+
+    fn dialect(&self) -> Dialect {
+        Dialect::Sqlite
+    }
+
     async fn close(&self) {
         self.pool.close().await;
     }
 }
 
+fn bind_values<'q>(
+    mut query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    params: &'q [BindValue],
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    for param in params {
+        query = match param {
+            BindValue::Null => query.bind(None::<String>),
+            BindValue::Text(value) => query.bind(value.as_str()),
+            BindValue::Integer(value) => query.bind(*value),
+            BindValue::Float(value) => query.bind(*value),
+            BindValue::Boolean(value) => query.bind(*value),
+        }
+    }
+    query
+}
+
+/// Reads a row an `INSERT` of a batch added.
+fn inserted_row(row: &SqliteRow) -> anyhow::Result<InsertedRow> {
+    let mut values = vec![];
+    for column in row.columns() {
+        values.push(convert_column_value_to_string(row, column)?)
+    }
+    Ok(InsertedRow {
+        headers: row
+            .columns()
+            .iter()
+            .map(|column| column.name().to_string())
+            .collect(),
+        row: values,
+    })
+}
+
 fn convert_column_value_to_string(
     row: &SqliteRow,
     column: &SqliteColumn,
@@ -421,6 +764,9 @@ fn convert_column_value_to_string(
     } else if let Ok(value) = row.try_get(column_name) {
         let value: Option<bool> = value;
         Ok(get_or_null!(value))
+    } else if let Ok(value) = row.try_get(column_name) {
+        let value: Option<Vec<u8>> = value;
+        Ok(value.map_or("NULL".to_string(), |v| binary_to_string(&v)))
     } else {
         anyhow::bail!(
             "column type not implemented: `{}` {}",
@@ -26,6 +26,10 @@ pub enum Key {
     Up,
     /// Down arrow
     Down,
+    /// Left arrow with Shift
+    ShiftLeft,
+    /// Right arrow with Shift
+    ShiftRight,
 
     /// Insert key
     Ins,
@@ -113,6 +117,8 @@ impl fmt::Display for Key {
             Key::Right => write!(f, "\u{2192}"), //→
             Key::Up => write!(f, "\u{2191}"),    //↑
             Key::Down => write!(f, "\u{2193}"),  //↓
+            Key::ShiftLeft => write!(f, "<Shift+\u{2190}>"),
+            Key::ShiftRight => write!(f, "<Shift+\u{2192}>"),
             Key::Enter
             | Key::Tab
             | Key::Backspace
@@ -139,6 +145,14 @@ impl From<event::KeyEvent> for Key {
                 code: event::KeyCode::Backspace,
                 ..
             } => Key::Backspace,
+            event::KeyEvent {
+                code: event::KeyCode::Left,
+                modifiers: event::KeyModifiers::SHIFT,
+            } => Key::ShiftLeft,
+            event::KeyEvent {
+                code: event::KeyCode::Right,
+                modifiers: event::KeyModifiers::SHIFT,
+            } => Key::ShiftRight,
             event::KeyEvent {
                 code: event::KeyCode::Left,
                 ..
@@ -0,0 +1,94 @@
+use crate::database::ParamType;
+use crate::json_store::JsonStore;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+const HISTORY_FILE: &str = "history.json";
+const MAX_QUERIES: usize = 100;
+const MAX_PARAMS_PER_QUERY: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Param {
+    pub r#type: ParamType,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    query: String,
+    /// Bind parameter values the query was run with, most recent first
+    params: Vec<Vec<Param>>,
+}
+
+/// Queries run from the SQL editor, most recent first
+#[derive(Default)]
+pub struct History {
+    entries: JsonStore<Vec<Entry>>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            entries: JsonStore::new(HISTORY_FILE),
+        }
+    }
+
+    fn entries(&mut self) -> &mut Vec<Entry> {
+        self.entries.get()
+    }
+
+    /// Bind parameter values `query` was recently run with, most recent first.
+    pub fn params(&mut self, query: &str) -> Vec<Vec<Param>> {
+        self.entries()
+            .iter()
+            .find(|entry| entry.query == query)
+            .map_or(vec![], |entry| entry.params.clone())
+    }
+
+    pub fn push(&mut self, query: &str, params: Vec<Param>) -> Result<()> {
+        let entries = self.entries();
+        let mut entry = match entries.iter().position(|entry| entry.query == query) {
+            Some(index) => entries.remove(index),
+            None => Entry {
+                query: query.to_string(),
+                params: vec![],
+            },
+        };
+        if !params.is_empty() {
+            entry.params.retain(|p| *p != params);
+            entry.params.insert(0, params);
+            entry.params.truncate(MAX_PARAMS_PER_QUERY);
+        }
+        entries.insert(0, entry);
+        entries.truncate(MAX_QUERIES);
+        self.entries.save()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{History, Param, ParamType};
+
+    fn param(value: &str) -> Param {
+        Param {
+            r#type: ParamType::Integer,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_recent_params() {
+        let mut history = History::default();
+        assert!(history.params("select ?").is_empty());
+
+        history.push("select ?", vec![param("1")]).unwrap();
+        history.push("select ?", vec![param("2")]).unwrap();
+        history.push("select ?", vec![param("1")]).unwrap();
+        history.push("select 1", vec![]).unwrap();
+        assert_eq!(
+            history.params("select ?"),
+            vec![vec![param("1")], vec![param("2")]]
+        );
+        assert!(history.params("select 1").is_empty());
+    }
+}
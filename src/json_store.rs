@@ -0,0 +1,89 @@
+use crate::config::get_app_config_path;
+use anyhow::Result;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A value kept as JSON in a file of the app config directory across
+/// sessions. The file is only read once the value is needed, and one that
+/// can't be read is moved aside to `<file>.bak` rather than overwritten.
+#[derive(Default)]
+pub struct JsonStore<T> {
+    path: Option<PathBuf>,
+    value: Option<T>,
+}
+
+impl<T: Default + Serialize + DeserializeOwned> JsonStore<T> {
+    pub fn new(file_name: &str) -> Self {
+        Self {
+            path: get_app_config_path().ok().map(|path| path.join(file_name)),
+            value: None,
+        }
+    }
+
+    pub fn get(&mut self) -> &mut T {
+        if self.value.is_none() {
+            let value = match self.path.as_deref().map(read) {
+                Some(Ok(value)) => value,
+                Some(Err(_)) => {
+                    // Nothing is saved over a file that is still in the way.
+                    if let Some(path) = self.path.take() {
+                        let mut backup = path.clone().into_os_string();
+                        backup.push(".bak");
+                        if fs::rename(&path, backup).is_ok() {
+                            self.path = Some(path);
+                        }
+                    }
+                    T::default()
+                }
+                None => T::default(),
+            };
+            self.value = Some(value);
+        }
+        self.value.get_or_insert_with(T::default)
+    }
+
+    /// Writes the value to the file, if it has been read.
+    pub fn save(&self) -> Result<()> {
+        if let (Some(path), Some(value)) = (&self.path, &self.value) {
+            fs::write(path, serde_json::to_string(value)?)?;
+        }
+        Ok(())
+    }
+}
+
+fn read<T: Default + DeserializeOwned>(path: &Path) -> Result<T> {
+    match fs::read_to_string(path) {
+        Ok(json) => Ok(serde_json::from_str(&json)?),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::JsonStore;
+    use std::fs;
+
+    #[test]
+    fn test_unreadable_file_moved_aside() {
+        let dir = std::env::temp_dir().join(format!("gobang-json-store-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("store.json");
+        let backup = dir.join("store.json.bak");
+        fs::write(&path, "not json").unwrap();
+
+        let mut store = JsonStore::<Vec<String>> {
+            path: Some(path.clone()),
+            value: None,
+        };
+        assert!(store.get().is_empty());
+        store.get().push("a".to_string());
+        store.save().unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "not json");
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"["a"]"#);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,16 @@
 mod app;
+mod change_set;
 mod cli;
 mod clipboard;
+mod column_layouts;
 mod components;
 mod config;
 mod database;
 mod event;
+mod history;
+mod json_store;
+mod saved_tabs;
+mod sql;
 mod ui;
 mod version;
 
@@ -18,12 +24,18 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
-use std::io;
+use std::io::{self, Read};
 use tui::{backend::CrosstermBackend, Terminal};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let value = crate::cli::parse();
+    if let Some(cli::Command::Fmt { dialect }) = value.command {
+        let mut sql = String::new();
+        io::stdin().read_to_string(&mut sql)?;
+        println!("{}", sql::format(&sql, dialect));
+        return Ok(());
+    }
     let config = config::Config::new(&value.config)?;
 
     setup_terminal()?;
@@ -59,6 +71,7 @@ async fn main() -> anyhow::Result<()> {
 
     shutdown_terminal();
     terminal.show_cursor()?;
+    app.save_state()?;
 
     Ok(())
 }
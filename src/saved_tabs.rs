@@ -0,0 +1,45 @@
+use crate::json_store::JsonStore;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const SAVED_TABS_FILE: &str = "sql_editor_tabs.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedTab {
+    pub name: String,
+    pub query: String,
+}
+
+/// The SQL editor tabs of each connection
+#[derive(Default)]
+pub struct SavedTabs {
+    connections: JsonStore<HashMap<String, Vec<SavedTab>>>,
+}
+
+impl SavedTabs {
+    pub fn new() -> Self {
+        Self {
+            connections: JsonStore::new(SAVED_TABS_FILE),
+        }
+    }
+
+    fn connections(&mut self) -> &mut HashMap<String, Vec<SavedTab>> {
+        self.connections.get()
+    }
+
+    pub fn get(&mut self, connection: &str) -> Vec<SavedTab> {
+        self.connections()
+            .get(connection)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, connection: &str, tabs: Vec<SavedTab>) -> Result<()> {
+        if self.connections().get(connection) == Some(&tabs) {
+            return Ok(());
+        }
+        self.connections().insert(connection.to_string(), tabs);
+        self.connections.save()
+    }
+}
@@ -0,0 +1,803 @@
+use super::token::{tokenize, Token, TokenKind};
+use super::{quote, Dialect};
+
+/// A column as entered in the form to add one
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnDefinition {
+    pub name: String,
+    pub type_name: String,
+    pub nullable: bool,
+    /// An SQL expression, written as it goes after `DEFAULT`
+    pub default: Option<String>,
+}
+
+/// A change to the columns of a table
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnChange {
+    Add(ColumnDefinition),
+    Rename { column: String, new_name: String },
+    ChangeType { column: String, type_name: String },
+    Drop { column: String },
+}
+
+impl ColumnChange {
+    pub fn description(&self) -> String {
+        match self {
+            Self::Add(definition) => format!("Add column {}?", definition.name),
+            Self::Rename { column, new_name } => {
+                format!("Rename column {} to {}?", column, new_name)
+            }
+            Self::ChangeType { column, type_name } => {
+                format!("Change the type of {} to {}?", column, type_name)
+            }
+            Self::Drop { column } => format!("Drop column {}?", column),
+        }
+    }
+}
+
+/// The definition of a column as it goes in `CREATE TABLE` or `ADD COLUMN`
+pub fn column_definition(definition: &ColumnDefinition, dialect: Dialect) -> String {
+    let mut sql = format!(
+        "{} {}",
+        quote::identifier(&definition.name, dialect),
+        definition.type_name
+    );
+    if !definition.nullable {
+        sql.push_str(" NOT NULL");
+    }
+    if let Some(default) = &definition.default {
+        sql.push_str(" DEFAULT ");
+        sql.push_str(default);
+    }
+    sql
+}
+
+/// Makes `change` to `table`, a name quoted already, with one `ALTER TABLE`.
+/// `None` if the database can't: MySQL needs the whole definition of a
+/// column to change its type, and SQLite has to rebuild the table to change
+/// a type or drop a column.
+pub fn alter_column(table: &str, change: &ColumnChange, dialect: Dialect) -> Option<String> {
+    let identifier = |name: &str| quote::identifier(name, dialect);
+    Some(match (change, dialect) {
+        (ColumnChange::Add(definition), _) => format!(
+            "ALTER TABLE {} ADD COLUMN {}",
+            table,
+            column_definition(definition, dialect)
+        ),
+        (ColumnChange::Rename { column, new_name }, _) => format!(
+            "ALTER TABLE {} RENAME COLUMN {} TO {}",
+            table,
+            identifier(column),
+            identifier(new_name)
+        ),
+        (ColumnChange::ChangeType { column, type_name }, Dialect::Postgres) => format!(
+            "ALTER TABLE {} ALTER COLUMN {} TYPE {} USING {}::{}",
+            table,
+            identifier(column),
+            type_name,
+            identifier(column),
+            type_name
+        ),
+        (ColumnChange::Drop { column }, Dialect::MySql | Dialect::Postgres) => {
+            format!("ALTER TABLE {} DROP COLUMN {}", table, identifier(column))
+        }
+        _ => return None,
+    })
+}
+
+const TABLE_CONSTRAINTS: [&str; 5] = ["CONSTRAINT", "PRIMARY", "UNIQUE", "CHECK", "FOREIGN"];
+const COLUMN_CONSTRAINTS: [&str; 11] = [
+    "CONSTRAINT",
+    "PRIMARY",
+    "NOT",
+    "NULL",
+    "UNIQUE",
+    "CHECK",
+    "DEFAULT",
+    "COLLATE",
+    "REFERENCES",
+    "GENERATED",
+    "AS",
+];
+
+fn is_keyword(token: &Token, keywords: &[&str]) -> bool {
+    token.kind == TokenKind::Word
+        && keywords
+            .iter()
+            .any(|keyword| token.text.eq_ignore_ascii_case(keyword))
+}
+
+/// The name a token stands for, if it is an identifier
+fn identifier_name(token: &Token) -> Option<String> {
+    match token.kind {
+        TokenKind::Word => Some(token.text.to_string()),
+        TokenKind::QuotedIdentifier | TokenKind::String => {
+            let quote = &token.text[..1];
+            Some(token.text[1..token.text.len() - 1].replace(&quote.repeat(2), quote))
+        }
+        _ => None,
+    }
+}
+
+fn mentions(sql: &str, column: &str) -> bool {
+    tokenize(sql, Dialect::Sqlite).iter().any(
+        |token| matches!(identifier_name(token), Some(name) if name.eq_ignore_ascii_case(column)),
+    )
+}
+
+/// The column definitions and table constraints of a `CREATE TABLE`, and the
+/// text after them, e.g. `) WITHOUT ROWID`
+fn table_items(sql: &str) -> Option<(Vec<&str>, &str)> {
+    let tokens = tokenize(sql, Dialect::Sqlite);
+    let start = tokens
+        .iter()
+        .position(|token| token.kind == TokenKind::LeftParen)?;
+    let mut items = vec![];
+    let mut item_start = tokens[start].end();
+    let mut depth = 0;
+    for token in &tokens[start + 1..] {
+        match token.kind {
+            TokenKind::LeftParen => depth += 1,
+            TokenKind::RightParen if depth == 0 => {
+                items.push(&sql[item_start..token.offset]);
+                return Some((items, &sql[token.offset..]));
+            }
+            TokenKind::RightParen => depth -= 1,
+            TokenKind::Comma if depth == 0 => {
+                items.push(&sql[item_start..token.offset]);
+                item_start = token.end();
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+fn significant_tokens(sql: &str) -> Vec<Token<'_>> {
+    tokenize(sql, Dialect::Sqlite)
+        .into_iter()
+        .filter(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment))
+        .collect()
+}
+
+/// The name of the column an item of `CREATE TABLE` defines, if it isn't a
+/// table constraint
+fn defined_column(item: &str) -> Option<String> {
+    let tokens = significant_tokens(item);
+    let first = tokens.first()?;
+    if is_keyword(first, &TABLE_CONSTRAINTS) {
+        return None;
+    }
+    identifier_name(first)
+}
+
+/// Replaces the type in the definition of a column, keeping its constraints.
+fn with_type(item: &str, type_name: &str) -> String {
+    let tokens = significant_tokens(item);
+    let name_end = tokens[0].end();
+    let mut depth = 0;
+    let constraints = tokens[1..].iter().find(|token| {
+        match token.kind {
+            TokenKind::LeftParen => depth += 1,
+            TokenKind::RightParen => depth -= 1,
+            _ => (),
+        }
+        depth == 0 && is_keyword(token, &COLUMN_CONSTRAINTS)
+    });
+    match constraints {
+        Some(constraints) => format!(
+            "{} {} {}",
+            &item[..name_end],
+            type_name,
+            &item[constraints.offset..]
+        ),
+        None => format!(
+            "{} {}{}",
+            &item[..name_end],
+            type_name,
+            &item[item.trim_end().len()..]
+        ),
+    }
+}
+
+/// An index, trigger or view as read from SQLite's `sqlite_master`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaObject {
+    /// `index`, `trigger` or `view`
+    pub kind: String,
+    pub name: String,
+    /// The table an index or trigger is on, or the view itself
+    pub table: String,
+    pub sql: String,
+}
+
+/// Changes the type of a column or drops it by rebuilding the table, as
+/// SQLite can't alter them: a copy of `table` is created from `create_sql`,
+/// its `CREATE TABLE` statement, with the change made, the rows copied over,
+/// and the table replaced. Of `objects`, the indexes, triggers and views of
+/// the database in the order they were created, those on the table or using
+/// it are dropped first and created again at the end.
+pub fn rebuild_table(
+    table: &str,
+    create_sql: &str,
+    objects: &[SchemaObject],
+    change: &ColumnChange,
+) -> anyhow::Result<Vec<String>> {
+    let dependents = objects
+        .iter()
+        .filter(|object| object.table.eq_ignore_ascii_case(table) || mentions(&object.sql, table))
+        .collect::<Vec<&SchemaObject>>();
+    let (items, rest) = table_items(create_sql)
+        .ok_or_else(|| anyhow::anyhow!("can't read the definition of {}", table))?;
+    let columns = items
+        .iter()
+        .filter_map(|item| defined_column(item))
+        .collect::<Vec<String>>();
+    let target = match change {
+        ColumnChange::ChangeType { column, .. } | ColumnChange::Drop { column } => column,
+        _ => anyhow::bail!("{} can be altered without rebuilding it", table),
+    };
+    if !columns
+        .iter()
+        .any(|column| column.eq_ignore_ascii_case(target))
+    {
+        anyhow::bail!("{} has no column {}", table, target);
+    }
+
+    let mut new_items = vec![];
+    for item in &items {
+        let is_target =
+            matches!(defined_column(item), Some(column) if column.eq_ignore_ascii_case(target));
+        match change {
+            ColumnChange::ChangeType { type_name, .. } if is_target => {
+                new_items.push(with_type(item, type_name))
+            }
+            ColumnChange::Drop { .. } if is_target => (),
+            ColumnChange::Drop { column } if defined_column(item).is_none() => {
+                if mentions(item, column) {
+                    anyhow::bail!(
+                        "a constraint of {} uses {}, so it can't be dropped",
+                        table,
+                        column
+                    );
+                }
+                new_items.push(item.to_string());
+            }
+            _ => new_items.push(item.to_string()),
+        }
+    }
+    if let ColumnChange::Drop { column } = change {
+        if columns.len() == 1 {
+            anyhow::bail!("{} is the only column of {}", column, table);
+        }
+        if let Some(dependent) = dependents
+            .iter()
+            .find(|dependent| mentions(&dependent.sql, column))
+        {
+            anyhow::bail!("`{}` uses {}, drop it first", dependent.sql, column);
+        }
+    }
+
+    let copied = columns
+        .iter()
+        .filter(|column| {
+            !matches!(change, ColumnChange::Drop { column: dropped } if column.eq_ignore_ascii_case(dropped))
+        })
+        .map(|column| quote::identifier(column, Dialect::Sqlite))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let quoted = quote::identifier(table, Dialect::Sqlite);
+    let new_table = quote::identifier(&format!("{}_new", table), Dialect::Sqlite);
+    let mut statements = dependents
+        .iter()
+        .rev()
+        .map(|dependent| {
+            format!(
+                "DROP {} {}",
+                dependent.kind.to_uppercase(),
+                quote::identifier(&dependent.name, Dialect::Sqlite)
+            )
+        })
+        .collect::<Vec<String>>();
+    statements.extend(vec![
+        format!(
+            "CREATE TABLE {} ({}{}",
+            new_table,
+            new_items.join(","),
+            rest
+        ),
+        format!(
+            "INSERT INTO {} ({}) SELECT {} FROM {}",
+            new_table, copied, copied, quoted
+        ),
+        format!("DROP TABLE {}", quoted),
+        format!("ALTER TABLE {} RENAME TO {}", new_table, quoted),
+    ]);
+    statements.extend(dependents.iter().map(|dependent| dependent.sql.clone()));
+    Ok(statements)
+}
+
+/// An index as entered in the form to create one
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexDefinition {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+    /// One of `index_methods`, or the database's default if `None`
+    pub method: Option<String>,
+    /// Builds a Postgres index without locking out writes to the table
+    pub concurrently: bool,
+}
+
+/// The index methods to pick from, the database's default first
+pub fn index_methods(dialect: Dialect) -> &'static [&'static str] {
+    match dialect {
+        Dialect::Postgres => &["btree", "hash", "gin"],
+        Dialect::MySql => &["BTREE", "HASH"],
+        Dialect::Sqlite => &[],
+    }
+}
+
+/// Creates the index `definition` on `table`, a name quoted already.
+pub fn create_index(table: &str, definition: &IndexDefinition, dialect: Dialect) -> String {
+    let mut sql = format!(
+        "CREATE {}INDEX ",
+        if definition.unique { "UNIQUE " } else { "" }
+    );
+    if definition.concurrently && dialect == Dialect::Postgres {
+        sql.push_str("CONCURRENTLY ");
+    }
+    sql.push_str(&quote::identifier(&definition.name, dialect));
+    let method = definition
+        .method
+        .as_ref()
+        .filter(|_| dialect != Dialect::Sqlite);
+    if let (Some(method), Dialect::MySql) = (method, dialect) {
+        sql.push_str(&format!(" USING {}", method));
+    }
+    sql.push_str(&format!(" ON {}", table));
+    if let (Some(method), Dialect::Postgres) = (method, dialect) {
+        sql.push_str(&format!(" USING {}", method));
+    }
+    sql.push_str(&format!(
+        " ({})",
+        definition
+            .columns
+            .iter()
+            .map(|column| quote::identifier(column, dialect))
+            .collect::<Vec<String>>()
+            .join(", ")
+    ));
+    sql
+}
+
+/// Drops the index `name` of `table`, a name quoted already. Postgres looks
+/// the index up in the table's `schema`.
+pub fn drop_index(table: &str, schema: Option<&str>, name: &str, dialect: Dialect) -> String {
+    let index = quote::identifier(name, dialect);
+    match dialect {
+        Dialect::MySql => format!("DROP INDEX {} ON {}", index, table),
+        Dialect::Postgres => format!(
+            "DROP INDEX {}.{}",
+            quote::identifier(schema.unwrap_or("public"), dialect),
+            index
+        ),
+        Dialect::Sqlite => format!("DROP INDEX {}", index),
+    }
+}
+
+/// A column as entered in the form to create a table
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewColumn {
+    pub definition: ColumnDefinition,
+    pub primary_key: bool,
+    pub unique: bool,
+    /// The table, a name quoted already, and the column the column references
+    pub references: Option<(String, String)>,
+}
+
+/// The column types to pick from
+pub fn column_types(dialect: Dialect) -> &'static [&'static str] {
+    match dialect {
+        Dialect::Postgres => &[
+            "integer",
+            "bigint",
+            "serial",
+            "bigserial",
+            "smallint",
+            "numeric",
+            "real",
+            "double precision",
+            "boolean",
+            "text",
+            "varchar(255)",
+            "char(1)",
+            "date",
+            "time",
+            "timestamp",
+            "timestamptz",
+            "uuid",
+            "json",
+            "jsonb",
+            "bytea",
+        ],
+        Dialect::MySql => &[
+            "INT",
+            "BIGINT",
+            "SMALLINT",
+            "TINYINT",
+            "DECIMAL(10,2)",
+            "FLOAT",
+            "DOUBLE",
+            "BOOLEAN",
+            "VARCHAR(255)",
+            "CHAR(1)",
+            "TEXT",
+            "DATE",
+            "TIME",
+            "DATETIME",
+            "TIMESTAMP",
+            "JSON",
+            "BLOB",
+        ],
+        Dialect::Sqlite => &["INTEGER", "REAL", "TEXT", "BLOB", "NUMERIC"],
+    }
+}
+
+/// Creates `table`, a name quoted already, with `columns`. Several primary
+/// key columns make up one primary key.
+pub fn create_table(table: &str, columns: &[NewColumn], dialect: Dialect) -> String {
+    let identifier = |name: &str| quote::identifier(name, dialect);
+    let mut items = columns
+        .iter()
+        .map(|column| column_definition(&column.definition, dialect))
+        .collect::<Vec<String>>();
+    let primary_key = columns
+        .iter()
+        .filter(|column| column.primary_key)
+        .map(|column| identifier(&column.definition.name))
+        .collect::<Vec<String>>();
+    if !primary_key.is_empty() {
+        items.push(format!("PRIMARY KEY ({})", primary_key.join(", ")));
+    }
+    for column in columns.iter().filter(|column| column.unique) {
+        items.push(format!("UNIQUE ({})", identifier(&column.definition.name)));
+    }
+    for column in columns {
+        if let Some((table, referenced)) = &column.references {
+            items.push(format!(
+                "FOREIGN KEY ({}) REFERENCES {} ({})",
+                identifier(&column.definition.name),
+                table,
+                identifier(referenced)
+            ));
+        }
+    }
+    format!("CREATE TABLE {} (\n  {}\n)", table, items.join(",\n  "))
+}
+
+/// Renames `table`, a name quoted already, to `new_name`. MySQL is told the
+/// `database` of the table, so that it doesn't move to another one.
+pub fn rename_table(table: &str, database: &str, new_name: &str, dialect: Dialect) -> String {
+    let new_name = quote::identifier(new_name, dialect);
+    match dialect {
+        Dialect::MySql => format!(
+            "RENAME TABLE {} TO {}.{}",
+            table,
+            quote::identifier(database, dialect),
+            new_name
+        ),
+        Dialect::Postgres | Dialect::Sqlite => {
+            format!("ALTER TABLE {} RENAME TO {}", table, new_name)
+        }
+    }
+}
+
+/// Deletes all the rows of `table`, a name quoted already, resetting its
+/// counters. SQLite has no `TRUNCATE`, so it only deletes them.
+pub fn truncate_table(table: &str, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::MySql | Dialect::Postgres => format!("TRUNCATE TABLE {}", table),
+        Dialect::Sqlite => format!("DELETE FROM {}", table),
+    }
+}
+
+/// Creates `new_table` with the columns and constraints of `table`, both
+/// names quoted already, and copies the rows over if `with_data`. SQLite has
+/// no `CREATE TABLE ... LIKE`, so its copy is made from `create_sql`, the
+/// `CREATE TABLE` statement of `table`, and leaves out the indexes.
+pub fn duplicate_table(
+    table: &str,
+    new_table: &str,
+    create_sql: Option<&str>,
+    with_data: bool,
+    dialect: Dialect,
+) -> anyhow::Result<Vec<String>> {
+    let mut statements = vec![match dialect {
+        Dialect::MySql => format!("CREATE TABLE {} LIKE {}", new_table, table),
+        Dialect::Postgres => format!("CREATE TABLE {} (LIKE {} INCLUDING ALL)", new_table, table),
+        Dialect::Sqlite => {
+            let (items, rest) = create_sql
+                .and_then(table_items)
+                .ok_or_else(|| anyhow::anyhow!("can't read the definition of {}", table))?;
+            format!("CREATE TABLE {} ({}{}", new_table, items.join(","), rest)
+        }
+    }];
+    if with_data {
+        statements.push(format!("INSERT INTO {} SELECT * FROM {}", new_table, table));
+    }
+    Ok(statements)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        alter_column, create_index, create_table, drop_index, duplicate_table, rebuild_table,
+        rename_table, truncate_table, ColumnChange, ColumnDefinition, Dialect, IndexDefinition,
+        NewColumn, SchemaObject,
+    };
+
+    #[test]
+    fn test_alter_column() {
+        let add = ColumnChange::Add(ColumnDefinition {
+            name: "age".to_string(),
+            type_name: "INTEGER".to_string(),
+            nullable: false,
+            default: Some("0".to_string()),
+        });
+        assert_eq!(
+            alter_column("`db`.`users`", &add, Dialect::MySql).unwrap(),
+            "ALTER TABLE `db`.`users` ADD COLUMN `age` INTEGER NOT NULL DEFAULT 0"
+        );
+
+        let change_type = ColumnChange::ChangeType {
+            column: "age".to_string(),
+            type_name: "bigint".to_string(),
+        };
+        assert_eq!(
+            alter_column("\"public\".\"users\"", &change_type, Dialect::Postgres).unwrap(),
+            "ALTER TABLE \"public\".\"users\" ALTER COLUMN \"age\" TYPE bigint USING \"age\"::bigint"
+        );
+        assert_eq!(alter_column("`users`", &change_type, Dialect::MySql), None);
+        assert_eq!(
+            alter_column("\"users\"", &change_type, Dialect::Sqlite),
+            None
+        );
+
+        let rename = ColumnChange::Rename {
+            column: "age".to_string(),
+            new_name: "years".to_string(),
+        };
+        assert_eq!(
+            alter_column("\"users\"", &rename, Dialect::Sqlite).unwrap(),
+            "ALTER TABLE \"users\" RENAME COLUMN \"age\" TO \"years\""
+        );
+    }
+
+    const CREATE_USERS: &str = "CREATE TABLE users (\n  id INTEGER PRIMARY KEY,\n  \"name\" varchar(10) NOT NULL DEFAULT '',\n  age int,\n  CHECK (id > 0)\n) WITHOUT ROWID";
+
+    fn object(kind: &str, name: &str, table: &str, sql: &str) -> SchemaObject {
+        SchemaObject {
+            kind: kind.to_string(),
+            name: name.to_string(),
+            table: table.to_string(),
+            sql: sql.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rebuild_table() {
+        let statements = rebuild_table(
+            "users",
+            CREATE_USERS,
+            &[
+                object(
+                    "index",
+                    "users_age",
+                    "users",
+                    "CREATE INDEX users_age ON users (age)",
+                ),
+                object(
+                    "index",
+                    "posts_title",
+                    "posts",
+                    "CREATE INDEX posts_title ON posts (title)",
+                ),
+                object(
+                    "view",
+                    "adults",
+                    "adults",
+                    "CREATE VIEW adults AS SELECT * FROM users WHERE age >= 18",
+                ),
+                object(
+                    "trigger",
+                    "posts_author",
+                    "posts",
+                    "CREATE TRIGGER posts_author AFTER INSERT ON posts BEGIN UPDATE users SET age = age WHERE id = NEW.author; END",
+                ),
+            ],
+            &ColumnChange::ChangeType {
+                column: "name".to_string(),
+                type_name: "TEXT".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            statements,
+            vec![
+                "DROP TRIGGER \"posts_author\"",
+                "DROP VIEW \"adults\"",
+                "DROP INDEX \"users_age\"",
+                "CREATE TABLE \"users_new\" (\n  id INTEGER PRIMARY KEY,\n  \"name\" TEXT NOT NULL DEFAULT '',\n  age int,\n  CHECK (id > 0)\n) WITHOUT ROWID",
+                "INSERT INTO \"users_new\" (\"id\", \"name\", \"age\") SELECT \"id\", \"name\", \"age\" FROM \"users\"",
+                "DROP TABLE \"users\"",
+                "ALTER TABLE \"users_new\" RENAME TO \"users\"",
+                "CREATE INDEX users_age ON users (age)",
+                "CREATE VIEW adults AS SELECT * FROM users WHERE age >= 18",
+                "CREATE TRIGGER posts_author AFTER INSERT ON posts BEGIN UPDATE users SET age = age WHERE id = NEW.author; END",
+            ]
+        );
+
+        let statements = rebuild_table(
+            "users",
+            CREATE_USERS,
+            &[],
+            &ColumnChange::Drop {
+                column: "AGE".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            statements[0],
+            "CREATE TABLE \"users_new\" (\n  id INTEGER PRIMARY KEY,\n  \"name\" varchar(10) NOT NULL DEFAULT '',\n  CHECK (id > 0)\n) WITHOUT ROWID"
+        );
+        assert_eq!(
+            statements[1],
+            "INSERT INTO \"users_new\" (\"id\", \"name\") SELECT \"id\", \"name\" FROM \"users\""
+        );
+    }
+
+    #[test]
+    fn test_rebuild_table_refuses() {
+        let drop = |column: &str| ColumnChange::Drop {
+            column: column.to_string(),
+        };
+        assert!(rebuild_table("users", CREATE_USERS, &[], &drop("id")).is_err());
+        assert!(rebuild_table("users", CREATE_USERS, &[], &drop("email")).is_err());
+        assert!(rebuild_table(
+            "users",
+            CREATE_USERS,
+            &[object(
+                "index",
+                "users_age",
+                "users",
+                "CREATE INDEX users_age ON users (age)"
+            )],
+            &drop("age")
+        )
+        .is_err());
+        assert!(rebuild_table(
+            "users",
+            CREATE_USERS,
+            &[object(
+                "view",
+                "adults",
+                "adults",
+                "CREATE VIEW adults AS SELECT name FROM users WHERE age >= 18"
+            )],
+            &drop("age")
+        )
+        .is_err());
+        assert!(rebuild_table("t", "CREATE TABLE t (id int)", &[], &drop("id")).is_err());
+    }
+
+    #[test]
+    fn test_create_index() {
+        let mut index = IndexDefinition {
+            name: "users_name_age_idx".to_string(),
+            columns: vec!["name".to_string(), "age".to_string()],
+            unique: true,
+            method: Some("hash".to_string()),
+            concurrently: true,
+        };
+        assert_eq!(
+            create_index("\"public\".\"users\"", &index, Dialect::Postgres),
+            "CREATE UNIQUE INDEX CONCURRENTLY \"users_name_age_idx\" ON \"public\".\"users\" USING hash (\"name\", \"age\")"
+        );
+        assert_eq!(
+            create_index("`db`.`users`", &index, Dialect::MySql),
+            "CREATE UNIQUE INDEX `users_name_age_idx` USING hash ON `db`.`users` (`name`, `age`)"
+        );
+        index.unique = false;
+        assert_eq!(
+            create_index("\"users\"", &index, Dialect::Sqlite),
+            "CREATE INDEX \"users_name_age_idx\" ON \"users\" (\"name\", \"age\")"
+        );
+    }
+
+    #[test]
+    fn test_drop_index() {
+        assert_eq!(
+            drop_index("`db`.`users`", None, "users_age", Dialect::MySql),
+            "DROP INDEX `users_age` ON `db`.`users`"
+        );
+        assert_eq!(
+            drop_index("\"s\".\"users\"", Some("s"), "users_age", Dialect::Postgres),
+            "DROP INDEX \"s\".\"users_age\""
+        );
+    }
+
+    #[test]
+    fn test_create_table() {
+        let column = |name: &str, type_name: &str, nullable: bool| NewColumn {
+            definition: ColumnDefinition {
+                name: name.to_string(),
+                type_name: type_name.to_string(),
+                nullable,
+                default: None,
+            },
+            primary_key: false,
+            unique: false,
+            references: None,
+        };
+        let mut id = column("id", "serial", false);
+        id.primary_key = true;
+        let mut email = column("email", "text", false);
+        email.unique = true;
+        email.definition.default = Some("''".to_string());
+        let mut customer_id = column("customer_id", "integer", true);
+        customer_id.references = Some(("\"public\".\"customers\"".to_string(), "id".to_string()));
+        assert_eq!(
+            create_table(
+                "\"public\".\"orders\"",
+                &[id, email, customer_id],
+                Dialect::Postgres
+            ),
+            "CREATE TABLE \"public\".\"orders\" (\n  \"id\" serial NOT NULL,\n  \"email\" text NOT NULL DEFAULT '',\n  \"customer_id\" integer,\n  PRIMARY KEY (\"id\"),\n  UNIQUE (\"email\"),\n  FOREIGN KEY (\"customer_id\") REFERENCES \"public\".\"customers\" (\"id\")\n)"
+        );
+    }
+
+    #[test]
+    fn test_table_actions() {
+        assert_eq!(
+            rename_table("`db`.`users`", "db", "people", Dialect::MySql),
+            "RENAME TABLE `db`.`users` TO `db`.`people`"
+        );
+        assert_eq!(
+            rename_table("\"public\".\"users\"", "db", "people", Dialect::Postgres),
+            "ALTER TABLE \"public\".\"users\" RENAME TO \"people\""
+        );
+        assert_eq!(
+            truncate_table("\"users\"", Dialect::Sqlite),
+            "DELETE FROM \"users\""
+        );
+        assert_eq!(
+            duplicate_table(
+                "`db`.`users`",
+                "`db`.`users_copy`",
+                None,
+                true,
+                Dialect::MySql
+            )
+            .unwrap(),
+            vec![
+                "CREATE TABLE `db`.`users_copy` LIKE `db`.`users`",
+                "INSERT INTO `db`.`users_copy` SELECT * FROM `db`.`users`",
+            ]
+        );
+        assert_eq!(
+            duplicate_table(
+                "\"users\"",
+                "\"users_copy\"",
+                Some(CREATE_USERS),
+                false,
+                Dialect::Sqlite
+            )
+            .unwrap(),
+            vec![
+                "CREATE TABLE \"users_copy\" (\n  id INTEGER PRIMARY KEY,\n  \"name\" varchar(10) NOT NULL DEFAULT '',\n  age int,\n  CHECK (id > 0)\n) WITHOUT ROWID"
+            ]
+        );
+        assert!(duplicate_table("\"users\"", "\"copy\"", None, false, Dialect::Sqlite).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use super::{quote, Dialect};
+use crate::database::ParamType;
+
+/// A column of a row with its type and value, `None` standing for NULL
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnValue {
+    pub column: String,
+    pub type_name: String,
+    pub value: Option<String>,
+}
+
+impl ColumnValue {
+    pub fn new(column: &str, type_name: &str, value: Option<&str>) -> Self {
+        Self {
+            column: column.to_string(),
+            type_name: type_name.to_string(),
+            value: value.map(str::to_string),
+        }
+    }
+
+    fn literal(&self, dialect: Dialect) -> String {
+        match &self.value {
+            Some(value) => quote::literal(value, &self.type_name, dialect),
+            None => "NULL".to_string(),
+        }
+    }
+}
+
+/// Matches the row whose key columns have the given values.
+fn key_condition(key: &[ColumnValue], dialect: Dialect) -> String {
+    key.iter()
+        .map(|value| match value.value {
+            Some(_) => format!(
+                "{} = {}",
+                quote::identifier(&value.column, dialect),
+                value.literal(dialect)
+            ),
+            None => format!("{} IS NULL", quote::identifier(&value.column, dialect)),
+        })
+        .collect::<Vec<String>>()
+        .join(" AND ")
+}
+
+/// Sets columns of the row identified by `key` in `table`, a name quoted
+/// already.
+pub fn update(table: &str, set: &[ColumnValue], key: &[ColumnValue], dialect: Dialect) -> String {
+    format!(
+        "UPDATE {} SET {} WHERE {}",
+        table,
+        set.iter()
+            .map(|value| format!(
+                "{} = {}",
+                quote::identifier(&value.column, dialect),
+                value.literal(dialect)
+            ))
+            .collect::<Vec<String>>()
+            .join(", "),
+        key_condition(key, dialect)
+    )
+}
+
+/// Deletes the rows identified by `keys` from `table`, a name quoted
+/// already.
+pub fn delete(table: &str, keys: &[Vec<ColumnValue>], dialect: Dialect) -> String {
+    let condition = match keys {
+        [key] => key_condition(key, dialect),
+        // Rows with a single key column are listed, which reads better.
+        _ if keys.iter().all(|key| key.len() == 1) => format!(
+            "{} IN ({})",
+            quote::identifier(&keys[0][0].column, dialect),
+            keys.iter()
+                .map(|key| key[0].literal(dialect))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        _ => keys
+            .iter()
+            .map(|key| format!("({})", key_condition(key, dialect)))
+            .collect::<Vec<String>>()
+            .join(" OR "),
+    };
+    format!("DELETE FROM {} WHERE {}", table, condition)
+}
+
+/// Selects the row MySQL just inserted into `table`, a name quoted already,
+/// by its key: the values in `key` and, if one of the key columns was left
+/// for it to generate, the value of that `generated` column.
+pub fn select_inserted(table: &str, key: &[ColumnValue], generated: Option<&str>) -> String {
+    let mut conditions = vec![];
+    if !key.is_empty() {
+        conditions.push(key_condition(key, Dialect::MySql));
+    }
+    if let Some(column) = generated {
+        conditions.push(format!(
+            "{} = LAST_INSERT_ID()",
+            quote::identifier(column, Dialect::MySql)
+        ));
+    }
+    format!("SELECT * FROM {} WHERE {}", table, conditions.join(" AND "))
+}
+
+/// Inserts a row into `table`, a name quoted already, with one bind
+/// parameter per column given by name and type. Postgres gets the types as
+/// casts, since it won't convert parameters to them by itself. Where the
+/// database supports it, the row is returned as stored.
+pub fn insert(table: &str, columns: &[(&str, &str)], dialect: Dialect) -> String {
+    let statement = if columns.is_empty() {
+        match dialect {
+            Dialect::MySql => format!("INSERT INTO {} () VALUES ()", table),
+            Dialect::Postgres | Dialect::Sqlite => {
+                format!("INSERT INTO {} DEFAULT VALUES", table)
+            }
+        }
+    } else {
+        format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            columns
+                .iter()
+                .map(|(column, _)| quote::identifier(column, dialect))
+                .collect::<Vec<String>>()
+                .join(", "),
+            columns
+                .iter()
+                .enumerate()
+                .map(|(i, (_, type_name))| match dialect {
+                    Dialect::MySql | Dialect::Sqlite => "?".to_string(),
+                    Dialect::Postgres => format!("${}::{}", i + 1, type_name),
+                })
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    };
+    match dialect {
+        Dialect::MySql => statement,
+        Dialect::Postgres | Dialect::Sqlite => format!("{} RETURNING *", statement),
+    }
+}
+
+/// How a value typed in for a column of `type_name` is checked and sent.
+/// Decimals are sent as text so that no precision is lost.
+pub fn param_type(type_name: &str) -> ParamType {
+    match quote::base_type(type_name).as_str() {
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "int2" | "int4"
+        | "int8" | "serial" | "smallserial" | "bigserial" => ParamType::Integer,
+        "float" | "float4" | "float8" | "double" | "real" => ParamType::Float,
+        "bool" | "boolean" => ParamType::Boolean,
+        _ => ParamType::Text,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        delete, insert, param_type, select_inserted, update, ColumnValue, Dialect, ParamType,
+    };
+
+    #[test]
+    fn test_update() {
+        assert_eq!(
+            update(
+                "`db`.`users`",
+                &[ColumnValue::new("name", "varchar(255)", Some("O'Brien"))],
+                &[ColumnValue::new("id", "int(11)", Some("42"))],
+                Dialect::MySql
+            ),
+            "UPDATE `db`.`users` SET `name` = 'O''Brien' WHERE `id` = 42"
+        );
+        assert_eq!(
+            update(
+                "\"public\".\"users\"",
+                &[ColumnValue::new("name", "text", None)],
+                &[
+                    ColumnValue::new("org", "text", Some("acme")),
+                    ColumnValue::new("id", "integer", Some("1")),
+                ],
+                Dialect::Postgres
+            ),
+            "UPDATE \"public\".\"users\" SET \"name\" = NULL WHERE \"org\" = 'acme' AND \"id\" = 1"
+        );
+        assert_eq!(
+            update(
+                "\"users\"",
+                &[ColumnValue::new("name", "TEXT", Some(""))],
+                &[ColumnValue::new("id", "INTEGER", Some("1"))],
+                Dialect::Sqlite
+            ),
+            "UPDATE \"users\" SET \"name\" = '' WHERE \"id\" = 1"
+        );
+    }
+
+    #[test]
+    fn test_delete() {
+        let id = |id| vec![ColumnValue::new("id", "integer", Some(id))];
+        assert_eq!(
+            delete("\"users\"", &[id("1")], Dialect::Sqlite),
+            "DELETE FROM \"users\" WHERE \"id\" = 1"
+        );
+        assert_eq!(
+            delete("\"users\"", &[id("1"), id("2")], Dialect::Sqlite),
+            "DELETE FROM \"users\" WHERE \"id\" IN (1, 2)"
+        );
+        let key = |org, id| {
+            vec![
+                ColumnValue::new("org", "text", Some(org)),
+                ColumnValue::new("id", "integer", Some(id)),
+            ]
+        };
+        assert_eq!(
+            delete(
+                "`db`.`users`",
+                &[key("a", "1"), key("b", "2")],
+                Dialect::MySql
+            ),
+            "DELETE FROM `db`.`users` WHERE (`org` = 'a' AND `id` = 1) OR (`org` = 'b' AND `id` = 2)"
+        );
+    }
+
+    #[test]
+    fn test_select_inserted() {
+        assert_eq!(
+            select_inserted(
+                "`db`.`orders`",
+                &[ColumnValue::new("shop", "int(11)", Some("3"))],
+                Some("id")
+            ),
+            "SELECT * FROM `db`.`orders` WHERE `shop` = 3 AND `id` = LAST_INSERT_ID()"
+        );
+        assert_eq!(
+            select_inserted(
+                "`db`.`tags`",
+                &[ColumnValue::new("name", "varchar(20)", Some("new"))],
+                None
+            ),
+            "SELECT * FROM `db`.`tags` WHERE `name` = 'new'"
+        );
+    }
+
+    #[test]
+    fn test_insert() {
+        let columns = [("name", "varchar(255)"), ("born", "date")];
+        assert_eq!(
+            insert("`db`.`users`", &columns, Dialect::MySql),
+            "INSERT INTO `db`.`users` (`name`, `born`) VALUES (?, ?)"
+        );
+        assert_eq!(
+            insert("\"public\".\"users\"", &columns, Dialect::Postgres),
+            "INSERT INTO \"public\".\"users\" (\"name\", \"born\") VALUES ($1::varchar(255), $2::date) RETURNING *"
+        );
+        assert_eq!(
+            insert("\"users\"", &[], Dialect::Sqlite),
+            "INSERT INTO \"users\" DEFAULT VALUES RETURNING *"
+        );
+        assert_eq!(
+            insert("`db`.`users`", &[], Dialect::MySql),
+            "INSERT INTO `db`.`users` () VALUES ()"
+        );
+    }
+
+    #[test]
+    fn test_param_type() {
+        assert_eq!(param_type("int(11) unsigned"), ParamType::Integer);
+        assert_eq!(param_type("double precision"), ParamType::Float);
+        assert_eq!(param_type("numeric(10,2)"), ParamType::Text);
+        assert_eq!(param_type("BOOLEAN"), ParamType::Boolean);
+        assert_eq!(param_type("character varying"), ParamType::Text);
+    }
+}
@@ -0,0 +1,554 @@
+use super::token::{tokenize, Token, TokenKind};
+use super::Dialect;
+
+const INDENT: &str = "  ";
+
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "ALL",
+    "ALTER",
+    "ANALYZE",
+    "AND",
+    "ANY",
+    "AS",
+    "ASC",
+    "BEGIN",
+    "BETWEEN",
+    "BY",
+    "CASCADE",
+    "CASE",
+    "CHECK",
+    "COLUMN",
+    "COMMIT",
+    "CONFLICT",
+    "CONSTRAINT",
+    "CREATE",
+    "CROSS",
+    "CURRENT_DATE",
+    "CURRENT_TIMESTAMP",
+    "DATABASE",
+    "DEFAULT",
+    "DELETE",
+    "DESC",
+    "DISTINCT",
+    "DO",
+    "DROP",
+    "DUPLICATE",
+    "ELSE",
+    "END",
+    "EXCEPT",
+    "EXISTS",
+    "EXPLAIN",
+    "FALSE",
+    "FETCH",
+    "FIRST",
+    "FOR",
+    "FOREIGN",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "IF",
+    "ILIKE",
+    "IN",
+    "INDEX",
+    "INNER",
+    "INSERT",
+    "INTERSECT",
+    "INTERVAL",
+    "INTO",
+    "IS",
+    "JOIN",
+    "KEY",
+    "LATERAL",
+    "LIKE",
+    "LIMIT",
+    "NATURAL",
+    "NOT",
+    "NULL",
+    "NULLS",
+    "OFFSET",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OVER",
+    "PARTITION",
+    "PRIMARY",
+    "RECURSIVE",
+    "REFERENCES",
+    "RETURNING",
+    "ROLLBACK",
+    "ROWS",
+    "SCHEMA",
+    "SELECT",
+    "SET",
+    "STRAIGHT_JOIN",
+    "TABLE",
+    "THEN",
+    "TO",
+    "TRUE",
+    "TRUNCATE",
+    "UNION",
+    "UNIQUE",
+    "UPDATE",
+    "USING",
+    "VALUES",
+    "VIEW",
+    "WHEN",
+    "WHERE",
+    "WINDOW",
+    "WITH",
+];
+
+const FUNCTIONS: &[&str] = &[
+    "AVG", "CAST", "COALESCE", "COUNT", "LEFT", "MAX", "MIN", "NULLIF", "REPLACE", "RIGHT", "SUM",
+];
+
+/// Keywords followed by a table name, which in turn may be followed by a
+/// column list
+const TABLE_KEYWORDS: &[&str] = &["INTO", "ON", "REFERENCES", "TABLE"];
+
+const JOIN_MODIFIERS: &[&str] = &[
+    "CROSS", "FULL", "INNER", "LEFT", "NATURAL", "OUTER", "RIGHT",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum ClauseKind {
+    /// The clause keyword sits on its own line and its items are indented
+    /// below it, one per line.
+    Block,
+    /// The clause starts a new line but its contents follow on the same line.
+    Line,
+    /// A join, indented like the items of the `FROM` it belongs to.
+    Join,
+}
+
+struct Clause {
+    kind: ClauseKind,
+    /// Number of word tokens the clause keyword consists of
+    len: usize,
+    /// Whether `AND` and `OR` start new lines inside the clause
+    conditions: bool,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Block {
+    indent: usize,
+    in_clause: bool,
+    conditions: bool,
+}
+
+/// Pretty-prints `sql`: keywords are upper-cased, the items of `SELECT`,
+/// `FROM`, `WHERE` etc. are put on their own indented lines, subqueries are
+/// indented one level deeper and statements are separated by a blank line.
+/// Strings, quoted identifiers and comments are kept as they are.
+pub fn format(sql: &str, dialect: Dialect) -> String {
+    let tokens: Vec<Token> = tokenize(sql, dialect)
+        .into_iter()
+        .filter(|t| t.kind != TokenKind::Whitespace)
+        .collect();
+    let mut formatter = Formatter::default();
+    let mut i = 0;
+    while i < tokens.len() {
+        i += formatter.token(&tokens, i);
+    }
+    formatter.out.trim_end().to_string()
+}
+
+#[derive(Default)]
+struct Formatter {
+    out: String,
+    block: Block,
+    /// One entry per open parenthesis: the enclosing block for a subquery, or
+    /// `None` for an inline list like function arguments.
+    parens: Vec<Option<Block>>,
+    /// Set after a clause keyword so its first item starts a new line
+    item_pending: bool,
+    /// Set inside `BETWEEN ... AND`, whose `AND` must stay on the same line
+    between: bool,
+    /// Set after a line comment, which the next token must not follow on the
+    /// same line
+    line_break_pending: bool,
+    prev: Option<(TokenKind, String)>,
+    before_prev: Option<String>,
+}
+
+impl Formatter {
+    /// Writes the token at `index` and returns how many tokens were consumed.
+    fn token(&mut self, tokens: &[Token], index: usize) -> usize {
+        let token = tokens[index];
+        let inline = matches!(self.parens.last(), Some(None));
+
+        if self.line_break_pending {
+            self.line_break_pending = false;
+            self.newline(self.block.indent + self.block.in_clause as usize);
+        }
+
+        match token.kind {
+            TokenKind::Word if !inline && !prev_is(tokens, index, TokenKind::Dot) => {
+                if let Some(clause) = clause_at(tokens, index, &self.block) {
+                    return self.clause(tokens, index, clause);
+                }
+            }
+            TokenKind::Semicolon => {
+                self.write(token.kind, ";");
+                if tokens[index + 1..]
+                    .iter()
+                    .any(|t| t.kind != TokenKind::Comment)
+                {
+                    self.out.push_str("\n\n");
+                }
+                self.block = Block::default();
+                self.parens.clear();
+                self.item_pending = false;
+                self.between = false;
+                self.prev = None;
+                self.before_prev = None;
+                return 1;
+            }
+            TokenKind::RightParen => {
+                if let Some(Some(outer)) = self.parens.pop() {
+                    self.block = outer;
+                    self.item_pending = false;
+                    self.newline(self.block.indent + self.block.in_clause as usize);
+                }
+                self.write(token.kind, ")");
+                return 1;
+            }
+            _ => (),
+        }
+
+        if self.item_pending {
+            self.item_pending = false;
+            self.newline(self.block.indent + 1);
+        }
+
+        match token.kind {
+            TokenKind::Word => {
+                let upper = token.text.to_uppercase();
+                let is_keyword = !prev_is(tokens, index, TokenKind::Dot)
+                    && !prev_is(tokens, index, TokenKind::Cast)
+                    && !next_is(tokens, index, TokenKind::Dot)
+                    && (KEYWORDS.contains(&upper.as_str())
+                        || (FUNCTIONS.contains(&upper.as_str())
+                            && next_is(tokens, index, TokenKind::LeftParen)));
+                match upper.as_str() {
+                    "BETWEEN" if is_keyword => self.between = true,
+                    "AND" | "OR" if is_keyword && !inline && self.block.conditions => {
+                        if self.between && upper == "AND" {
+                            self.between = false;
+                        } else {
+                            self.newline(self.block.indent + 1);
+                        }
+                    }
+                    _ => (),
+                }
+                if is_keyword {
+                    self.write(token.kind, &upper);
+                } else {
+                    self.write(token.kind, token.text);
+                }
+            }
+            TokenKind::LeftParen => {
+                self.write(token.kind, "(");
+                let subquery = matches!(
+                    tokens.get(index + 1),
+                    Some(t) if t.kind == TokenKind::Word
+                        && matches!(t.text.to_uppercase().as_str(), "SELECT" | "WITH")
+                );
+                if subquery {
+                    self.parens.push(Some(self.block));
+                    self.block = Block {
+                        indent: self.block.indent + self.block.in_clause as usize + 1,
+                        ..Block::default()
+                    };
+                    self.newline(self.block.indent);
+                } else {
+                    self.parens.push(None);
+                }
+            }
+            TokenKind::Comma => {
+                self.write(token.kind, ",");
+                if !inline && self.block.in_clause {
+                    self.newline(self.block.indent + 1);
+                }
+            }
+            TokenKind::Comment if token.text.starts_with("/*") => {
+                self.write(token.kind, token.text)
+            }
+            TokenKind::Comment => {
+                self.write(token.kind, token.text);
+                self.line_break_pending = true;
+            }
+            _ => self.write(token.kind, token.text),
+        }
+        1
+    }
+
+    fn clause(&mut self, tokens: &[Token], index: usize, clause: Clause) -> usize {
+        let (indent, in_clause) = match clause.kind {
+            ClauseKind::Join => (self.block.indent + 1, true),
+            _ => (self.block.indent, clause.kind == ClauseKind::Block),
+        };
+        self.item_pending = false;
+        self.between = false;
+        self.newline(indent);
+
+        let mut consumed = 0;
+        let mut words = 0;
+        while words < clause.len {
+            let token = tokens[index + consumed];
+            if token.kind == TokenKind::Word {
+                self.write(token.kind, &token.text.to_uppercase());
+                words += 1;
+            } else {
+                self.write(token.kind, token.text);
+                if token.kind == TokenKind::Comment && !token.text.starts_with("/*") {
+                    self.line_break_pending = true;
+                }
+            }
+            consumed += 1;
+        }
+
+        if clause.kind != ClauseKind::Join {
+            self.block.in_clause = in_clause;
+            self.block.conditions = clause.conditions;
+        }
+        self.item_pending = clause.kind == ClauseKind::Block;
+        consumed
+    }
+
+    fn newline(&mut self, indent: usize) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+        if self.out.ends_with('\n') {
+            self.out.push_str(&INDENT.repeat(indent));
+        }
+        self.prev = None;
+        self.before_prev = None;
+    }
+
+    fn write(&mut self, kind: TokenKind, text: &str) {
+        if let Some(prev) = &self.prev {
+            if needs_space(prev, self.before_prev.as_deref(), kind) {
+                self.out.push(' ');
+            }
+        }
+        self.out.push_str(text);
+        self.before_prev = self.prev.take().map(|(_, text)| text);
+        self.prev = Some((kind, text.to_string()));
+    }
+}
+
+fn needs_space(prev: &(TokenKind, String), before_prev: Option<&str>, kind: TokenKind) -> bool {
+    match (prev.0, kind) {
+        (TokenKind::LeftParen, _) | (TokenKind::Dot, _) | (TokenKind::Cast, _) => false,
+        (_, TokenKind::RightParen)
+        | (_, TokenKind::Comma)
+        | (_, TokenKind::Semicolon)
+        | (_, TokenKind::Dot)
+        | (_, TokenKind::Cast) => false,
+        // Tell a function call from a keyword like `IN (` or a column list
+        // like `INSERT INTO t (`
+        (TokenKind::Word, TokenKind::LeftParen) => {
+            let upper = prev.1.to_uppercase();
+            if FUNCTIONS.contains(&upper.as_str()) {
+                false
+            } else if KEYWORDS.contains(&upper.as_str()) {
+                true
+            } else {
+                matches!(before_prev, Some(w) if TABLE_KEYWORDS.contains(&w))
+            }
+        }
+        (TokenKind::QuotedIdentifier, TokenKind::LeftParen) => {
+            matches!(before_prev, Some(w) if TABLE_KEYWORDS.contains(&w))
+        }
+        _ => true,
+    }
+}
+
+fn prev_is(tokens: &[Token], index: usize, kind: TokenKind) -> bool {
+    index > 0 && tokens[index - 1].kind == kind
+}
+
+fn next_is(tokens: &[Token], index: usize, kind: TokenKind) -> bool {
+    matches!(tokens.get(index + 1), Some(t) if t.kind == kind)
+}
+
+/// Upper-cased words starting at `index`, skipping comments.
+fn words_at(tokens: &[Token], index: usize, count: usize) -> Vec<String> {
+    tokens[index..]
+        .iter()
+        .filter(|t| t.kind != TokenKind::Comment)
+        .take_while(|t| t.kind == TokenKind::Word)
+        .take(count)
+        .map(|t| t.text.to_uppercase())
+        .collect()
+}
+
+fn clause_at(tokens: &[Token], index: usize, block: &Block) -> Option<Clause> {
+    let words = words_at(tokens, index, 4);
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let clause = |kind, len, conditions| {
+        Some(Clause {
+            kind,
+            len,
+            conditions,
+        })
+    };
+    match words.as_slice() {
+        ["SELECT", "DISTINCT", ..] | ["SELECT", "ALL", ..] => clause(ClauseKind::Block, 2, false),
+        ["SELECT", ..] | ["FROM", ..] | ["SET", ..] | ["VALUES", ..] | ["RETURNING", ..] => {
+            clause(ClauseKind::Block, 1, false)
+        }
+        ["WITH", "RECURSIVE", ..] => clause(ClauseKind::Block, 2, false),
+        ["WITH", ..] if !block.in_clause => clause(ClauseKind::Block, 1, false),
+        ["WHERE", ..] | ["HAVING", ..] => clause(ClauseKind::Block, 1, true),
+        ["GROUP", "BY", ..] | ["ORDER", "BY", ..] => clause(ClauseKind::Block, 2, false),
+        ["LIMIT", ..] | ["OFFSET", ..] | ["EXCEPT", ..] | ["INTERSECT", ..] => {
+            clause(ClauseKind::Line, 1, false)
+        }
+        ["UNION", "ALL", ..] => clause(ClauseKind::Line, 2, false),
+        ["UNION", ..] => clause(ClauseKind::Line, 1, false),
+        ["INSERT", "INTO", ..] | ["REPLACE", "INTO", ..] | ["DELETE", "FROM", ..] => {
+            clause(ClauseKind::Line, 2, false)
+        }
+        ["ON", "CONFLICT", ..] => clause(ClauseKind::Line, 2, false),
+        ["ON", "DUPLICATE", "KEY", "UPDATE"] => clause(ClauseKind::Block, 4, false),
+        ["UPDATE", ..] if !block.in_clause => clause(ClauseKind::Line, 1, false),
+        ["JOIN", ..] | ["STRAIGHT_JOIN", ..] if block.in_clause => {
+            clause(ClauseKind::Join, 1, false)
+        }
+        [first, ..] if block.in_clause && JOIN_MODIFIERS.contains(first) => {
+            let len = words
+                .iter()
+                .position(|w| *w == "JOIN")
+                .filter(|&len| words[..len].iter().all(|w| JOIN_MODIFIERS.contains(w)))?;
+            clause(ClauseKind::Join, len + 1, false)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format, Dialect};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_format_select() {
+        assert_eq!(
+            format(
+                "select id, count(*) as n from users u left outer join orders o on o.user_id = u.id where u.age between 1 and 2 and u.name like 'a%' or u.id in (1, 2) group by id order by n desc limit 10",
+                Dialect::Sqlite
+            ),
+            r#"SELECT
+  id,
+  COUNT(*) AS n
+FROM
+  users u
+  LEFT OUTER JOIN orders o ON o.user_id = u.id
+WHERE
+  u.age BETWEEN 1 AND 2
+  AND u.name LIKE 'a%'
+  OR u.id IN (1, 2)
+GROUP BY
+  id
+ORDER BY
+  n DESC
+LIMIT 10"#
+        );
+    }
+
+    #[test]
+    fn test_format_subquery() {
+        assert_eq!(
+            format(
+                "select * from t where id in (select user_id from orders where total > 10)",
+                Dialect::Sqlite
+            ),
+            r#"SELECT
+  *
+FROM
+  t
+WHERE
+  id IN (
+    SELECT
+      user_id
+    FROM
+      orders
+    WHERE
+      total > 10
+  )"#
+        );
+    }
+
+    #[test]
+    fn test_format_statements() {
+        assert_eq!(
+            format(
+                "insert into t (a, b) values (1, 'x;y'), (2, null); update t set a = 1, b = 2 where id = 3; delete from t",
+                Dialect::Sqlite
+            ),
+            r#"INSERT INTO t (a, b)
+VALUES
+  (1, 'x;y'),
+  (2, NULL);
+
+UPDATE t
+SET
+  a = 1,
+  b = 2
+WHERE
+  id = 3;
+
+DELETE FROM t"#
+        );
+    }
+
+    #[test]
+    fn test_format_mysql() {
+        assert_eq!(
+            format(
+                "select `from`, `t`.`select` from `t` # trailing\nwhere `id` = \"a\"",
+                Dialect::MySql
+            ),
+            r#"SELECT
+  `from`,
+  `t`.`select`
+FROM
+  `t` # trailing
+WHERE
+  `id` = "a""#
+        );
+    }
+
+    #[test]
+    fn test_format_postgres() {
+        assert_eq!(
+            format(
+                "select id::text, $$ select 1; $$ from t where created_at > now() - '1 day'::interval and t.order = $1",
+                Dialect::Postgres
+            ),
+            r#"SELECT
+  id::text,
+  $$ select 1; $$
+FROM
+  t
+WHERE
+  created_at > now() - '1 day'::interval
+  AND t.order = $1"#
+        );
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        let sql = "select a, b from t join u using (id) where a = 1 and (b = 2 or c = 3)";
+        let formatted = format(sql, Dialect::Postgres);
+        assert_eq!(format(&formatted, Dialect::Postgres), formatted);
+    }
+}
@@ -0,0 +1,50 @@
+pub mod ddl;
+pub mod dml;
+pub mod format;
+pub mod params;
+pub mod quote;
+pub mod statement;
+pub mod token;
+
+pub use format::format;
+pub use params::placeholders;
+pub use statement::statement_at;
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The SQL flavour spoken by a connection. It decides how the editor buffer
+/// is tokenized, e.g. whether backticks quote identifiers or `$$` starts a
+/// string.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    MySql,
+    Postgres,
+    Sqlite,
+}
+
+impl FromStr for Dialect {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mysql" => Ok(Self::MySql),
+            "postgres" | "postgresql" => Ok(Self::Postgres),
+            "sqlite" => Ok(Self::Sqlite),
+            _ => Err(anyhow::anyhow!(
+                "unknown dialect `{}`, expected one of mysql, postgres or sqlite",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MySql => write!(f, "mysql"),
+            Self::Postgres => write!(f, "postgres"),
+            Self::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::token::{tokenize, TokenKind};
+use super::Dialect;
+
+/// A query whose placeholders have been rewritten to the dialect's native
+/// positional ones.
+#[derive(Debug, PartialEq)]
+pub struct Placeholders {
+    pub query: String,
+    /// Distinct parameters in the order they first appear, e.g. `$1`, `?2` or
+    /// `:name`. These are what the user is prompted for.
+    pub names: Vec<String>,
+    /// For each bind position of `query`, the index into `names` whose value
+    /// goes there
+    pub binds: Vec<usize>,
+}
+
+impl Placeholders {
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// Finds the placeholders in `sql`. Named ones (`:name`) are not understood
+/// by all databases, so they are replaced with `$n` in Postgres and with `?`
+/// elsewhere; every `?` is a parameter of its own. Postgres parameters are
+/// numbered again in the order they appear, so that a query using only `$2`
+/// isn't asked for an unused `$1`, whose type Postgres can't tell.
+pub fn placeholders(sql: &str, dialect: Dialect) -> Placeholders {
+    let tokens = tokenize(sql, dialect);
+    let mut names: Vec<String> = vec![];
+
+    let mut query = String::with_capacity(sql.len());
+    let mut binds = vec![];
+    let mut question_marks = 0;
+    for token in tokens {
+        if token.kind != TokenKind::Placeholder {
+            query.push_str(token.text);
+            continue;
+        }
+        let name = if token.text == "?" {
+            question_marks += 1;
+            format!("?{}", question_marks)
+        } else {
+            token.text.to_string()
+        };
+        let index = names.iter().position(|n| *n == name).unwrap_or_else(|| {
+            names.push(name.clone());
+            names.len() - 1
+        });
+        match dialect {
+            Dialect::Postgres => {
+                query.push_str(&format!("${}", index + 1));
+            }
+            Dialect::MySql | Dialect::Sqlite => {
+                query.push('?');
+                binds.push(index);
+            }
+        }
+    }
+    if dialect == Dialect::Postgres {
+        binds = (0..names.len()).collect();
+    }
+
+    Placeholders {
+        query,
+        names,
+        binds,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{placeholders, Dialect, Placeholders};
+
+    #[test]
+    fn test_postgres_placeholders() {
+        assert_eq!(
+            placeholders(
+                "select * from t where a = :a and b = $2 and c = :a and d = ':a'",
+                Dialect::Postgres
+            ),
+            Placeholders {
+                query: "select * from t where a = $1 and b = $2 and c = $1 and d = ':a'"
+                    .to_string(),
+                names: vec![":a".to_string(), "$2".to_string()],
+                binds: vec![0, 1],
+            }
+        );
+        assert_eq!(
+            placeholders("select * from t where b = $2 and a = $2", Dialect::Postgres),
+            Placeholders {
+                query: "select * from t where b = $1 and a = $1".to_string(),
+                names: vec!["$2".to_string()],
+                binds: vec![0],
+            }
+        );
+    }
+
+    #[test]
+    fn test_mysql_placeholders() {
+        assert_eq!(
+            placeholders(
+                "select * from t where a = ? and b = :b and c = ? and d = :b",
+                Dialect::MySql
+            ),
+            Placeholders {
+                query: "select * from t where a = ? and b = ? and c = ? and d = ?".to_string(),
+                names: vec!["?1".to_string(), ":b".to_string(), "?2".to_string()],
+                binds: vec![0, 1, 2, 1],
+            }
+        );
+    }
+
+    #[test]
+    fn test_no_placeholders() {
+        let sql = "select '?', `:a`, a := 1 from t -- ?";
+        assert!(placeholders(sql, Dialect::MySql).is_empty());
+        assert_eq!(placeholders(sql, Dialect::MySql).query, sql);
+    }
+}
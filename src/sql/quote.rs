@@ -0,0 +1,106 @@
+use super::Dialect;
+
+/// Column types whose values are written as bare numbers
+const NUMERIC_TYPES: &[&str] = &[
+    "tinyint",
+    "smallint",
+    "mediumint",
+    "int",
+    "integer",
+    "bigint",
+    "int2",
+    "int4",
+    "int8",
+    "serial",
+    "smallserial",
+    "bigserial",
+    "decimal",
+    "numeric",
+    "dec",
+    "fixed",
+    "float",
+    "float4",
+    "float8",
+    "double",
+    "real",
+];
+
+/// Quotes a table or column name so that any name can be used.
+pub fn identifier(name: &str, dialect: Dialect) -> String {
+    match dialect {
+        Dialect::MySql => format!("`{}`", name.replace('`', "``")),
+        Dialect::Postgres | Dialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+    }
+}
+
+/// Quotes `value` as a string literal. MySQL treats backslashes in strings
+/// as escapes, so they are escaped too.
+pub fn string(value: &str, dialect: Dialect) -> String {
+    let value = value.replace('\'', "''");
+    match dialect {
+        Dialect::MySql => format!("'{}'", value.replace('\\', "\\\\")),
+        Dialect::Postgres | Dialect::Sqlite => format!("'{}'", value),
+    }
+}
+
+/// The name of `type_name` without its size or modifiers, lowercased, e.g.
+/// `int` for `INT(11) unsigned`.
+pub fn base_type(type_name: &str) -> String {
+    type_name
+        .split(|c: char| c == '(' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Whether `type_name`, as reported for a column, holds numbers, e.g.
+/// `int(11) unsigned` or `numeric(10,2)`.
+pub fn is_numeric_type(type_name: &str) -> bool {
+    NUMERIC_TYPES.contains(&base_type(type_name).as_str())
+}
+
+/// Writes `value` of a column of `type_name` as a literal: bare if it is a
+/// number in a numeric column, quoted otherwise. The database converts
+/// quoted literals to the column's type when comparing.
+pub fn literal(value: &str, type_name: &str, dialect: Dialect) -> String {
+    let is_number = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'))
+        && value.parse::<f64>().is_ok();
+    if is_number && is_numeric_type(type_name) {
+        value.to_string()
+    } else {
+        string(value, dialect)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{identifier, is_numeric_type, literal, Dialect};
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier("a`b", Dialect::MySql), "`a``b`");
+        assert_eq!(identifier("a\"b", Dialect::Postgres), "\"a\"\"b\"");
+        assert_eq!(identifier("user", Dialect::Sqlite), "\"user\"");
+    }
+
+    #[test]
+    fn test_literal() {
+        assert!(is_numeric_type("int(11) unsigned"));
+        assert!(is_numeric_type("NUMERIC(10,2)"));
+        assert!(!is_numeric_type("interval"));
+        assert!(!is_numeric_type("point"));
+
+        assert_eq!(literal("42", "integer", Dialect::Postgres), "42");
+        assert_eq!(literal("-1.5e3", "double", Dialect::MySql), "-1.5e3");
+        assert_eq!(literal("42", "text", Dialect::Postgres), "'42'");
+        assert_eq!(literal("NaN", "float8", Dialect::Postgres), "'NaN'");
+        assert_eq!(literal("it's", "text", Dialect::Sqlite), "'it''s'");
+        assert_eq!(
+            literal("C:\\it's", "varchar(10)", Dialect::MySql),
+            "'C:\\\\it''s'"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use super::token::{tokenize, TokenKind};
+use super::Dialect;
+use std::ops::Range;
+
+/// The statement the cursor at byte offset `cursor` is in, split on `;`
+/// outside of strings, quoted identifiers and comments. The range covers the
+/// statement from its first to its last token, without the terminating `;`.
+/// A cursor right after a `;` or in the blank space following the last
+/// statement belongs to the statement before it.
+pub fn statement_at(sql: &str, dialect: Dialect, cursor: usize) -> Option<Range<usize>> {
+    let mut found = None;
+    for (segment, statement) in segments(sql, dialect) {
+        if statement.is_some() {
+            found = statement;
+        }
+        if cursor <= segment.end {
+            break;
+        }
+    }
+    found
+}
+
+/// Splits `sql` into segments ending after each `;`, together with the range
+/// of the statement in each segment if it is not blank.
+fn segments(sql: &str, dialect: Dialect) -> Vec<(Range<usize>, Option<Range<usize>>)> {
+    let mut segments = vec![];
+    let mut start = 0;
+    let mut statement: Option<Range<usize>> = None;
+    for token in tokenize(sql, dialect) {
+        match token.kind {
+            TokenKind::Whitespace | TokenKind::Comment => (),
+            TokenKind::Semicolon => {
+                segments.push((start..token.end(), statement.take()));
+                start = token.end();
+            }
+            _ => {
+                let range = statement.map_or(token.offset, |r| r.start)..token.end();
+                statement = Some(range);
+            }
+        }
+    }
+    if start < sql.len() || segments.is_empty() {
+        segments.push((start..sql.len(), statement));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod test {
+    use super::{segments, statement_at, Dialect};
+
+    #[test]
+    fn test_segments() {
+        let sql = "select ';'; -- a;b\n select 2 ;\n\n;select \"x;y\"";
+        assert_eq!(
+            segments(sql, Dialect::Postgres)
+                .into_iter()
+                .filter_map(|(_, r)| r.map(|r| &sql[r]))
+                .collect::<Vec<_>>(),
+            vec!["select ';'", "select 2", "select \"x;y\""]
+        );
+    }
+
+    #[test]
+    fn test_statement_at() {
+        let sql = "select 1;\nselect 2;\n\n";
+        let at = |cursor| statement_at(sql, Dialect::MySql, cursor).map(|r| &sql[r]);
+        assert_eq!(at(0), Some("select 1"));
+        assert_eq!(at(9), Some("select 1"));
+        assert_eq!(at(10), Some("select 2"));
+        assert_eq!(at(sql.len()), Some("select 2"));
+        assert_eq!(statement_at("  ", Dialect::MySql, 1), None);
+    }
+}
@@ -0,0 +1,293 @@
+use super::Dialect;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    Whitespace,
+    /// `-- ...`, `# ...` (MySQL) or `/* ... */`
+    Comment,
+    /// A keyword or an unquoted identifier
+    Word,
+    /// `"name"`, or `` `name` `` in MySQL and SQLite
+    QuotedIdentifier,
+    /// `'...'`, `E'...'`, `$tag$...$tag$` (Postgres) or `"..."` (MySQL)
+    String,
+    Number,
+    /// `$1` (Postgres), `?` (MySQL and SQLite) or `:name`
+    Placeholder,
+    /// `::` (Postgres)
+    Cast,
+    Operator,
+    LeftParen,
+    RightParen,
+    Comma,
+    Dot,
+    Semicolon,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// Byte offset of the token in the tokenized string
+    pub offset: usize,
+}
+
+impl<'a> Token<'a> {
+    pub fn end(&self) -> usize {
+        self.offset + self.text.len()
+    }
+}
+
+const OPERATORS: [&str; 12] = [
+    "->>", "<=>", "->", "<=", ">=", "<>", "!=", "||", "&&", ":=", "<<", ">>",
+];
+
+/// Splits `sql` into tokens. Every byte of the input ends up in exactly one
+/// token, so the original text can be rebuilt by concatenating them; an
+/// unterminated string or comment simply runs to the end of the input.
+///
+/// `:name` is no placeholder within an array subscript such as `a[lo:hi]`,
+/// nor right after a word, number or `)`.
+pub fn tokenize(sql: &str, dialect: Dialect) -> Vec<Token<'_>> {
+    let mut tokens: Vec<Token<'_>> = vec![];
+    let mut offset = 0;
+    let mut subscripts = 0usize;
+    while offset < sql.len() {
+        let rest = &sql[offset..];
+        let placeholder = subscripts == 0
+            && !matches!(
+                tokens.last(),
+                Some(token) if matches!(
+                    token.kind,
+                    TokenKind::Word
+                        | TokenKind::QuotedIdentifier
+                        | TokenKind::Number
+                        | TokenKind::RightParen
+                )
+            );
+        let (kind, len) = next_token(rest, dialect, placeholder);
+        let text = &rest[..len];
+        match (kind, text) {
+            (TokenKind::Operator, "[") => subscripts += 1,
+            (TokenKind::Operator, "]") => subscripts = subscripts.saturating_sub(1),
+            _ => {}
+        }
+        tokens.push(Token { kind, text, offset });
+        offset += len;
+    }
+    tokens
+}
+
+fn next_token(rest: &str, dialect: Dialect, placeholder: bool) -> (TokenKind, usize) {
+    let c = rest.chars().next().unwrap();
+    let next = rest[c.len_utf8()..].chars().next();
+
+    if c.is_whitespace() {
+        return (TokenKind::Whitespace, take_while(rest, char::is_whitespace));
+    }
+    if rest.starts_with("--") || (c == '#' && dialect == Dialect::MySql) {
+        return (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()));
+    }
+    if let Some(body) = rest.strip_prefix("/*") {
+        return (
+            TokenKind::Comment,
+            body.find("*/").map_or(rest.len(), |i| i + 4),
+        );
+    }
+    match c {
+        '\'' => (
+            TokenKind::String,
+            quoted_len(rest, '\'', dialect == Dialect::MySql),
+        ),
+        'e' | 'E' if next == Some('\'') && dialect == Dialect::Postgres => {
+            (TokenKind::String, 1 + quoted_len(&rest[1..], '\'', true))
+        }
+        '"' if dialect == Dialect::MySql => (TokenKind::String, quoted_len(rest, '"', true)),
+        '"' => (TokenKind::QuotedIdentifier, quoted_len(rest, '"', false)),
+        '`' if dialect != Dialect::Postgres => {
+            (TokenKind::QuotedIdentifier, quoted_len(rest, '`', false))
+        }
+        '$' if dialect == Dialect::Postgres => {
+            if matches!(next, Some(c) if c.is_ascii_digit()) {
+                (
+                    TokenKind::Placeholder,
+                    1 + take_while(&rest[1..], |c| c.is_ascii_digit()),
+                )
+            } else if let Some(len) = dollar_quoted_len(rest) {
+                (TokenKind::String, len)
+            } else {
+                (TokenKind::Operator, 1)
+            }
+        }
+        '?' if dialect != Dialect::Postgres => (TokenKind::Placeholder, 1),
+        '(' => (TokenKind::LeftParen, 1),
+        ')' => (TokenKind::RightParen, 1),
+        ',' => (TokenKind::Comma, 1),
+        ';' => (TokenKind::Semicolon, 1),
+        '.' if !matches!(next, Some(c) if c.is_ascii_digit()) => (TokenKind::Dot, 1),
+        ':' if next == Some(':') && dialect == Dialect::Postgres => (TokenKind::Cast, 2),
+        ':' if placeholder && matches!(next, Some(c) if c.is_alphabetic() || c == '_') => (
+            TokenKind::Placeholder,
+            1 + take_while(&rest[1..], |c| c.is_alphanumeric() || c == '_'),
+        ),
+        c if c.is_ascii_digit() || c == '.' => (TokenKind::Number, number_len(rest)),
+        c if is_word_char(c) => (TokenKind::Word, take_while(rest, is_word_char)),
+        _ => (
+            TokenKind::Operator,
+            OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .map_or(c.len_utf8(), |op| op.len()),
+        ),
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+fn take_while(s: &str, f: impl Fn(char) -> bool) -> usize {
+    s.find(|c| !f(c)).unwrap_or(s.len())
+}
+
+/// Length of a string quoted with `quote`, where a doubled quote is an
+/// escaped one and, if `backslash` is set, so is a backslash-escaped one.
+fn quoted_len(s: &str, quote: char, backslash: bool) -> usize {
+    let mut chars = s.char_indices().skip(1);
+    while let Some((i, c)) = chars.next() {
+        if backslash && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            if s[i + 1..].starts_with(quote) {
+                chars.next();
+            } else {
+                return i + 1;
+            }
+        }
+    }
+    s.len()
+}
+
+fn dollar_quoted_len(s: &str) -> Option<usize> {
+    let tag_len = 1 + take_while(&s[1..], |c| c.is_alphanumeric() || c == '_');
+    if !s[tag_len..].starts_with('$') {
+        return None;
+    }
+    let tag = &s[..=tag_len];
+    Some(
+        s[tag.len()..]
+            .find(tag)
+            .map_or(s.len(), |i| tag.len() + i + tag.len()),
+    )
+}
+
+fn number_len(s: &str) -> usize {
+    let mut len = take_while(s, |c| c.is_ascii_digit() || c == '.');
+    let rest = &s[len..];
+    if rest.starts_with(['e', 'E']) {
+        let sign = rest[1..].starts_with(['+', '-']) as usize;
+        let digits = take_while(&rest[1 + sign..], |c| c.is_ascii_digit());
+        if digits > 0 {
+            len += 1 + sign + digits;
+        }
+    }
+    len
+}
+
+#[cfg(test)]
+mod test {
+    use super::{tokenize, Dialect, TokenKind};
+
+    fn kinds(sql: &str, dialect: Dialect) -> Vec<(TokenKind, &str)> {
+        tokenize(sql, dialect)
+            .into_iter()
+            .filter(|t| t.kind != TokenKind::Whitespace)
+            .map(|t| (t.kind, t.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_round_trip() {
+        let sql = "select `a`, 'it''s' -- note\nfrom t where x >= 1.5e3;";
+        let text: String = tokenize(sql, Dialect::MySql)
+            .iter()
+            .map(|t| t.text)
+            .collect();
+        assert_eq!(text, sql);
+    }
+
+    #[test]
+    fn test_tokenize_mysql() {
+        assert_eq!(
+            kinds("select `order`, \"a\\\"b\" # comment", Dialect::MySql),
+            vec![
+                (TokenKind::Word, "select"),
+                (TokenKind::QuotedIdentifier, "`order`"),
+                (TokenKind::Comma, ","),
+                (TokenKind::String, "\"a\\\"b\""),
+                (TokenKind::Comment, "# comment"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_postgres() {
+        assert_eq!(
+            kinds(
+                "select $1::int, $fn$ a ; 'b' $fn$, E'\\'' from t where :id",
+                Dialect::Postgres
+            ),
+            vec![
+                (TokenKind::Word, "select"),
+                (TokenKind::Placeholder, "$1"),
+                (TokenKind::Cast, "::"),
+                (TokenKind::Word, "int"),
+                (TokenKind::Comma, ","),
+                (TokenKind::String, "$fn$ a ; 'b' $fn$"),
+                (TokenKind::Comma, ","),
+                (TokenKind::String, "E'\\''"),
+                (TokenKind::Word, "from"),
+                (TokenKind::Word, "t"),
+                (TokenKind::Word, "where"),
+                (TokenKind::Placeholder, ":id"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_array_slice() {
+        assert_eq!(
+            kinds(
+                "select a[1:n], a[lo:hi], b[:n] from t where x = :n",
+                Dialect::Postgres
+            )
+            .into_iter()
+            .filter(|(kind, _)| *kind == TokenKind::Placeholder)
+            .collect::<Vec<_>>(),
+            vec![(TokenKind::Placeholder, ":n")]
+        );
+        assert_eq!(
+            kinds("a[i:j]", Dialect::Postgres),
+            vec![
+                (TokenKind::Word, "a"),
+                (TokenKind::Operator, "["),
+                (TokenKind::Word, "i"),
+                (TokenKind::Operator, ":"),
+                (TokenKind::Word, "j"),
+                (TokenKind::Operator, "]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_unterminated() {
+        assert_eq!(
+            kinds("select 'abc", Dialect::Sqlite),
+            vec![(TokenKind::Word, "select"), (TokenKind::String, "'abc")]
+        );
+        assert_eq!(
+            kinds("/* abc", Dialect::Sqlite),
+            vec![(TokenKind::Comment, "/* abc")]
+        );
+    }
+}